@@ -0,0 +1,171 @@
+use serde::Deserialize;
+use std::path::PathBuf;
+
+/// Beep pitch, the traditional broadcast censor tone
+const BEEP_HZ: f32 = 1000.0;
+
+/// Beep level, about -12 dBFS
+const BEEP_AMPLITUDE: f32 = 0.25;
+
+/// Ramp at each edge of a redacted range, so cuts don't click
+const FADE_MS: f32 = 5.0;
+
+/// How a redacted range sounds in the copy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RedactionSound {
+    #[default]
+    Silence,
+    Beep,
+}
+
+/// A span of the recording to redact, in the same units as transcript
+/// segments - from frontend
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RedactionRange {
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
+/// Write a copy of a WAV recording with the given ranges silenced or beeped
+///
+/// The copy keeps the original's sample rate, channels and sample format, and
+/// the original is left untouched. Ranges may overlap or run past the end of
+/// the recording.
+///
+/// # Arguments
+/// * `input_path` - WAV recording to redact
+/// * `output_path` - Where to write the redacted copy
+/// * `ranges` - Spans to redact, e.g. from segments flagged by PII redaction
+/// * `sound` - Silence (default) or a 1 kHz beep
+///
+/// # Returns
+/// * `Ok(String)` - Path of the redacted copy
+/// * `Err(String)` - Error message if the recording can't be read or written
+#[tauri::command]
+pub async fn redact_audio(
+    input_path: String,
+    output_path: String,
+    ranges: Vec<RedactionRange>,
+    sound: Option<RedactionSound>,
+) -> Result<String, String> {
+    if PathBuf::from(&input_path) == PathBuf::from(&output_path) {
+        return Err("The redacted copy can't overwrite the original recording".to_string());
+    }
+
+    tokio::task::spawn_blocking(move || {
+        let mut reader = hound::WavReader::open(&input_path)
+            .map_err(|e| format!("Failed to open {} as WAV: {}", input_path, e))?;
+        let spec = reader.spec();
+        let mut samples = read_samples(&mut reader, spec)?;
+
+        redact_samples(&mut samples, spec, &ranges, sound.unwrap_or_default());
+        write_samples(&output_path, spec, &samples)?;
+
+        println!(
+            "[Audio Redaction] Redacted {} range(s) of {} into {}",
+            ranges.len(),
+            input_path,
+            output_path
+        );
+        Ok(output_path)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Interleaved samples scaled to [-1.0, 1.0]
+fn read_samples<R: std::io::Read>(
+    reader: &mut hound::WavReader<R>,
+    spec: hound::WavSpec,
+) -> Result<Vec<f32>, String> {
+    let to_error = |e: hound::Error| format!("Failed to read WAV samples: {}", e);
+    match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .map_err(to_error),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|s| s as f32 / scale))
+                .collect::<Result<_, _>>()
+                .map_err(to_error)
+        }
+    }
+}
+
+fn write_samples(path: &str, spec: hound::WavSpec, samples: &[f32]) -> Result<(), String> {
+    let to_error = |e: hound::Error| format!("Failed to write {}: {}", path, e);
+    let mut writer = hound::WavWriter::create(path, spec).map_err(to_error)?;
+    match spec.sample_format {
+        hound::SampleFormat::Float => {
+            for &sample in samples {
+                writer.write_sample(sample).map_err(to_error)?;
+            }
+        }
+        hound::SampleFormat::Int => {
+            let max = ((1i64 << (spec.bits_per_sample - 1)) - 1) as f32;
+            for &sample in samples {
+                let value = (sample.clamp(-1.0, 1.0) * max).round() as i32;
+                writer.write_sample(value).map_err(to_error)?;
+            }
+        }
+    }
+    writer.finalize().map_err(to_error)
+}
+
+/// Replace each range of interleaved samples with silence or a beep, fading
+/// the original out and back in at the edges
+fn redact_samples(
+    samples: &mut [f32],
+    spec: hound::WavSpec,
+    ranges: &[RedactionRange],
+    sound: RedactionSound,
+) {
+    let channels = spec.channels.max(1) as usize;
+    let frames = samples.len() / channels;
+    let rate = spec.sample_rate as f32;
+    let to_frame = |ms: i64| ((ms.max(0) as f32 / 1000.0 * rate) as usize).min(frames);
+    let fade = ((FADE_MS / 1000.0 * rate) as usize).max(1);
+
+    // How much of the original to keep at each frame: 0 inside a range
+    let mut keep = vec![1.0f32; frames];
+    for range in ranges {
+        let (start, end) = (to_frame(range.start_ms), to_frame(range.end_ms));
+        if start >= end {
+            continue;
+        }
+        let edges = start.saturating_sub(fade)..(end + fade).min(frames);
+        for (frame, level) in keep.iter_mut().enumerate().take(edges.end).skip(edges.start) {
+            let distance = if frame < start {
+                start - frame
+            } else if frame >= end {
+                frame + 1 - end
+            } else {
+                0
+            };
+            if distance < fade {
+                *level = level.min(distance as f32 / fade as f32);
+            }
+        }
+    }
+
+    for (frame, &level) in keep.iter().enumerate() {
+        if level >= 1.0 {
+            continue;
+        }
+        let beep = match sound {
+            RedactionSound::Silence => 0.0,
+            RedactionSound::Beep => {
+                let phase = 2.0 * std::f32::consts::PI * BEEP_HZ * frame as f32 / rate;
+                BEEP_AMPLITUDE * phase.sin()
+            }
+        };
+        for sample in &mut samples[frame * channels..(frame + 1) * channels] {
+            *sample = *sample * level + beep * (1.0 - level);
+        }
+    }
+}
@@ -4,6 +4,10 @@ use serde::{Deserialize, Serialize};
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
+use crate::graceful_shutdown::{own_process_group, track_process_tree};
+#[cfg(target_os = "windows")]
+use crate::graceful_shutdown::CREATE_NEW_PROCESS_GROUP;
+
 // Windows process creation flag to prevent console window from appearing
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
@@ -132,12 +136,16 @@ pub async fn spawn_command(command: String) -> Result<u32, String> {
 
     #[cfg(target_os = "windows")]
     {
-        cmd.creation_flags(CREATE_NO_WINDOW);
+        // Its own process group lets send_sigint stop it with Ctrl+Break
+        cmd.creation_flags(CREATE_NO_WINDOW | CREATE_NEW_PROCESS_GROUP);
         println!("[Rust] spawn_command: Windows - using CREATE_NO_WINDOW flag");
     }
 
+    own_process_group(&mut cmd);
+
     match cmd.spawn() {
         Ok(child) => {
+            track_process_tree(&child);
             let pid = child.id();
             println!("[Rust] spawn_command: spawned process with PID={}", pid);
             Ok(pid)
@@ -1,4 +1,7 @@
 use serde::Serialize;
+use std::process::{Child, Command};
+use std::time::Duration;
+use sysinfo::{ProcessesToUpdate, System};
 
 #[derive(Debug, Serialize)]
 pub struct SignalResult {
@@ -6,10 +9,48 @@ pub struct SignalResult {
     message: String,
 }
 
+impl SignalResult {
+    pub fn succeeded(&self) -> bool {
+        self.success
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+/// How long `send_sigint` gives a Windows process to exit after CTRL_BREAK
+/// before terminating it
+#[cfg(windows)]
+const TERMINATE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long each graceful stage of `shutdown_process` waits by default
+const DEFAULT_STAGE_TIMEOUT_MS: u64 = 5000;
+
+/// Creation flag that makes a Windows child lead its own process group, so
+/// `interrupt` can send CTRL_BREAK to it and its children alone
+#[cfg(windows)]
+pub const CREATE_NEW_PROCESS_GROUP: u32 = 0x00000200;
+
 /// Send a SIGINT signal to a process by PID.
 /// This is equivalent to Ctrl+C and allows graceful shutdown.
+///
+/// On Windows the process gets CTRL_BREAK instead, and is terminated if it
+/// hasn't exited after `TERMINATE_TIMEOUT`. That holds even when CTRL_BREAK
+/// couldn't be delivered, which is when a process is most likely to hang on.
 #[tauri::command]
 pub fn send_sigint(pid: u32) -> SignalResult {
+    let result = interrupt(pid);
+    #[cfg(windows)]
+    platform::terminate_after(pid, TERMINATE_TIMEOUT);
+    result
+}
+
+/// Ask a process to stop the way Ctrl+C would, without waiting for it
+///
+/// SIGINT on Unix. On Windows, CTRL_BREAK sent to the process group `pid`
+/// leads, which needs the process spawned with `CREATE_NEW_PROCESS_GROUP`.
+pub fn interrupt(pid: u32) -> SignalResult {
     #[cfg(unix)]
     {
         use nix::sys::signal::{kill, Signal};
@@ -31,23 +72,401 @@ pub fn send_sigint(pid: u32) -> SignalResult {
     
     #[cfg(windows)]
     {
-        // Windows: Use Ctrl+C event for console processes
-        use windows_sys::Win32::System::Console::{GenerateConsoleCtrlEvent, CTRL_C_EVENT};
-        
+        match platform::send_ctrl_break(pid) {
+            Ok(()) => SignalResult {
+                success: true,
+                message: format!("Ctrl+Break event sent to process {}", pid),
+            },
+            Err(err) => SignalResult {
+                success: false,
+                message: format!("Failed to send Ctrl+Break to process {}: {}", pid, err),
+            },
+        }
+    }
+}
+
+/// The step of `shutdown_process` that stopped the process - sent to frontend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShutdownStage {
+    /// It had already exited
+    NotRunning,
+    /// It exited after SIGINT, or Ctrl+Break on Windows
+    Interrupted,
+    /// It exited after SIGTERM (Unix only)
+    #[cfg_attr(windows, allow(dead_code))]
+    Terminated,
+    /// It had to be killed
+    Killed,
+}
+
+/// Stop a process, escalating until it exits: SIGINT, then SIGTERM, then
+/// SIGKILL on Unix, and Ctrl+Break, then termination on Windows
+///
+/// Each graceful stage gets `timeout_ms` (default 5 seconds) to take effect,
+/// so servers get a chance to flush their state before being killed.
+#[tauri::command]
+pub async fn shutdown_process(pid: u32, timeout_ms: Option<u64>) -> Result<ShutdownStage, String> {
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_STAGE_TIMEOUT_MS));
+    tokio::task::spawn_blocking(move || platform::shutdown(pid, timeout))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Put a command's process in its own process group on Unix, so
+/// `kill_process_tree` can signal the group without reaching the app
+pub fn own_process_group(command: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(windows)]
+    let _ = command;
+}
+
+/// Keep track of a spawned child's process tree for `kill_process_tree`
+///
+/// On Windows the child goes into a job object, which also holds every
+/// process it starts from then on. Unix uses the child's process group, set
+/// up by `own_process_group` before spawning.
+pub fn track_process_tree(child: &Child) {
+    #[cfg(windows)]
+    platform::track(child);
+    #[cfg(unix)]
+    let _ = child;
+}
+
+/// Stop a process and every process it started, such as a server's workers,
+/// which otherwise outlive it
+///
+/// The tree is asked to stop (SIGTERM, or Ctrl+Break on Windows) and given
+/// `timeout_ms` (default 5 seconds) before whatever is left is killed. The
+/// process group or job object covers children that were orphaned, and a
+/// walk of the process table covers those that left it.
+#[tauri::command]
+pub async fn kill_process_tree(pid: u32, timeout_ms: Option<u64>) -> Result<(), String> {
+    let timeout = Duration::from_millis(timeout_ms.unwrap_or(DEFAULT_STAGE_TIMEOUT_MS));
+    tokio::task::spawn_blocking(move || stop_process_tree(pid, timeout))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Blocking core of `kill_process_tree`, giving the tree `timeout` to exit
+/// before killing it
+pub fn stop_process_tree(pid: u32, timeout: Duration) -> Result<(), String> {
+    // Taken before anything exits, while parents still link the tree
+    let descendants = descendants(pid);
+    platform::kill_tree(pid, &descendants, timeout)
+}
+
+/// Every process descended from `pid`
+fn descendants(pid: u32) -> Vec<u32> {
+    let mut system = System::new();
+    system.refresh_processes(ProcessesToUpdate::All, true);
+    let mut tree = vec![pid];
+    let mut next = 0;
+    while next < tree.len() {
+        let parent = tree[next];
+        let children: Vec<u32> = system
+            .processes()
+            .iter()
+            .filter(|(_, process)| process.parent().map(|parent| parent.as_u32()) == Some(parent))
+            .map(|(child, _)| child.as_u32())
+            .filter(|child| !tree.contains(child))
+            .collect();
+        tree.extend(children);
+        next += 1;
+    }
+    tree.split_off(1)
+}
+
+#[cfg(unix)]
+mod platform {
+    use super::ShutdownStage;
+    use nix::errno::Errno;
+    use nix::sys::signal::{kill, killpg, Signal};
+    use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+    use nix::unistd::{getpgid, Pid};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// SIGKILL can't be ignored, but the process still takes a moment to go
+    const KILL_TIMEOUT: Duration = Duration::from_secs(2);
+
+    pub fn shutdown(pid: u32, timeout: Duration) -> Result<ShutdownStage, String> {
+        let pid = Pid::from_raw(pid as i32);
+        if !is_alive(pid) {
+            return Ok(ShutdownStage::NotRunning);
+        }
+        let graceful = [
+            (Signal::SIGINT, ShutdownStage::Interrupted),
+            (Signal::SIGTERM, ShutdownStage::Terminated),
+        ];
+        for (signal, stage) in graceful {
+            if kill(pid, signal).is_ok() && wait_for_exit(pid, timeout) {
+                return Ok(stage);
+            }
+        }
+
+        kill(pid, Signal::SIGKILL).map_err(|e| format!("Failed to kill process {}: {}", pid, e))?;
+        if wait_for_exit(pid, KILL_TIMEOUT) {
+            Ok(ShutdownStage::Killed)
+        } else {
+            Err(format!("Process {} is still running after SIGKILL", pid))
+        }
+    }
+
+    /// Whether `pid` is still running
+    ///
+    /// An exited child of the app stays a zombie that still answers signals
+    /// until it's waited on, so children are reaped here first.
+    fn is_alive(pid: Pid) -> bool {
+        match waitpid(pid, Some(WaitPidFlag::WNOHANG)) {
+            Ok(WaitStatus::StillAlive) => true,
+            Ok(_) => false,
+            // Not our child; EPERM still means it exists
+            Err(_) => kill(pid, None) != Err(Errno::ESRCH),
+        }
+    }
+
+    fn wait_for_exit(pid: Pid, timeout: Duration) -> bool {
+        wait_for_all(&[pid], timeout)
+    }
+
+    fn wait_for_all(processes: &[Pid], timeout: Duration) -> bool {
+        let started = Instant::now();
+        while processes.iter().any(|&process| is_alive(process)) {
+            if started.elapsed() >= timeout {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        true
+    }
+
+    pub fn kill_tree(pid: u32, descendants: &[u32], timeout: Duration) -> Result<(), String> {
+        let leader = Pid::from_raw(pid as i32);
+        let group = (getpgid(Some(leader)) == Ok(leader)).then_some(leader);
+        let processes: Vec<Pid> = std::iter::once(pid)
+            .chain(descendants.iter().copied())
+            .map(|pid| Pid::from_raw(pid as i32))
+            .collect();
+
+        signal_tree(group, &processes, Signal::SIGTERM);
+        if wait_for_all(&processes, timeout) {
+            return Ok(());
+        }
+        signal_tree(group, &processes, Signal::SIGKILL);
+        if wait_for_all(&processes, KILL_TIMEOUT) {
+            Ok(())
+        } else {
+            Err(format!("Processes from the tree of {} are still running", pid))
+        }
+    }
+
+    fn signal_tree(group: Option<Pid>, processes: &[Pid], signal: Signal) {
+        if let Some(group) = group {
+            let _ = killpg(group, signal);
+        }
+        for &process in processes {
+            if is_alive(process) {
+                let _ = kill(process, signal);
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::ShutdownStage;
+    use std::io;
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::Duration;
+    use std::{mem, ptr};
+    use windows_sys::Win32::Foundation::{
+        CloseHandle, ERROR_INVALID_PARAMETER, HANDLE, WAIT_OBJECT_0, WAIT_TIMEOUT,
+    };
+    use windows_sys::Win32::System::Console::{
+        AttachConsole, FreeConsole, GenerateConsoleCtrlEvent, SetConsoleCtrlHandler,
+        CTRL_BREAK_EVENT,
+    };
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, JobObjectBasicAccountingInformation,
+        QueryInformationJobObject, TerminateJobObject, JOBOBJECT_BASIC_ACCOUNTING_INFORMATION,
+    };
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, TerminateProcess, WaitForSingleObject, PROCESS_SYNCHRONIZE,
+        PROCESS_TERMINATE,
+    };
+
+    /// Termination is immediate, but the process still takes a moment to go
+    const KILL_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// A process can only be attached to one console at a time
+    static CONSOLE: Mutex<()> = Mutex::new(());
+
+    /// Job objects holding the trees of tracked children, by the child's pid;
+    /// handles are stored as `usize` since they aren't Send
+    static JOBS: Mutex<Vec<(u32, usize)>> = Mutex::new(Vec::new());
+
+    /// Send CTRL_BREAK to the process group `pid` leads
+    ///
+    /// Console events only reach processes on the sender's console, and the
+    /// app has none of its own, so it borrows the child's for the call,
+    /// ignoring the event itself while attached.
+    pub fn send_ctrl_break(pid: u32) -> Result<(), String> {
+        let _console = CONSOLE.lock().unwrap();
         unsafe {
-            let result = GenerateConsoleCtrlEvent(CTRL_C_EVENT, pid);
-            
-            if result != 0 {
-                SignalResult {
-                    success: true,
-                    message: format!("Ctrl+C event sent to process {}", pid),
-                }
+            FreeConsole();
+            if AttachConsole(pid) == 0 {
+                return Err(format!(
+                    "failed to attach to its console: {}",
+                    io::Error::last_os_error()
+                ));
+            }
+            SetConsoleCtrlHandler(None, 1);
+            let sent = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) != 0;
+            let error = io::Error::last_os_error();
+            FreeConsole();
+            SetConsoleCtrlHandler(None, 0);
+            if sent {
+                Ok(())
             } else {
-                SignalResult {
-                    success: false,
-                    message: format!("Failed to send Ctrl+C to process {}", pid),
+                Err(error.to_string())
+            }
+        }
+    }
+
+    /// Terminate `pid` in the background if it hasn't exited within `timeout`
+    pub fn terminate_after(pid: u32, timeout: Duration) {
+        // Opened now, so the pid can't be reused by another process before
+        // the timeout
+        let process = unsafe { OpenProcess(PROCESS_SYNCHRONIZE | PROCESS_TERMINATE, 0, pid) };
+        if process.is_null() {
+            return;
+        }
+        // Handles aren't Send, but the handle is only used on the new thread
+        let process = process as usize;
+        thread::spawn(move || unsafe {
+            let process = process as HANDLE;
+            if WaitForSingleObject(process, timeout.as_millis() as u32) == WAIT_TIMEOUT {
+                eprintln!("[Shutdown] Process {} outlived Ctrl+Break, terminating it", pid);
+                TerminateProcess(process, 1);
+            }
+            CloseHandle(process);
+        });
+    }
+
+    pub fn shutdown(pid: u32, timeout: Duration) -> Result<ShutdownStage, String> {
+        let process = unsafe { OpenProcess(PROCESS_SYNCHRONIZE | PROCESS_TERMINATE, 0, pid) };
+        if process.is_null() {
+            let error = io::Error::last_os_error();
+            if error.raw_os_error() == Some(ERROR_INVALID_PARAMETER as i32) {
+                return Ok(ShutdownStage::NotRunning);
+            }
+            return Err(format!("Failed to open process {}: {}", pid, error));
+        }
+
+        let exited = |timeout: Duration| unsafe {
+            WaitForSingleObject(process, timeout.as_millis() as u32) == WAIT_OBJECT_0
+        };
+        let stage = if exited(Duration::ZERO) {
+            Ok(ShutdownStage::NotRunning)
+        } else if send_ctrl_break(pid).is_ok() && exited(timeout) {
+            Ok(ShutdownStage::Interrupted)
+        } else if unsafe { TerminateProcess(process, 1) } == 0 {
+            Err(format!("Failed to terminate process {}: {}", pid, io::Error::last_os_error()))
+        } else if exited(KILL_TIMEOUT) {
+            Ok(ShutdownStage::Killed)
+        } else {
+            Err(format!("Process {} is still running after being terminated", pid))
+        };
+        unsafe { CloseHandle(process) };
+        stage
+    }
+
+    pub fn track(child: &Child) {
+        unsafe {
+            let job = CreateJobObjectW(ptr::null(), ptr::null());
+            if job.is_null() {
+                return;
+            }
+            if AssignProcessToJobObject(job, child.as_raw_handle() as HANDLE) == 0 {
+                CloseHandle(job);
+                return;
+            }
+            let mut jobs = JOBS.lock().unwrap();
+            // Let go of jobs whose processes have all exited
+            jobs.retain(|&(_, job)| {
+                let active = has_active_processes(job as HANDLE);
+                if !active {
+                    CloseHandle(job as HANDLE);
                 }
+                active
+            });
+            jobs.push((child.id(), job as usize));
+        }
+    }
+
+    unsafe fn has_active_processes(job: HANDLE) -> bool {
+        let mut info: JOBOBJECT_BASIC_ACCOUNTING_INFORMATION = mem::zeroed();
+        let queried = QueryInformationJobObject(
+            job,
+            JobObjectBasicAccountingInformation,
+            ptr::addr_of_mut!(info).cast(),
+            mem::size_of_val(&info) as u32,
+            ptr::null_mut(),
+        );
+        queried != 0 && info.ActiveProcesses > 0
+    }
+
+    pub fn kill_tree(pid: u32, descendants: &[u32], timeout: Duration) -> Result<(), String> {
+        // Ctrl+Break reaches the whole process group, so the tree can exit
+        // cleanly; the group only lasts while the leader does
+        let leader = unsafe { OpenProcess(PROCESS_SYNCHRONIZE, 0, pid) };
+        if !leader.is_null() {
+            if send_ctrl_break(pid).is_ok() {
+                unsafe { WaitForSingleObject(leader, timeout.as_millis() as u32) };
             }
+            unsafe { CloseHandle(leader) };
+        }
+
+        let job = {
+            let mut jobs = JOBS.lock().unwrap();
+            let index = jobs.iter().position(|&(child, _)| child == pid);
+            index.map(|index| jobs.swap_remove(index).1 as HANDLE)
+        };
+        if let Some(job) = job {
+            unsafe {
+                TerminateJobObject(job, 1);
+                CloseHandle(job);
+            }
+        }
+
+        // Processes outside the job, or the whole tree when there is none
+        let mut survivors = Vec::new();
+        for process_id in std::iter::once(pid).chain(descendants.iter().copied()) {
+            let process =
+                unsafe { OpenProcess(PROCESS_SYNCHRONIZE | PROCESS_TERMINATE, 0, process_id) };
+            if process.is_null() {
+                continue;
+            }
+            unsafe {
+                TerminateProcess(process, 1);
+                if WaitForSingleObject(process, KILL_TIMEOUT.as_millis() as u32) != WAIT_OBJECT_0 {
+                    survivors.push(process_id);
+                }
+                CloseHandle(process);
+            }
+        }
+        if survivors.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Processes {:?} from the tree of {} are still running", survivors, pid))
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,272 @@
+use serde::Serialize;
+use std::process::Command;
+use sysinfo::System;
+
+use crate::transcription::{get_compute_backends, ComputeBackends};
+
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// SIMD instruction sets whisper.cpp's CPU kernels are optimised for - sent to frontend
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuFeatures {
+    pub avx: bool,
+    pub avx2: bool,
+    pub avx512: bool,
+    pub fma: bool,
+    pub f16c: bool,
+    pub neon: bool,
+}
+
+impl CpuFeatures {
+    /// Whether the CPU has the vector units whisper.cpp needs to run larger models at usable speed
+    pub fn has_fast_simd(&self) -> bool {
+        self.avx2 || self.neon
+    }
+}
+
+/// A discrete or integrated GPU - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuInfo {
+    pub name: String,
+    /// Dedicated video memory; None when unknown or shared with system RAM
+    pub vram_bytes: Option<u64>,
+    /// Video memory not in use right now; only known for NVIDIA GPUs
+    pub free_vram_bytes: Option<u64>,
+}
+
+/// Machine capabilities relevant to local transcription - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HardwareInfo {
+    pub os: String,
+    pub arch: String,
+    pub cpu_brand: String,
+    pub physical_cores: usize,
+    pub logical_cores: usize,
+    pub cpu_features: CpuFeatures,
+    pub total_memory_bytes: u64,
+    pub available_memory_bytes: u64,
+    pub gpus: Vec<GpuInfo>,
+    /// GPU and CPU share system RAM (Apple Silicon)
+    pub unified_memory: bool,
+    /// Backends whisper.cpp was compiled with
+    pub backends: ComputeBackends,
+}
+
+/// Report CPU, memory, GPU, and whisper.cpp backend details for support
+/// diagnostics and model recommendations.
+///
+/// GPU detection shells out to platform tools (`nvidia-smi`, `system_profiler`,
+/// PowerShell, `lspci`), so this runs on a blocking thread.
+#[tauri::command]
+pub async fn get_hardware_info() -> Result<HardwareInfo, String> {
+    tokio::task::spawn_blocking(|| {
+        let mut system = System::new();
+        system.refresh_memory();
+        system.refresh_cpu_all();
+
+        let cpu_brand = system
+            .cpus()
+            .first()
+            .map(|cpu| cpu.brand().trim().to_string())
+            .unwrap_or_default();
+
+        HardwareInfo {
+            os: System::long_os_version().unwrap_or_else(|| std::env::consts::OS.to_string()),
+            arch: System::cpu_arch(),
+            cpu_brand,
+            physical_cores: physical_cores(),
+            logical_cores: system.cpus().len(),
+            cpu_features: cpu_features(),
+            total_memory_bytes: system.total_memory(),
+            available_memory_bytes: system.available_memory(),
+            gpus: gpus(),
+            unified_memory: has_unified_memory(),
+            backends: get_compute_backends(),
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
+
+pub fn cpu_features() -> CpuFeatures {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        CpuFeatures {
+            avx: std::arch::is_x86_feature_detected!("avx"),
+            avx2: std::arch::is_x86_feature_detected!("avx2"),
+            avx512: std::arch::is_x86_feature_detected!("avx512f"),
+            fma: std::arch::is_x86_feature_detected!("fma"),
+            f16c: std::arch::is_x86_feature_detected!("f16c"),
+            neon: false,
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        CpuFeatures {
+            neon: std::arch::is_aarch64_feature_detected!("neon"),
+            ..Default::default()
+        }
+    }
+
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+    {
+        CpuFeatures::default()
+    }
+}
+
+/// Total and currently available system memory in bytes
+pub fn memory() -> (u64, u64) {
+    let mut system = System::new();
+    system.refresh_memory();
+    (system.total_memory(), system.available_memory())
+}
+
+/// Physical cores, falling back to logical cores when the OS doesn't report them
+pub fn physical_cores() -> usize {
+    System::new().physical_core_count().unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    })
+}
+
+/// Whether the GPU shares system memory with the CPU (Apple Silicon)
+pub fn has_unified_memory() -> bool {
+    cfg!(all(target_os = "macos", target_arch = "aarch64"))
+}
+
+/// Detect NVIDIA GPUs and their VRAM via `nvidia-smi`
+///
+/// Returns an empty list when no NVIDIA driver is installed. Other vendors
+/// have no equally portable query, so they aren't reported.
+pub fn nvidia_gpus() -> Vec<GpuInfo> {
+    let mut cmd = Command::new("nvidia-smi");
+    cmd.args([
+        "--query-gpu=name,memory.total,memory.free",
+        "--format=csv,noheader,nounits",
+    ]);
+
+    #[cfg(target_os = "windows")]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = match cmd.output() {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+
+    let bytes = |mib: &str| mib.trim().parse::<u64>().ok().map(|mib| mib * 1024 * 1024);
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            // GPU names can contain commas, so split from the right
+            let mut fields = line.rsplitn(3, ',');
+            let free_mib = fields.next()?;
+            let total_mib = fields.next()?;
+            let name = fields.next()?;
+            Some(GpuInfo {
+                name: name.trim().to_string(),
+                vram_bytes: bytes(total_mib),
+                free_vram_bytes: bytes(free_mib),
+            })
+        })
+        .collect()
+}
+
+/// All GPUs we can identify, preferring `nvidia-smi` since it reports exact VRAM
+pub fn gpus() -> Vec<GpuInfo> {
+    let nvidia = nvidia_gpus();
+    if !nvidia.is_empty() {
+        return nvidia;
+    }
+    platform_gpus()
+}
+
+/// Apple GPUs have no dedicated VRAM; `system_profiler` lists the chipset
+#[cfg(target_os = "macos")]
+fn platform_gpus() -> Vec<GpuInfo> {
+    let output = match Command::new("system_profiler")
+        .arg("SPDisplaysDataType")
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("Chipset Model:"))
+        .map(|name| GpuInfo {
+            name: name.trim().to_string(),
+            vram_bytes: None,
+            free_vram_bytes: None,
+        })
+        .collect()
+}
+
+/// `AdapterRAM` is a 32-bit field, so VRAM above 4 GB is reported as unknown
+#[cfg(target_os = "windows")]
+fn platform_gpus() -> Vec<GpuInfo> {
+    let mut cmd = Command::new("powershell");
+    cmd.args([
+        "-NoProfile",
+        "-Command",
+        "Get-CimInstance Win32_VideoController | ForEach-Object { \"$($_.Name)|$($_.AdapterRAM)\" }",
+    ]);
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    let output = match cmd.output() {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (name, adapter_ram) = line.trim().rsplit_once('|')?;
+            let vram_bytes = adapter_ram
+                .parse::<u64>()
+                .ok()
+                .filter(|&bytes| bytes > 0 && bytes < u32::MAX as u64);
+            Some(GpuInfo {
+                name: name.trim().to_string(),
+                vram_bytes,
+                free_vram_bytes: None,
+            })
+        })
+        .collect()
+}
+
+/// `lspci` names the display controllers but not their memory
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn platform_gpus() -> Vec<GpuInfo> {
+    let output = match Command::new("lspci").arg("-mm").output() {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+
+    // -mm lines look like: 00:02.0 "VGA compatible controller" "Intel Corporation" "UHD Graphics 620" ...
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| {
+            line.contains("\"VGA compatible controller\"") || line.contains("\"3D controller\"")
+        })
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('"').collect();
+            let vendor = fields.get(3)?;
+            let device = fields.get(5)?;
+            Some(GpuInfo {
+                name: format!("{} {}", vendor, device),
+                vram_bytes: None,
+                free_vram_bytes: None,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,408 @@
+use axum::body::Bytes;
+use axum::extract::{DefaultBodyLimit, FromRequest, Multipart, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+use crate::ports::port_in_use;
+use crate::recorder;
+use crate::supervisor::{SupervisedProcess, Supervisor};
+use crate::transcription::{
+    transcribe_audio, transcribe_audio_parakeet, transcribe_audio_whisper_server,
+    whisper_server_port, whisper_server_status, EngineKind, LoadedModel, ModelManager,
+    TranscribeOptions, WhisperServerStatus,
+};
+
+/// Port the API listens on when none is given
+const DEFAULT_PORT: u16 = 7437;
+
+/// Large enough for more than an hour of 16 kHz WAV
+const MAX_UPLOAD_BYTES: usize = 200 * 1024 * 1024;
+
+/// Shortest token accepted, so other local programs can't guess it
+const MIN_TOKEN_LENGTH: usize = 16;
+
+/// How to run the local API - from frontend
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpApiConfig {
+    pub port: Option<u16>,
+    /// Clients send this as `Authorization: Bearer <token>`
+    pub token: String,
+    /// Folder listed by `/recordings`, the one recording sessions write to
+    pub recordings_folder: Option<String>,
+}
+
+/// Whether the local API is listening - sent to frontend
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HttpApiStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+}
+
+struct RunningApi {
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+}
+
+/// An opt-in HTTP server on localhost, so scripts, launchers like Raycast
+/// or Alfred, and other apps can use the local transcription setup
+///
+/// Every request needs the token from `HttpApiConfig`. Routes:
+/// - `GET /status`: recorder, model and local server state
+/// - `GET /recordings`: WAV files in the recordings folder, newest first
+/// - `POST /transcribe`: transcribes the body, or the `file` field of a
+///   multipart form, with whisper-server or the loaded model. `?language=`
+///   sets the language.
+pub struct HttpApi {
+    server: Mutex<Option<RunningApi>>,
+}
+
+impl HttpApi {
+    pub fn new() -> Self {
+        Self {
+            server: Mutex::new(None),
+        }
+    }
+
+    pub fn status(&self) -> HttpApiStatus {
+        let port = self.server.lock().unwrap().as_ref().map(|server| server.port);
+        HttpApiStatus {
+            running: port.is_some(),
+            port,
+        }
+    }
+
+    /// Stop listening, letting requests in progress finish
+    pub fn stop(&self) {
+        if let Some(server) = self.server.lock().unwrap().take() {
+            let _ = server.shutdown.send(());
+            println!("[HTTP API] Stopped listening on port {}", server.port);
+        }
+    }
+}
+
+impl Default for HttpApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone)]
+struct ApiState {
+    app: AppHandle,
+    token: Arc<str>,
+    recordings_folder: Option<Arc<Path>>,
+}
+
+/// An error response, sent as `{ "error": message }`
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+/// Start the local API on 127.0.0.1, replacing one already running
+#[tauri::command]
+pub async fn start_http_api(
+    config: HttpApiConfig,
+    app_handle: AppHandle,
+) -> Result<HttpApiStatus, String> {
+    if config.token.len() < MIN_TOKEN_LENGTH {
+        return Err(format!(
+            "The API token must be at least {} characters",
+            MIN_TOKEN_LENGTH
+        ));
+    }
+    let api = app_handle.state::<HttpApi>();
+    api.stop();
+
+    let port = config.port.unwrap_or(DEFAULT_PORT);
+    let check_handle = app_handle.clone();
+    let in_use = tokio::task::spawn_blocking(move || port_in_use(&check_handle, port, None))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?;
+    if let Some(error) = in_use {
+        return Err(error.to_string());
+    }
+    let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .await
+        .map_err(|e| format!("Failed to listen on port {}: {}", port, e))?;
+
+    let state = ApiState {
+        app: app_handle.clone(),
+        token: config.token.into(),
+        recordings_folder: config.recordings_folder.map(|folder| PathBuf::from(folder).into()),
+    };
+    let router = Router::new()
+        .route("/status", get(status))
+        .route("/recordings", get(recordings))
+        .route("/transcribe", post(transcribe))
+        .layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES))
+        .layer(middleware::from_fn_with_state(state.clone(), authorize))
+        .with_state(state);
+
+    let (shutdown, stopped) = oneshot::channel();
+    tauri::async_runtime::spawn(async move {
+        let server = axum::serve(listener, router).with_graceful_shutdown(async {
+            let _ = stopped.await;
+        });
+        if let Err(e) = server.await {
+            eprintln!("[HTTP API] Server error: {}", e);
+        }
+    });
+
+    let previous = api
+        .server
+        .lock()
+        .unwrap()
+        .replace(RunningApi { port, shutdown });
+    if let Some(previous) = previous {
+        let _ = previous.shutdown.send(());
+    }
+    println!("[HTTP API] Listening on 127.0.0.1:{}", port);
+    Ok(api.status())
+}
+
+/// Stop the local API, if it's running
+#[tauri::command]
+pub fn stop_http_api(api: tauri::State<'_, HttpApi>) {
+    api.stop();
+}
+
+#[tauri::command]
+pub fn get_http_api_status(api: tauri::State<'_, HttpApi>) -> HttpApiStatus {
+    api.status()
+}
+
+/// Reject requests without the API token
+async fn authorize(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if !token.is_some_and(|token| tokens_match(token.as_bytes(), state.token.as_bytes())) {
+        return ApiError::new(StatusCode::UNAUTHORIZED, "Missing or invalid API token")
+            .into_response();
+    }
+    next.run(request).await
+}
+
+/// Compare without stopping at the first difference, so response times
+/// don't give the token away
+fn tokens_match(given: &[u8], expected: &[u8]) -> bool {
+    given.len() == expected.len()
+        && given
+            .iter()
+            .zip(expected)
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// Response of `GET /status`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiStatus {
+    version: String,
+    /// Set while the app is recording
+    recording_id: Option<String>,
+    loaded_model: Option<LoadedModel>,
+    /// Set while a transcription is using the model, which leaves
+    /// `loaded_model` unknown
+    model_busy: bool,
+    whisper_server: WhisperServerStatus,
+    processes: Vec<SupervisedProcess>,
+}
+
+async fn status(State(state): State<ApiState>) -> Json<ApiStatus> {
+    let app = &state.app;
+    let recording_id = recorder::get_current_recording_id(app.state()).await;
+    // Waiting for the model would hold the request for a whole transcription
+    let loaded_model = app.state::<ModelManager>().try_loaded_model();
+    Json(ApiStatus {
+        version: app.package_info().version.to_string(),
+        recording_id: recording_id.ok().flatten(),
+        model_busy: loaded_model.is_none(),
+        loaded_model: loaded_model.flatten(),
+        whisper_server: whisper_server_status(app),
+        processes: app.state::<Supervisor>().list(),
+    })
+}
+
+/// A recording in the recordings folder, in responses of `GET /recordings`
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Recording {
+    id: String,
+    path: String,
+    size_bytes: u64,
+    /// Missing while the recording is still being written
+    duration_seconds: Option<f32>,
+    /// Milliseconds since the Unix epoch
+    modified: Option<u64>,
+}
+
+async fn recordings(State(state): State<ApiState>) -> Result<Json<Vec<Recording>>, ApiError> {
+    let folder = state.recordings_folder.clone().ok_or_else(|| {
+        ApiError::new(StatusCode::NOT_FOUND, "No recordings folder is configured")
+    })?;
+    tokio::task::spawn_blocking(move || list_recordings(&folder))
+        .await
+        .map_err(|e| {
+            ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, format!("Task join error: {}", e))
+        })?
+        .map(Json)
+        .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+fn list_recordings(folder: &Path) -> Result<Vec<Recording>, String> {
+    let entries = std::fs::read_dir(folder)
+        .map_err(|e| format!("Failed to read recordings folder: {}", e))?;
+    let mut recordings: Vec<Recording> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "wav"))
+        .filter_map(|path| {
+            let metadata = std::fs::metadata(&path).ok()?;
+            let duration_seconds = hound::WavReader::open(&path)
+                .ok()
+                .map(|reader| reader.duration() as f32 / reader.spec().sample_rate as f32);
+            Some(Recording {
+                id: path.file_stem()?.to_string_lossy().to_string(),
+                path: path.to_string_lossy().to_string(),
+                size_bytes: metadata.len(),
+                duration_seconds,
+                modified: metadata
+                    .modified()
+                    .ok()
+                    .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+                    .map(|modified| modified.as_millis() as u64),
+            })
+        })
+        .collect();
+    recordings.sort_by(|a, b| b.modified.cmp(&a.modified));
+    Ok(recordings)
+}
+
+#[derive(Deserialize)]
+struct TranscribeQuery {
+    language: Option<String>,
+}
+
+/// Response of `POST /transcribe`
+#[derive(Serialize)]
+struct Transcript {
+    text: String,
+}
+
+/// Transcribe with whisper-server when it's running, otherwise with the
+/// model loaded in the app
+async fn transcribe(
+    State(state): State<ApiState>,
+    Query(query): Query<TranscribeQuery>,
+    request: Request,
+) -> Result<Json<Transcript>, ApiError> {
+    let audio = read_upload(&state, request).await?;
+    if audio.is_empty() {
+        return Err(ApiError::new(StatusCode::BAD_REQUEST, "The request has no audio"));
+    }
+
+    let app = &state.app;
+    let language = query.language;
+    let result = if whisper_server_port(app).is_some() {
+        transcribe_audio_whisper_server(audio, language, None, None, app.clone()).await
+    } else {
+        let model_manager = app.state::<ModelManager>().inner().clone();
+        let model = tokio::task::spawn_blocking(move || model_manager.loaded_model())
+            .await
+            .map_err(|e| {
+                ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, format!("Task join error: {}", e))
+            })?;
+        let model = model.ok_or_else(|| {
+            ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "No transcription model is loaded in the app",
+            )
+        })?;
+        match model.engine {
+            EngineKind::Whisper => {
+                let options = TranscribeOptions {
+                    language,
+                    ..Default::default()
+                };
+                transcribe_audio(audio, model.model_path, options, app.clone()).await
+            }
+            EngineKind::Parakeet => {
+                transcribe_audio_parakeet(audio, model.model_path, None, app.state(), app.clone())
+                    .await
+            }
+        }
+    };
+
+    let text = result
+        .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(Transcript { text }))
+}
+
+/// The uploaded audio: the `file` field of a multipart form, or the whole
+/// body otherwise
+async fn read_upload(state: &ApiState, request: Request) -> Result<Vec<u8>, ApiError> {
+    let is_form = request
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("multipart/form-data"));
+    if !is_form {
+        let body = Bytes::from_request(request, state)
+            .await
+            .map_err(|e| ApiError::new(e.status(), e.body_text()))?;
+        return Ok(body.to_vec());
+    }
+
+    let mut form = Multipart::from_request(request, state)
+        .await
+        .map_err(|e| ApiError::new(e.status(), e.body_text()))?;
+    while let Some(field) = form
+        .next_field()
+        .await
+        .map_err(|e| ApiError::new(e.status(), e.body_text()))?
+    {
+        if field.name() == Some("file") {
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| ApiError::new(e.status(), e.body_text()))?;
+            return Ok(bytes.to_vec());
+        }
+    }
+    Err(ApiError::new(
+        StatusCode::BAD_REQUEST,
+        "Expected the audio in a form field named \"file\"",
+    ))
+}
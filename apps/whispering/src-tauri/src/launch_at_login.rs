@@ -0,0 +1,287 @@
+use serde::Serialize;
+use tauri::AppHandle;
+
+/// Passed by the login entry when the app should start without its window
+pub const HIDDEN_ARG: &str = "--hidden";
+
+/// Whether the app opens at login - sent to frontend
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchAtLogin {
+    pub enabled: bool,
+    /// Whether it starts without showing the main window
+    pub hidden: bool,
+    /// macOS only: registered, but waiting for the user to allow it in
+    /// System Settings > General > Login Items
+    pub requires_approval: bool,
+}
+
+/// Whether this process was started hidden by its login entry
+pub fn launched_hidden() -> bool {
+    std::env::args().any(|arg| arg == HIDDEN_ARG)
+}
+
+/// Get whether the app opens at login
+#[tauri::command]
+pub fn get_launch_at_login(app_handle: AppHandle) -> Result<LaunchAtLogin, String> {
+    platform::status(&app_handle)
+}
+
+/// Open the app at login, optionally without showing its window, or stop
+/// opening it, returning the resulting state
+///
+/// Uses SMAppService on macOS (13 and later), the registry Run key on Windows
+/// and an XDG autostart entry on Linux. macOS starts login items without
+/// arguments, so there `hidden` has no effect.
+#[tauri::command]
+pub fn set_launch_at_login(
+    enabled: bool,
+    hidden: bool,
+    app_handle: AppHandle,
+) -> Result<LaunchAtLogin, String> {
+    if enabled {
+        platform::enable(&app_handle, hidden)?;
+    } else {
+        platform::disable(&app_handle)?;
+    }
+    platform::status(&app_handle)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::LaunchAtLogin;
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject};
+    use objc2_foundation::NSString;
+    use std::ptr;
+    use tauri::AppHandle;
+
+    #[link(name = "ServiceManagement", kind = "framework")]
+    extern "C" {}
+
+    // SMAppServiceStatus
+    const STATUS_NOT_REGISTERED: isize = 0;
+    const STATUS_ENABLED: isize = 1;
+    const STATUS_REQUIRES_APPROVAL: isize = 2;
+
+    fn main_app_service() -> Result<Retained<AnyObject>, String> {
+        let class = AnyClass::get(c"SMAppService")
+            .ok_or_else(|| "Launching at login needs macOS 13 or later".to_string())?;
+        Ok(unsafe { msg_send![class, mainAppService] })
+    }
+
+    fn service_status(service: &AnyObject) -> isize {
+        unsafe { msg_send![service, status] }
+    }
+
+    pub fn status(_app: &AppHandle) -> Result<LaunchAtLogin, String> {
+        let status = service_status(&main_app_service()?);
+        Ok(LaunchAtLogin {
+            enabled: status == STATUS_ENABLED || status == STATUS_REQUIRES_APPROVAL,
+            hidden: false,
+            requires_approval: status == STATUS_REQUIRES_APPROVAL,
+        })
+    }
+
+    pub fn enable(_app: &AppHandle, _hidden: bool) -> Result<(), String> {
+        let service = main_app_service()?;
+        let mut error: *mut AnyObject = ptr::null_mut();
+        let registered: bool = unsafe { msg_send![&*service, registerAndReturnError: &mut error] };
+        // Registering succeeds but reports an error while approval is pending
+        if registered || service_status(&service) == STATUS_REQUIRES_APPROVAL {
+            return Ok(());
+        }
+        Err(format!("Failed to add login item: {}", unsafe { describe(error) }))
+    }
+
+    pub fn disable(_app: &AppHandle) -> Result<(), String> {
+        let service = main_app_service()?;
+        if service_status(&service) == STATUS_NOT_REGISTERED {
+            return Ok(());
+        }
+        let mut error: *mut AnyObject = ptr::null_mut();
+        let unregistered: bool =
+            unsafe { msg_send![&*service, unregisterAndReturnError: &mut error] };
+        if unregistered {
+            return Ok(());
+        }
+        Err(format!("Failed to remove login item: {}", unsafe { describe(error) }))
+    }
+
+    /// The localized description of an `NSError`, which may be null
+    unsafe fn describe(error: *mut AnyObject) -> String {
+        let Some(error) = error.as_ref() else {
+            return "unknown error".to_string();
+        };
+        let description: Option<Retained<NSString>> = msg_send![error, localizedDescription];
+        description.map_or_else(|| "unknown error".to_string(), |d| d.to_string())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{LaunchAtLogin, HIDDEN_ARG};
+    use std::ptr;
+    use tauri::{AppHandle, Manager};
+    use windows_sys::Win32::Foundation::{ERROR_FILE_NOT_FOUND, ERROR_SUCCESS};
+    use windows_sys::Win32::System::Registry::{
+        RegDeleteKeyValueW, RegGetValueW, RegSetKeyValueW, HKEY_CURRENT_USER, REG_SZ,
+        RRF_RT_REG_SZ,
+    };
+
+    const RUN_KEY: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(Some(0)).collect()
+    }
+
+    /// The command line stored under the Run key, if there is one
+    fn run_command(app: &AppHandle) -> Result<Option<String>, String> {
+        let (key, name) = (wide(RUN_KEY), wide(&app.package_info().name));
+        let mut size: u32 = 0;
+        unsafe {
+            let status = RegGetValueW(
+                HKEY_CURRENT_USER,
+                key.as_ptr(),
+                name.as_ptr(),
+                RRF_RT_REG_SZ,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut size,
+            );
+            if status == ERROR_FILE_NOT_FOUND {
+                return Ok(None);
+            }
+            if status != ERROR_SUCCESS {
+                return Err(format!("Failed to read login entry: error {}", status));
+            }
+            let mut buffer = vec![0u16; size as usize / 2];
+            let status = RegGetValueW(
+                HKEY_CURRENT_USER,
+                key.as_ptr(),
+                name.as_ptr(),
+                RRF_RT_REG_SZ,
+                ptr::null_mut(),
+                buffer.as_mut_ptr().cast(),
+                &mut size,
+            );
+            if status != ERROR_SUCCESS {
+                return Err(format!("Failed to read login entry: error {}", status));
+            }
+            let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+            Ok(Some(String::from_utf16_lossy(&buffer[..len])))
+        }
+    }
+
+    pub fn status(app: &AppHandle) -> Result<LaunchAtLogin, String> {
+        let command = run_command(app)?;
+        Ok(LaunchAtLogin {
+            enabled: command.is_some(),
+            hidden: command.is_some_and(|command| command.ends_with(HIDDEN_ARG)),
+            requires_approval: false,
+        })
+    }
+
+    pub fn enable(app: &AppHandle, hidden: bool) -> Result<(), String> {
+        let executable = std::env::current_exe()
+            .map_err(|e| format!("Failed to find the app executable: {}", e))?;
+        let mut command = format!("\"{}\"", executable.display());
+        if hidden {
+            command.push(' ');
+            command.push_str(HIDDEN_ARG);
+        }
+
+        let (key, name, command) = (wide(RUN_KEY), wide(&app.package_info().name), wide(&command));
+        let status = unsafe {
+            RegSetKeyValueW(
+                HKEY_CURRENT_USER,
+                key.as_ptr(),
+                name.as_ptr(),
+                REG_SZ,
+                command.as_ptr().cast(),
+                (command.len() * 2) as u32,
+            )
+        };
+        if status != ERROR_SUCCESS {
+            return Err(format!("Failed to write login entry: error {}", status));
+        }
+        Ok(())
+    }
+
+    pub fn disable(app: &AppHandle) -> Result<(), String> {
+        let (key, name) = (wide(RUN_KEY), wide(&app.package_info().name));
+        let status = unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, key.as_ptr(), name.as_ptr()) };
+        if status != ERROR_SUCCESS && status != ERROR_FILE_NOT_FOUND {
+            return Err(format!("Failed to remove login entry: error {}", status));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    use super::{LaunchAtLogin, HIDDEN_ARG};
+    use std::path::PathBuf;
+    use tauri::{AppHandle, Manager};
+
+    /// The XDG autostart entry, in `~/.config/autostart`
+    fn entry_path(app: &AppHandle) -> Result<PathBuf, String> {
+        let file_name = format!("{}.desktop", app.package_info().name.to_lowercase());
+        app.path()
+            .config_dir()
+            .map(|dir| dir.join("autostart").join(file_name))
+            .map_err(|e| format!("Failed to resolve config directory: {}", e))
+    }
+
+    pub fn status(app: &AppHandle) -> Result<LaunchAtLogin, String> {
+        let path = entry_path(app)?;
+        if !path.exists() {
+            return Ok(LaunchAtLogin::default());
+        }
+        let entry = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read autostart entry: {}", e))?;
+        Ok(LaunchAtLogin {
+            enabled: true,
+            hidden: entry
+                .lines()
+                .any(|line| line.starts_with("Exec=") && line.ends_with(HIDDEN_ARG)),
+            requires_approval: false,
+        })
+    }
+
+    pub fn enable(app: &AppHandle, hidden: bool) -> Result<(), String> {
+        // Inside an AppImage the executable is a temporary mount; start the
+        // AppImage itself instead
+        let executable = match std::env::var_os("APPIMAGE") {
+            Some(appimage) => PathBuf::from(appimage),
+            None => std::env::current_exe()
+                .map_err(|e| format!("Failed to find the app executable: {}", e))?,
+        };
+        let mut exec = format!("\"{}\"", executable.display());
+        if hidden {
+            exec.push(' ');
+            exec.push_str(HIDDEN_ARG);
+        }
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec={}\nX-GNOME-Autostart-enabled=true\n",
+            app.package_info().name,
+            exec
+        );
+
+        let path = entry_path(app)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create autostart directory: {}", e))?;
+        }
+        std::fs::write(&path, entry).map_err(|e| format!("Failed to write autostart entry: {}", e))
+    }
+
+    pub fn disable(app: &AppHandle) -> Result<(), String> {
+        match std::fs::remove_file(entry_path(app)?) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to remove autostart entry: {}", e)),
+        }
+    }
+}
@@ -9,13 +9,58 @@ use recorder::commands::{
 };
 
 pub mod transcription;
-use transcription::{transcribe_audio_whisper, transcribe_audio_parakeet, ModelManager};
+use transcription::{
+    benchmark_models, cancel_job, cancel_transcription, detect_language, download_coreml_encoder,
+    enqueue_transcription, get_compute_backends, get_coreml_encoder_status, get_loaded_model,
+    get_replacement_rules, get_vocabulary, get_whisper_server_status, list_jobs, load_model,
+    retry_failed_jobs, set_model_idle_timeout, set_replacement_rules, set_vocabulary,
+    start_live_transcription, start_whisper_server, stop_live_transcription, stop_whisper_server,
+    transcribe_audio_apple, transcribe_audio_parakeet, transcribe_audio_vosk,
+    transcribe_audio_whisper, transcribe_audio_whisper_grammar, transcribe_audio_whisper_server,
+    transcribe_audio_windows, transcribe_pcm_whisper, unload_model, JobRegistry,
+    LiveTranscription, ModelManager, NerModels, PunctuationModels, Replacements,
+    TranscriptionQueue, Vocabulary, VoskModels,
+};
+
+pub mod models;
+use models::{
+    cancel_model_download, delete_local_model, download_model, estimate_model_memory,
+    extract_model_archive, list_local_models, recommend_model, ModelDownloads,
+};
+
+pub mod hardware;
+use hardware::get_hardware_info;
+
+pub mod loudness;
+
+pub mod audio_redaction;
+use audio_redaction::redact_audio;
+
+pub mod media;
+use media::{
+    duck_system_volume, get_active_media_players, get_disabled_media_players, get_media_players,
+    mute_system_output, pause_active_media, restore_system_output, restore_system_volume,
+    resume_media, set_disabled_media_players, DisabledPlayers, MediaSession, OutputMute,
+    VolumeDucking,
+};
 
 pub mod windows_path;
 use windows_path::fix_windows_path;
 
 pub mod graceful_shutdown;
-use graceful_shutdown::send_sigint;
+use graceful_shutdown::{kill_process_tree, send_sigint, shutdown_process};
+
+pub mod process_status;
+use process_status::get_process_status;
+
+pub mod ports;
+use ports::{check_port, find_free_port};
+
+pub mod supervisor;
+use supervisor::{
+    get_process_logs, list_supervised_processes, start_supervised_process,
+    stop_supervised_process, unwatch_process_logs, watch_process_logs, Supervisor,
+};
 
 pub mod command;
 use command::{execute_command, spawn_command};
@@ -23,6 +68,47 @@ use command::{execute_command, spawn_command};
 pub mod markdown_reader;
 use markdown_reader::{count_markdown_files, read_markdown_files};
 
+#[cfg(desktop)]
+pub mod http_api;
+#[cfg(desktop)]
+use http_api::{get_http_api_status, start_http_api, stop_http_api, HttpApi};
+
+#[cfg(desktop)]
+pub mod launch_at_login;
+#[cfg(desktop)]
+use launch_at_login::{get_launch_at_login, set_launch_at_login};
+
+#[cfg(desktop)]
+pub mod overlay;
+#[cfg(desktop)]
+use overlay::{set_live_captions, set_recording_overlay, LiveCaptions, RecordingOverlay};
+
+#[cfg(desktop)]
+pub mod push_to_talk;
+#[cfg(desktop)]
+use push_to_talk::{set_push_to_talk_shortcut, PushToTalk};
+
+#[cfg(desktop)]
+pub mod shortcuts;
+#[cfg(desktop)]
+use shortcuts::{
+    set_mouse_shortcuts, set_toggle_recording_shortcut, MouseShortcuts, ToggleShortcut,
+};
+
+#[cfg(desktop)]
+pub mod tray;
+#[cfg(desktop)]
+use tray::{
+    get_close_to_tray, set_close_to_tray, set_dock_icon_hidden, set_tray_recording_meter,
+    CloseToTray, TrayMeter,
+};
+
+pub mod text_injection;
+use text_injection::{
+    get_excluded_apps, get_focused_application, get_text_injection_capabilities,
+    set_excluded_apps, write_text, write_text_to_window, ExcludedApps, InsertionHistory,
+};
+
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 #[tokio::main]
@@ -37,6 +123,10 @@ pub async fn run() {
     
     let mut builder = tauri::Builder::default();
 
+    // Keep transcription models resident between requests, freeing them when idle
+    let model_manager = ModelManager::new();
+    model_manager.spawn_idle_watcher();
+
     // Try to get APTABASE_KEY from environment, use empty string if not found
     let aptabase_key = option_env!("APTABASE_KEY").unwrap_or("");
 
@@ -62,10 +152,48 @@ pub async fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
         .manage(AppData::new())
-        .manage(ModelManager::new());
+        .manage(model_manager)
+        .manage(JobRegistry::new())
+        .manage(Vocabulary::new())
+        .manage(Replacements::new())
+        .manage(TranscriptionQueue::new())
+        .manage(LiveTranscription::new())
+        .manage(VoskModels::new())
+        .manage(PunctuationModels::new())
+        .manage(NerModels::new())
+        .manage(InsertionHistory::new())
+        .manage(ExcludedApps::new())
+        .manage(MediaSession::new())
+        .manage(DisabledPlayers::new())
+        .manage(VolumeDucking::new())
+        .manage(OutputMute::new())
+        .manage(Supervisor::new())
+        .manage(ModelDownloads::new())
+        .setup(|app| {
+            TranscriptionQueue::spawn_worker(app.handle().clone());
+            #[cfg(desktop)]
+            tray::create(app.handle())?;
+            #[cfg(desktop)]
+            if launch_at_login::launched_hidden() {
+                if let Some(window) = app.get_webview_window("main") {
+                    let _ = window.hide();
+                }
+            }
+            Ok(())
+        });
 
     #[cfg(desktop)]
     {
+        builder = builder
+            .manage(CloseToTray::new())
+            .manage(TrayMeter::new())
+            .manage(PushToTalk::new())
+            .manage(MouseShortcuts::new())
+            .manage(ToggleShortcut::new())
+            .manage(RecordingOverlay::new())
+            .manage(LiveCaptions::new())
+            .manage(HttpApi::new())
+            .on_window_event(tray::handle_window_event);
         builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
             let _ = app
                 .get_webview_window("main")
@@ -77,6 +205,11 @@ pub async fn run() {
     // Register command handlers (same for all platforms now)
     let builder = builder.invoke_handler(tauri::generate_handler![
         write_text,
+        write_text_to_window,
+        get_text_injection_capabilities,
+        get_focused_application,
+        get_excluded_apps,
+        set_excluded_apps,
         // Audio recorder commands
         get_current_recording_id,
         enumerate_recording_devices,
@@ -85,9 +218,103 @@ pub async fn run() {
         start_recording,
         stop_recording,
         cancel_recording,
+        redact_audio,
+        // Pausing or ducking media while recording
+        pause_active_media,
+        resume_media,
+        get_active_media_players,
+        get_media_players,
+        get_disabled_media_players,
+        set_disabled_media_players,
+        duck_system_volume,
+        restore_system_volume,
+        mute_system_output,
+        restore_system_output,
+        // Tray, overlays and window behavior
+        #[cfg(desktop)]
+        get_close_to_tray,
+        #[cfg(desktop)]
+        set_close_to_tray,
+        #[cfg(desktop)]
+        set_tray_recording_meter,
+        #[cfg(desktop)]
+        set_dock_icon_hidden,
+        #[cfg(desktop)]
+        set_recording_overlay,
+        #[cfg(desktop)]
+        set_live_captions,
+        #[cfg(desktop)]
+        get_launch_at_login,
+        #[cfg(desktop)]
+        set_launch_at_login,
+        // Push to talk and native shortcuts
+        #[cfg(desktop)]
+        set_push_to_talk_shortcut,
+        #[cfg(desktop)]
+        set_mouse_shortcuts,
+        #[cfg(desktop)]
+        set_toggle_recording_shortcut,
+        // Local HTTP API for other apps
+        #[cfg(desktop)]
+        start_http_api,
+        #[cfg(desktop)]
+        stop_http_api,
+        #[cfg(desktop)]
+        get_http_api_status,
+        // Local transcription commands
         transcribe_audio_whisper,
+        transcribe_audio_whisper_grammar,
         transcribe_audio_parakeet,
+        transcribe_audio_vosk,
+        transcribe_audio_apple,
+        transcribe_audio_windows,
+        transcribe_pcm_whisper,
+        cancel_transcription,
+        detect_language,
+        load_model,
+        unload_model,
+        get_loaded_model,
+        set_model_idle_timeout,
+        get_compute_backends,
+        benchmark_models,
+        get_hardware_info,
+        get_coreml_encoder_status,
+        download_coreml_encoder,
+        get_vocabulary,
+        set_vocabulary,
+        get_replacement_rules,
+        set_replacement_rules,
+        enqueue_transcription,
+        list_jobs,
+        cancel_job,
+        retry_failed_jobs,
+        start_live_transcription,
+        stop_live_transcription,
+        start_whisper_server,
+        stop_whisper_server,
+        get_whisper_server_status,
+        transcribe_audio_whisper_server,
+        // Model management
+        download_model,
+        cancel_model_download,
+        list_local_models,
+        delete_local_model,
+        extract_model_archive,
+        recommend_model,
+        estimate_model_memory,
         send_sigint,
+        shutdown_process,
+        kill_process_tree,
+        get_process_status,
+        // Supervised local servers
+        start_supervised_process,
+        stop_supervised_process,
+        list_supervised_processes,
+        get_process_logs,
+        watch_process_logs,
+        unwatch_process_logs,
+        check_port,
+        find_free_port,
         // Command execution (prevents console window flash on Windows)
         execute_command,
         spawn_command,
@@ -101,6 +328,27 @@ pub async fn run() {
         .expect("error while building tauri application");
 
     app.run(|handler, event| {
+        // Don't leave managed servers running after the app quits,
+        // or media paused and speakers muted by a recording that never stopped
+        if let tauri::RunEvent::Exit { .. } = event {
+            handler.state::<Supervisor>().stop_all();
+            #[cfg(desktop)]
+            handler.state::<HttpApi>().stop();
+            handler.state::<MediaSession>().resume();
+            let _ = handler.state::<OutputMute>().restore();
+            let _ = handler.state::<VolumeDucking>().restore();
+        }
+
+        // Clicking the Dock icon brings back a window hidden to the tray
+        #[cfg(target_os = "macos")]
+        if let tauri::RunEvent::Reopen {
+            has_visible_windows: false,
+            ..
+        } = event
+        {
+            tray::show_main_window(handler);
+        }
+
         // Only track events if Aptabase is enabled (key is not empty)
         if !aptabase_key.is_empty() {
             match event {
@@ -116,70 +364,3 @@ pub async fn run() {
         }
     });
 }
-
-use enigo::{Direction, Enigo, Key, Keyboard, Settings};
-use tauri_plugin_clipboard_manager::ClipboardExt;
-
-/// Writes text at the cursor position using the clipboard sandwich technique
-///
-/// This method preserves the user's existing clipboard content by:
-/// 1. Saving the current clipboard content
-/// 2. Writing the new text to clipboard
-/// 3. Simulating a paste operation (Cmd+V on macOS, Ctrl+V elsewhere)
-/// 4. Restoring the original clipboard content
-///
-/// This approach is faster than typing character-by-character and preserves
-/// the user's clipboard, making it ideal for inserting transcribed text.
-#[tauri::command]
-async fn write_text(app: tauri::AppHandle, text: String) -> Result<(), String> {
-    // 1. Save current clipboard content
-    let original_clipboard = app.clipboard().read_text().ok();
-
-    // 2. Write new text to clipboard
-    app.clipboard()
-        .write_text(&text)
-        .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
-
-    // Small delay to ensure clipboard is updated
-    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
-
-    // 3. Simulate paste operation using virtual key codes (layout-independent)
-    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
-    
-    // Use virtual key codes for V to work with any keyboard layout
-    #[cfg(target_os = "macos")]
-    let (modifier, v_key) = (Key::Meta, Key::Other(9)); // Virtual key code for V on macOS
-    #[cfg(target_os = "windows")]
-    let (modifier, v_key) = (Key::Control, Key::Other(0x56)); // VK_V on Windows
-    #[cfg(target_os = "linux")]
-    let (modifier, v_key) = (Key::Control, Key::Unicode('v')); // Fallback for Linux
-
-    // Press modifier + V
-    enigo
-        .key(modifier, Direction::Press)
-        .map_err(|e| format!("Failed to press modifier key: {}", e))?;
-    enigo
-        .key(v_key, Direction::Press)
-        .map_err(|e| format!("Failed to press V key: {}", e))?;
-    
-    // Release V + modifier (in reverse order for proper cleanup)
-    enigo
-        .key(v_key, Direction::Release)
-        .map_err(|e| format!("Failed to release V key: {}", e))?;
-    enigo
-        .key(modifier, Direction::Release)
-        .map_err(|e| format!("Failed to release modifier key: {}", e))?;
-
-    // Small delay to ensure paste completes
-    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-    // 4. Restore original clipboard content
-    if let Some(content) = original_clipboard {
-        app.clipboard()
-            .write_text(&content)
-            .map_err(|e| format!("Failed to restore clipboard: {}", e))?;
-    }
-
-    Ok(())
-}
-
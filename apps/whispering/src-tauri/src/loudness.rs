@@ -0,0 +1,180 @@
+/// EBU R128 programme loudness target, measured per ITU-R BS.1770
+pub const TARGET_LUFS: f64 = -23.0;
+
+/// Never push peaks above -1 dBFS, so quiet recordings with a single loud
+/// click aren't clipped
+const MAX_PEAK: f32 = 0.891;
+
+/// Cap the boost so near-silent recordings don't turn into amplified noise
+const MAX_GAIN_DB: f64 = 30.0;
+
+/// Gating blocks are 400 ms long with 75% overlap
+const BLOCK_SECONDS: f64 = 0.4;
+const STEP_SECONDS: f64 = 0.1;
+
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+const RELATIVE_GATE_LU: f64 = -10.0;
+
+/// Second-order IIR section in direct form I
+#[derive(Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+}
+
+impl Biquad {
+    fn run(&self, input: &[f64]) -> Vec<f64> {
+        let (mut x1, mut x2, mut y1, mut y2) = (0.0, 0.0, 0.0, 0.0);
+        input
+            .iter()
+            .map(|&x| {
+                let y = self.b[0] * x + self.b[1] * x1 + self.b[2] * x2
+                    - self.a[0] * y1
+                    - self.a[1] * y2;
+                x2 = x1;
+                x1 = x;
+                y2 = y1;
+                y1 = y;
+                y
+            })
+            .collect()
+    }
+}
+
+/// The two K-weighting stages (high shelf, then high pass) for any sample
+/// rate, using the same analog prototypes as libebur128
+fn k_weighting(sample_rate: u32) -> [Biquad; 2] {
+    let fs = sample_rate as f64;
+
+    let f0 = 1681.974450955533;
+    let gain_db = 3.999843853973347;
+    let q = 0.7071752369554196;
+    let k = (std::f64::consts::PI * f0 / fs).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = Biquad {
+        b: [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    };
+
+    let f0 = 38.13547087602444;
+    let q = 0.5003270373238773;
+    let k = (std::f64::consts::PI * f0 / fs).tan();
+    let a0 = 1.0 + k / q + k * k;
+    let high_pass = Biquad {
+        b: [1.0, -2.0, 1.0],
+        a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    };
+
+    [shelf, high_pass]
+}
+
+/// Integrated loudness of interleaved audio in LUFS
+///
+/// Returns None when the audio is too short for a single gating block or
+/// entirely below the absolute gate (silence).
+pub fn integrated_loudness(samples: &[f32], channels: usize, sample_rate: u32) -> Option<f64> {
+    let channels = channels.max(1);
+    let frames = samples.len() / channels;
+    let block = (BLOCK_SECONDS * sample_rate as f64) as usize;
+    let step = (STEP_SECONDS * sample_rate as f64) as usize;
+    if frames < block || step == 0 {
+        return None;
+    }
+
+    let [shelf, high_pass] = k_weighting(sample_rate);
+
+    // Running sum of squared K-weighted samples per channel, for O(1) block energies
+    let cumulative: Vec<Vec<f64>> = (0..channels)
+        .map(|channel| {
+            let channel_samples: Vec<f64> = samples
+                .iter()
+                .skip(channel)
+                .step_by(channels)
+                .map(|&s| s as f64)
+                .collect();
+            let weighted = high_pass.run(&shelf.run(&channel_samples));
+
+            let mut sums = Vec::with_capacity(weighted.len() + 1);
+            sums.push(0.0);
+            for value in weighted {
+                sums.push(sums.last().unwrap() + value * value);
+            }
+            sums
+        })
+        .collect();
+
+    // Surround channels would be weighted 1.41, but recordings here are mono or stereo
+    let block_powers: Vec<f64> = (0..=(frames - block) / step)
+        .map(|index| {
+            let start = index * step;
+            cumulative
+                .iter()
+                .map(|sums| (sums[start + block] - sums[start]) / block as f64)
+                .sum()
+        })
+        .collect();
+
+    let to_lufs = |power: f64| -0.691 + 10.0 * power.log10();
+    let mean = |powers: &[f64]| powers.iter().sum::<f64>() / powers.len() as f64;
+
+    let above_absolute: Vec<f64> = block_powers
+        .into_iter()
+        .filter(|&power| power > 0.0 && to_lufs(power) > ABSOLUTE_GATE_LUFS)
+        .collect();
+    if above_absolute.is_empty() {
+        return None;
+    }
+
+    let relative_gate = to_lufs(mean(&above_absolute)) + RELATIVE_GATE_LU;
+    let gated: Vec<f64> = above_absolute
+        .into_iter()
+        .filter(|&power| to_lufs(power) > relative_gate)
+        .collect();
+    if gated.is_empty() {
+        return None;
+    }
+
+    Some(to_lufs(mean(&gated)))
+}
+
+/// Scale interleaved audio in place to `target_lufs`
+///
+/// The gain is limited so peaks stay below -1 dBFS and quiet recordings are
+/// boosted by at most 30 dB.
+///
+/// # Returns
+/// * `Some(f64)` - Gain applied in dB
+/// * `None` - Audio was too short or silent to measure, and was left unchanged
+pub fn normalize(
+    samples: &mut [f32],
+    channels: usize,
+    sample_rate: u32,
+    target_lufs: f64,
+) -> Option<f64> {
+    let loudness = integrated_loudness(samples, channels, sample_rate)?;
+
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    let peak_limit_db = if peak > 0.0 {
+        20.0 * (MAX_PEAK as f64 / peak as f64).log10()
+    } else {
+        MAX_GAIN_DB
+    };
+
+    let gain_db = (target_lufs - loudness).min(peak_limit_db).min(MAX_GAIN_DB);
+    let gain = 10f64.powf(gain_db / 20.0) as f32;
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+
+    println!(
+        "[Loudness] Measured {:.1} LUFS, applied {:+.1} dB gain",
+        loudness, gain_db
+    );
+    Some(gain_db)
+}
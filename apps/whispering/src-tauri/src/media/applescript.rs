@@ -0,0 +1,249 @@
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2_foundation::NSString;
+use std::process::Command;
+
+use super::players::is_disabled;
+use super::MediaPlayer;
+
+/// How a player is asked whether it's playing and told to pause or play
+enum Control {
+    /// `player state`, `pause` and `play` (Music, Spotify, TV)
+    PlayerState,
+    /// VLC's `playing` property and its play/pause toggle
+    Vlc,
+    /// The app's own menu bar through System Events, for apps without a
+    /// scripting dictionary; the pause item only exists while playing
+    Menu {
+        menu: &'static str,
+        pause: &'static str,
+        play: &'static str,
+    },
+    /// Audio and video elements in every Safari tab
+    Safari,
+    /// Audio and video elements in every tab of a Chromium browser
+    Chromium,
+}
+
+/// A player that can be paused with AppleScript
+pub struct ScriptablePlayer {
+    pub bundle_id: &'static str,
+    pub name: &'static str,
+    control: Control,
+}
+
+/// Every player paused here when the Now Playing app isn't available
+///
+/// Browsers only answer with "Allow JavaScript from Apple Events" turned on in
+/// their developer settings, and menu control needs the accessibility
+/// permission text insertion already asks for.
+pub const PLAYERS: &[ScriptablePlayer] = &[
+    ScriptablePlayer {
+        bundle_id: "com.apple.Music",
+        name: "Music",
+        control: Control::PlayerState,
+    },
+    ScriptablePlayer {
+        bundle_id: "com.spotify.client",
+        name: "Spotify",
+        control: Control::PlayerState,
+    },
+    ScriptablePlayer {
+        bundle_id: "com.apple.TV",
+        name: "TV",
+        control: Control::PlayerState,
+    },
+    ScriptablePlayer {
+        bundle_id: "org.videolan.vlc",
+        name: "VLC",
+        control: Control::Vlc,
+    },
+    ScriptablePlayer {
+        bundle_id: "com.colliderli.iina",
+        name: "IINA",
+        control: Control::Menu {
+            menu: "Playback",
+            pause: "Pause",
+            play: "Resume",
+        },
+    },
+    ScriptablePlayer {
+        bundle_id: "com.apple.podcasts",
+        name: "Podcasts",
+        control: Control::Menu {
+            menu: "Controls",
+            pause: "Pause",
+            play: "Play",
+        },
+    },
+    ScriptablePlayer {
+        bundle_id: "com.apple.Safari",
+        name: "Safari",
+        control: Control::Safari,
+    },
+    ScriptablePlayer {
+        bundle_id: "com.google.Chrome",
+        name: "Google Chrome",
+        control: Control::Chromium,
+    },
+    ScriptablePlayer {
+        bundle_id: "com.brave.Browser",
+        name: "Brave",
+        control: Control::Chromium,
+    },
+    ScriptablePlayer {
+        bundle_id: "com.microsoft.edgemac",
+        name: "Microsoft Edge",
+        control: Control::Chromium,
+    },
+];
+
+/// Count the tab's playing media elements
+const COUNT_PLAYING_JS: &str =
+    "Array.from(document.querySelectorAll('audio,video')).filter(m => !m.paused).length";
+/// Pause the tab's playing media elements, marking them for `RESUME_JS`
+const PAUSE_JS: &str = "Array.from(document.querySelectorAll('audio,video')) \
+    .filter(m => !m.paused) \
+    .map(m => { m.dataset.whisperingPaused = '1'; m.pause(); }).length";
+/// Play only the media elements `PAUSE_JS` paused
+const RESUME_JS: &str = "Array.from(document.querySelectorAll('[data-whispering-paused]')) \
+    .map(m => { delete m.dataset.whisperingPaused; m.play(); }).length";
+
+impl Control {
+    fn is_playing(&self, bundle_id: &str) -> bool {
+        let script = match self {
+            Control::PlayerState => {
+                format!(r#"tell application id "{}" to get player state"#, bundle_id)
+            }
+            Control::Vlc => format!(r#"tell application id "{}" to get playing"#, bundle_id),
+            Control::Menu { menu, pause, .. } => menu_script(
+                bundle_id,
+                &format!(r#"exists menu item "{}" of menu "{}" of menu bar 1"#, pause, menu),
+            ),
+            Control::Safari | Control::Chromium => self.in_every_tab(bundle_id, COUNT_PLAYING_JS),
+        };
+        run(&script).is_ok_and(|answer| match self {
+            Control::PlayerState => answer == "playing",
+            Control::Vlc | Control::Menu { .. } => answer == "true",
+            Control::Safari | Control::Chromium => answer.parse::<f64>().is_ok_and(|n| n > 0.0),
+        })
+    }
+
+    fn pause(&self, bundle_id: &str) -> String {
+        match self {
+            Control::PlayerState => format!(r#"tell application id "{}" to pause"#, bundle_id),
+            Control::Vlc => {
+                format!(r#"tell application id "{}" to if playing then play"#, bundle_id)
+            }
+            Control::Menu { menu, pause, .. } => menu_script(
+                bundle_id,
+                &format!(r#"click menu item "{}" of menu "{}" of menu bar 1"#, pause, menu),
+            ),
+            Control::Safari | Control::Chromium => self.in_every_tab(bundle_id, PAUSE_JS),
+        }
+    }
+
+    fn resume(&self, bundle_id: &str) -> String {
+        match self {
+            Control::PlayerState => format!(r#"tell application id "{}" to play"#, bundle_id),
+            Control::Vlc => {
+                format!(r#"tell application id "{}" to if not playing then play"#, bundle_id)
+            }
+            Control::Menu { menu, play, .. } => menu_script(
+                bundle_id,
+                &format!(r#"click menu item "{}" of menu "{}" of menu bar 1"#, play, menu),
+            ),
+            Control::Safari | Control::Chromium => self.in_every_tab(bundle_id, RESUME_JS),
+        }
+    }
+
+    /// Run `javascript` in every tab of every window, summing the results
+    fn in_every_tab(&self, bundle_id: &str, javascript: &str) -> String {
+        let run_in_tab = match self {
+            Control::Safari => format!(r#"do JavaScript "{}" in aTab"#, javascript),
+            _ => format!(r#"execute aTab javascript "{}""#, javascript),
+        };
+        format!(
+            r#"tell application id "{bundle_id}"
+    set total to 0
+    repeat with aWindow in windows
+        repeat with aTab in tabs of aWindow
+            set total to total + ({run_in_tab})
+        end repeat
+    end repeat
+    return total
+end tell"#
+        )
+    }
+}
+
+fn menu_script(bundle_id: &str, command: &str) -> String {
+    format!(
+        r#"tell application "System Events"
+    tell (first process whose bundle identifier is "{}") to {}
+end tell"#,
+        bundle_id, command
+    )
+}
+
+/// Check without AppleScript, since telling an app that isn't running
+/// launches it
+fn is_running(bundle_id: &str) -> bool {
+    let Some(class) = AnyClass::get(c"NSRunningApplication") else {
+        return false;
+    };
+    unsafe {
+        let bundle_id = NSString::from_str(bundle_id);
+        let applications: Retained<AnyObject> =
+            msg_send![class, runningApplicationsWithBundleIdentifier: &*bundle_id];
+        let count: usize = msg_send![&*applications, count];
+        count > 0
+    }
+}
+
+fn run(script: &str) -> Result<String, String> {
+    let output = Command::new("osascript")
+        .args(["-e", script])
+        .output()
+        .map_err(|e| format!("Failed to run osascript: {}", e))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+/// Running, enabled players that report they're playing
+pub fn playing_players(disabled: &[String]) -> Vec<MediaPlayer> {
+    PLAYERS
+        .iter()
+        .filter(|player| !is_disabled(player.bundle_id, disabled))
+        .filter(|player| is_running(player.bundle_id))
+        .filter(|player| player.control.is_playing(player.bundle_id))
+        .map(|player| MediaPlayer {
+            id: player.bundle_id.to_string(),
+            name: player.name.to_string(),
+        })
+        .collect()
+}
+
+fn find(player: &MediaPlayer) -> Result<&'static ScriptablePlayer, String> {
+    if !is_running(&player.id) {
+        return Err("The player has quit".to_string());
+    }
+    PLAYERS
+        .iter()
+        .find(|known| known.bundle_id == player.id)
+        .ok_or_else(|| format!("{} can't be controlled with AppleScript", player.name))
+}
+
+pub fn pause(player: &MediaPlayer) -> Result<(), String> {
+    let known = find(player)?;
+    run(&known.control.pause(known.bundle_id)).map(|_| ())
+}
+
+pub fn resume(player: &MediaPlayer) -> Result<(), String> {
+    let known = find(player)?;
+    run(&known.control.resume(known.bundle_id)).map(|_| ())
+}
@@ -0,0 +1,244 @@
+#[cfg(target_os = "macos")]
+mod applescript;
+#[cfg(target_os = "linux")]
+mod mpris;
+#[cfg(target_os = "macos")]
+mod now_playing;
+mod players;
+#[cfg(windows)]
+mod smtc;
+mod volume;
+
+pub use players::{
+    get_disabled_media_players, get_media_players, set_disabled_media_players, DisabledPlayers,
+};
+pub use volume::{
+    duck_system_volume, mute_system_output, restore_system_output, restore_system_volume,
+    OutputMute, VolumeDucking,
+};
+
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// A media player that was playing - sent to frontend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaPlayer {
+    /// Platform handle used to resume it: an MPRIS bus name on Linux, the
+    /// app's AUMID on Windows, and on macOS either the Now Playing app or a
+    /// scriptable player's bundle id
+    pub id: String,
+    /// Display name ("Spotify", "Firefox")
+    pub name: String,
+}
+
+/// Players paused for the current recording, so only those are resumed
+/// afterwards and anything the user paused themselves stays paused
+pub struct MediaSession {
+    paused: Mutex<Vec<MediaPlayer>>,
+}
+
+impl MediaSession {
+    pub fn new() -> Self {
+        Self {
+            paused: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Pause every playing player that isn't disabled, remembering which ones
+    /// were paused
+    pub fn pause_active(&self, disabled: &[String]) -> Result<Vec<MediaPlayer>, String> {
+        let mut paused = Vec::new();
+        for player in platform::playing_players(disabled)? {
+            match platform::pause(&player) {
+                Ok(()) => paused.push(player),
+                Err(e) => eprintln!("[Media] Failed to pause {}: {}", player.name, e),
+            }
+        }
+        if !paused.is_empty() {
+            println!("[Media] Paused {} player(s) for recording", paused.len());
+        }
+        self.paused.lock().unwrap().extend(paused.iter().cloned());
+        Ok(paused)
+    }
+
+    /// Resume the players paused by `pause_active`
+    ///
+    /// A player that has since quit is skipped rather than failing the rest.
+    pub fn resume(&self) -> Vec<MediaPlayer> {
+        let paused = std::mem::take(&mut *self.paused.lock().unwrap());
+        paused
+            .into_iter()
+            .filter(|player| match platform::resume(player) {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("[Media] Failed to resume {}: {}", player.name, e);
+                    false
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for MediaSession {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pause whatever media is playing before recording, returning the players
+/// that were paused
+#[tauri::command]
+pub async fn pause_active_media(app_handle: AppHandle) -> Result<Vec<MediaPlayer>, String> {
+    // Player IPC blocks, and a hung player can take seconds to time out
+    tokio::task::spawn_blocking(move || {
+        let disabled = app_handle.state::<DisabledPlayers>().for_pausing(&app_handle);
+        app_handle.state::<MediaSession>().pause_active(&disabled)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// The players `pause_active_media` would pause right now, without pausing
+/// them
+#[tauri::command]
+pub async fn get_active_media_players(app_handle: AppHandle) -> Result<Vec<MediaPlayer>, String> {
+    tokio::task::spawn_blocking(move || {
+        let disabled = app_handle.state::<DisabledPlayers>().for_pausing(&app_handle);
+        platform::playing_players(&disabled)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Resume the players `pause_active_media` paused, returning the ones that
+/// were resumed
+#[tauri::command]
+pub async fn resume_media(app_handle: AppHandle) -> Result<Vec<MediaPlayer>, String> {
+    tokio::task::spawn_blocking(move || app_handle.state::<MediaSession>().resume())
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::players::is_disabled;
+    use super::{mpris, MediaPlayer};
+
+    pub use super::mpris::{pause, resume};
+
+    pub fn playing_players(disabled: &[String]) -> Result<Vec<MediaPlayer>, String> {
+        let mut players = mpris::playing_players()?;
+        players.retain(|player| !is_disabled(&player.id, disabled));
+        Ok(players)
+    }
+
+    /// Players are found on the session bus while they play, so none are
+    /// known up front
+    pub fn known_players() -> Vec<MediaPlayer> {
+        Vec::new()
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::players::is_disabled;
+    use super::{smtc, MediaPlayer};
+
+    pub use super::smtc::{pause, resume};
+
+    pub fn playing_players(disabled: &[String]) -> Result<Vec<MediaPlayer>, String> {
+        let mut players = smtc::playing_players()?;
+        players.retain(|player| !is_disabled(&player.id, disabled));
+        Ok(players)
+    }
+
+    /// Sessions only exist while apps publish them, so none are known up front
+    pub fn known_players() -> Vec<MediaPlayer> {
+        Vec::new()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::players::is_disabled;
+    use super::{applescript, now_playing, MediaPlayer};
+
+    /// The Now Playing app when MediaRemote reports one, which covers
+    /// browsers and any player using the system media controls in a single
+    /// command; otherwise the scriptable players, one AppleScript each
+    ///
+    /// A disabled Now Playing app falls through to the scriptable players, so
+    /// disabling a browser doesn't stop Music from being paused.
+    pub fn playing_players(disabled: &[String]) -> Result<Vec<MediaPlayer>, String> {
+        if !is_disabled(now_playing::PLAYER_ID, disabled) {
+            match now_playing::playing_player() {
+                Ok(Some(now_playing)) => {
+                    let app_disabled = now_playing
+                        .bundle_id
+                        .as_deref()
+                        .is_some_and(|bundle_id| is_disabled(bundle_id, disabled));
+                    if !app_disabled {
+                        return Ok(vec![now_playing.player]);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => eprintln!("[Media] Falling back to AppleScript: {}", e),
+            }
+        }
+        Ok(applescript::playing_players(disabled))
+    }
+
+    pub fn pause(player: &MediaPlayer) -> Result<(), String> {
+        if player.id == now_playing::PLAYER_ID {
+            now_playing::pause()
+        } else {
+            applescript::pause(player)
+        }
+    }
+
+    pub fn resume(player: &MediaPlayer) -> Result<(), String> {
+        if player.id == now_playing::PLAYER_ID {
+            now_playing::resume()
+        } else {
+            applescript::resume(player)
+        }
+    }
+
+    /// The Now Playing app, then every scriptable player
+    pub fn known_players() -> Vec<MediaPlayer> {
+        let now_playing = MediaPlayer {
+            id: now_playing::PLAYER_ID.to_string(),
+            name: "Now Playing app".to_string(),
+        };
+        std::iter::once(now_playing)
+            .chain(applescript::PLAYERS.iter().map(|player| MediaPlayer {
+                id: player.bundle_id.to_string(),
+                name: player.name.to_string(),
+            }))
+            .collect()
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    use super::MediaPlayer;
+
+    /// No player control here, so nothing is reported as playing
+    pub fn playing_players(_disabled: &[String]) -> Result<Vec<MediaPlayer>, String> {
+        Ok(Vec::new())
+    }
+
+    pub fn pause(_player: &MediaPlayer) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn resume(_player: &MediaPlayer) -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn known_players() -> Vec<MediaPlayer> {
+        Vec::new()
+    }
+}
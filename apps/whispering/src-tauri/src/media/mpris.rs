@@ -0,0 +1,71 @@
+use zbus::blocking::fdo::DBusProxy;
+use zbus::blocking::{Connection, Proxy};
+
+use super::MediaPlayer;
+
+/// Every MPRIS player owns a bus name under this prefix
+/// (`org.mpris.MediaPlayer2.spotify`, `org.mpris.MediaPlayer2.firefox.instance_1_42`)
+const BUS_NAME_PREFIX: &str = "org.mpris.MediaPlayer2.";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const ROOT_INTERFACE: &str = "org.mpris.MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+
+fn session_bus() -> Result<Connection, String> {
+    Connection::session().map_err(|e| format!("Failed to connect to the session bus: {}", e))
+}
+
+fn proxy<'a>(
+    connection: &'a Connection,
+    bus_name: &'a str,
+    interface: &'a str,
+) -> zbus::Result<Proxy<'a>> {
+    Proxy::new(connection, bus_name, OBJECT_PATH, interface)
+}
+
+/// The player's `Identity`, falling back to its bus name
+fn display_name(connection: &Connection, bus_name: &str) -> String {
+    proxy(connection, bus_name, ROOT_INTERFACE)
+        .and_then(|root| root.get_property::<String>("Identity"))
+        .unwrap_or_else(|_| bus_name.trim_start_matches(BUS_NAME_PREFIX).to_string())
+}
+
+/// MPRIS players on the session bus whose `PlaybackStatus` is `Playing`
+pub fn playing_players() -> Result<Vec<MediaPlayer>, String> {
+    let connection = session_bus()?;
+    let names = DBusProxy::new(&connection)
+        .and_then(|dbus| dbus.list_names())
+        .map_err(|e| format!("Failed to list D-Bus names: {}", e))?;
+
+    let mut playing = Vec::new();
+    for name in names {
+        let bus_name = name.as_str();
+        if !bus_name.starts_with(BUS_NAME_PREFIX) {
+            continue;
+        }
+        let status = proxy(&connection, bus_name, PLAYER_INTERFACE)
+            .and_then(|player| player.get_property::<String>("PlaybackStatus"));
+        if matches!(status.as_deref(), Ok("Playing")) {
+            playing.push(MediaPlayer {
+                id: bus_name.to_string(),
+                name: display_name(&connection, bus_name),
+            });
+        }
+    }
+    Ok(playing)
+}
+
+fn call(player: &MediaPlayer, method: &str) -> Result<(), String> {
+    let connection = session_bus()?;
+    proxy(&connection, &player.id, PLAYER_INTERFACE)
+        .and_then(|proxy| proxy.call_method(method, &()))
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+pub fn pause(player: &MediaPlayer) -> Result<(), String> {
+    call(player, "Pause")
+}
+
+pub fn resume(player: &MediaPlayer) -> Result<(), String> {
+    call(player, "Play")
+}
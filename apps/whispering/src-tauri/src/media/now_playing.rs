@@ -0,0 +1,153 @@
+use block2::{Block, RcBlock};
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2_foundation::NSString;
+use std::ffi::c_void;
+use std::sync::mpsc;
+use std::time::Duration;
+
+use super::MediaPlayer;
+
+/// `MediaPlayer::id` for the system's Now Playing app, which is controlled
+/// through MediaRemote rather than by name
+pub const PLAYER_ID: &str = "now-playing";
+
+const FRAMEWORK_PATH: &std::ffi::CStr =
+    c"/System/Library/PrivateFrameworks/MediaRemote.framework/MediaRemote";
+
+/// `MRMediaRemoteCommand` values
+const COMMAND_PLAY: u32 = 0;
+const COMMAND_PAUSE: u32 = 1;
+
+/// How long to wait for MediaRemote to answer a query before giving up on it
+const QUERY_TIMEOUT: Duration = Duration::from_secs(1);
+
+type SendCommand = unsafe extern "C" fn(command: u32, options: *const c_void) -> u8;
+type IsPlaying = unsafe extern "C" fn(queue: *mut c_void, completion: &Block<dyn Fn(u8)>);
+type ProcessId = unsafe extern "C" fn(queue: *mut c_void, completion: &Block<dyn Fn(i32)>);
+
+extern "C" {
+    fn dispatch_get_global_queue(identifier: isize, flags: usize) -> *mut c_void;
+}
+
+/// The MediaRemote functions used here
+///
+/// MediaRemote is a private framework, so it's loaded at runtime and anything
+/// missing just disables this path in favour of AppleScript.
+struct MediaRemote {
+    send_command: SendCommand,
+    is_playing: IsPlaying,
+    process_id: ProcessId,
+}
+
+impl MediaRemote {
+    fn load() -> Option<Self> {
+        unsafe {
+            // Never closed: the framework stays loaded for the life of the app
+            // once anything has linked it
+            let handle = libc::dlopen(FRAMEWORK_PATH.as_ptr(), libc::RTLD_LAZY);
+            if handle.is_null() {
+                return None;
+            }
+            let send_command = libc::dlsym(handle, c"MRMediaRemoteSendCommand".as_ptr());
+            let is_playing =
+                libc::dlsym(handle, c"MRMediaRemoteGetNowPlayingApplicationIsPlaying".as_ptr());
+            let process_id =
+                libc::dlsym(handle, c"MRMediaRemoteGetNowPlayingApplicationPID".as_ptr());
+            if send_command.is_null() || is_playing.is_null() || process_id.is_null() {
+                return None;
+            }
+            Some(Self {
+                send_command: std::mem::transmute::<*mut c_void, SendCommand>(send_command),
+                is_playing: std::mem::transmute::<*mut c_void, IsPlaying>(is_playing),
+                process_id: std::mem::transmute::<*mut c_void, ProcessId>(process_id),
+            })
+        }
+    }
+
+    fn is_playing(&self) -> Option<bool> {
+        let (playing_tx, playing_rx) = mpsc::channel();
+        let handler = RcBlock::new(move |playing: u8| {
+            let _ = playing_tx.send(playing != 0);
+        });
+        unsafe { (self.is_playing)(dispatch_get_global_queue(0, 0), &*handler) };
+        playing_rx.recv_timeout(QUERY_TIMEOUT).ok()
+    }
+
+    fn process_id(&self) -> Option<i32> {
+        let (pid_tx, pid_rx) = mpsc::channel();
+        let handler = RcBlock::new(move |pid: i32| {
+            let _ = pid_tx.send(pid);
+        });
+        unsafe { (self.process_id)(dispatch_get_global_queue(0, 0), &*handler) };
+        pid_rx.recv_timeout(QUERY_TIMEOUT).ok().filter(|pid| *pid > 0)
+    }
+
+    fn send(&self, command: u32) -> Result<(), String> {
+        let accepted = unsafe { (self.send_command)(command, std::ptr::null()) };
+        if accepted != 0 {
+            Ok(())
+        } else {
+            Err("The Now Playing app rejected the command".to_string())
+        }
+    }
+}
+
+fn media_remote() -> Result<MediaRemote, String> {
+    MediaRemote::load().ok_or_else(|| "MediaRemote is unavailable".to_string())
+}
+
+/// The Now Playing app's name and bundle id, from its process
+fn application(pid: i32) -> Option<(String, Option<String>)> {
+    let class = AnyClass::get(c"NSRunningApplication")?;
+    unsafe {
+        let application: Option<Retained<AnyObject>> =
+            msg_send![class, runningApplicationWithProcessIdentifier: pid];
+        let application = application?;
+        let name: Option<Retained<NSString>> = msg_send![&*application, localizedName];
+        let bundle_id: Option<Retained<NSString>> = msg_send![&*application, bundleIdentifier];
+        Some((name?.to_string(), bundle_id.map(|id| id.to_string())))
+    }
+}
+
+/// The app the system reports as Now Playing
+pub struct NowPlaying {
+    pub player: MediaPlayer,
+    pub bundle_id: Option<String>,
+}
+
+/// Whatever the system reports as Now Playing (a browser tab, VLC, Podcasts,
+/// Music), if it's playing
+///
+/// `Err` means MediaRemote can't be used at all. Recent macOS releases also
+/// report nothing playing to apps outside Apple's allowlist, so `None` isn't
+/// conclusive either.
+pub fn playing_player() -> Result<Option<NowPlaying>, String> {
+    let media_remote = media_remote()?;
+    let playing = media_remote
+        .is_playing()
+        .ok_or_else(|| "MediaRemote did not answer".to_string())?;
+    if !playing {
+        return Ok(None);
+    }
+    let (name, bundle_id) = media_remote
+        .process_id()
+        .and_then(application)
+        .unwrap_or_else(|| ("Now Playing".to_string(), None));
+    Ok(Some(NowPlaying {
+        player: MediaPlayer {
+            id: PLAYER_ID.to_string(),
+            name,
+        },
+        bundle_id,
+    }))
+}
+
+pub fn pause() -> Result<(), String> {
+    media_remote()?.send(COMMAND_PAUSE)
+}
+
+pub fn resume() -> Result<(), String> {
+    media_remote()?.send(COMMAND_PLAY)
+}
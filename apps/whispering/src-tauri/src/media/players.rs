@@ -0,0 +1,141 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use super::platform;
+
+/// A player the app knows how to pause, and whether the user allows it - sent
+/// to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnownPlayer {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+}
+
+/// Players never paused while recording, by `MediaPlayer::id`
+///
+/// Persisted as a JSON array in the app config directory. Known players are
+/// listed by `get_media_players`; on Linux and Windows players are only found
+/// while they play, so ids from `pause_active_media` results work too.
+pub struct DisabledPlayers {
+    ids: Mutex<Option<Vec<String>>>,
+}
+
+impl DisabledPlayers {
+    pub fn new() -> Self {
+        Self {
+            ids: Mutex::new(None),
+        }
+    }
+
+    /// Get the disabled player ids, reading them from disk on first use
+    pub fn get(&self, app: &AppHandle) -> Result<Vec<String>, String> {
+        let mut ids = self.ids.lock().unwrap();
+        if let Some(ids) = ids.as_ref() {
+            return Ok(ids.clone());
+        }
+
+        let path = disabled_players_path(app)?;
+        let loaded: Vec<String> = if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read disabled media players: {}", e))?;
+            serde_json::from_str(&contents)
+                .map_err(|e| format!("Failed to parse disabled media players: {}", e))?
+        } else {
+            Vec::new()
+        };
+        Ok(ids.insert(loaded).clone())
+    }
+
+    /// Replace the disabled player ids, dropping blanks and duplicates
+    pub fn set(&self, app: &AppHandle, ids: Vec<String>) -> Result<Vec<String>, String> {
+        let mut cleaned: Vec<String> = Vec::with_capacity(ids.len());
+        for id in ids {
+            let id = id.trim();
+            if !id.is_empty() && !cleaned.iter().any(|kept| kept == id) {
+                cleaned.push(id.to_string());
+            }
+        }
+
+        let path = disabled_players_path(app)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let contents = serde_json::to_string_pretty(&cleaned)
+            .map_err(|e| format!("Failed to serialize disabled media players: {}", e))?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to save disabled media players: {}", e))?;
+
+        *self.ids.lock().unwrap() = Some(cleaned.clone());
+        Ok(cleaned)
+    }
+
+    /// The disabled ids for pausing; a list that can't be read disables
+    /// nothing, but is logged
+    pub fn for_pausing(&self, app: &AppHandle) -> Vec<String> {
+        self.get(app).unwrap_or_else(|e| {
+            eprintln!("[Media] Ignoring disabled media players: {}", e);
+            Vec::new()
+        })
+    }
+}
+
+impl Default for DisabledPlayers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn disabled_players_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join("disabled-media-players.json"))
+        .map_err(|e| format!("Failed to resolve config directory: {}", e))
+}
+
+/// Whether `id` is one of the `disabled` player ids
+pub fn is_disabled(id: &str, disabled: &[String]) -> bool {
+    disabled.iter().any(|entry| entry == id)
+}
+
+/// List the players this platform knows how to pause, with whether each is
+/// enabled
+#[tauri::command]
+pub fn get_media_players(
+    app_handle: AppHandle,
+    disabled_players: tauri::State<'_, DisabledPlayers>,
+) -> Result<Vec<KnownPlayer>, String> {
+    let disabled = disabled_players.get(&app_handle)?;
+    Ok(platform::known_players()
+        .into_iter()
+        .map(|player| KnownPlayer {
+            enabled: !is_disabled(&player.id, &disabled),
+            id: player.id,
+            name: player.name,
+        })
+        .collect())
+}
+
+/// Get the ids of the players never paused while recording
+#[tauri::command]
+pub fn get_disabled_media_players(
+    app_handle: AppHandle,
+    disabled_players: tauri::State<'_, DisabledPlayers>,
+) -> Result<Vec<String>, String> {
+    disabled_players.get(&app_handle)
+}
+
+/// Replace the players never paused while recording, returning the ids that
+/// were stored
+#[tauri::command]
+pub fn set_disabled_media_players(
+    ids: Vec<String>,
+    app_handle: AppHandle,
+    disabled_players: tauri::State<'_, DisabledPlayers>,
+) -> Result<Vec<String>, String> {
+    disabled_players.set(&app_handle, ids)
+}
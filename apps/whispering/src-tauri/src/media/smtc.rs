@@ -0,0 +1,102 @@
+use windows::Media::Control::{
+    GlobalSystemMediaTransportControlsSession, GlobalSystemMediaTransportControlsSessionManager,
+    GlobalSystemMediaTransportControlsSessionPlaybackStatus,
+};
+use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_MULTITHREADED};
+
+use super::MediaPlayer;
+
+fn to_error(e: windows::core::Error) -> String {
+    format!("Media transport controls failed: {}", e)
+}
+
+/// Run `f` with COM initialized on this (blocking pool) thread
+pub fn with_com<T>(f: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+    let initialized = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) }.is_ok();
+    let result = f();
+    if initialized {
+        unsafe { CoUninitialize() };
+    }
+    result
+}
+
+/// Every app that publishes media sessions to the system (the media flyout):
+/// Spotify, browsers, the Media Player app and most video players
+fn sessions() -> Result<Vec<GlobalSystemMediaTransportControlsSession>, String> {
+    let manager = GlobalSystemMediaTransportControlsSessionManager::RequestAsync()
+        .and_then(|request| request.get())
+        .map_err(to_error)?;
+    let sessions = manager.GetSessions().map_err(to_error)?;
+    let count = sessions.Size().map_err(to_error)?;
+    (0..count)
+        .map(|index| sessions.GetAt(index).map_err(to_error))
+        .collect()
+}
+
+fn is_playing(session: &GlobalSystemMediaTransportControlsSession) -> bool {
+    session
+        .GetPlaybackInfo()
+        .and_then(|info| info.PlaybackStatus())
+        .is_ok_and(|status| {
+            status == GlobalSystemMediaTransportControlsSessionPlaybackStatus::Playing
+        })
+}
+
+/// The app's AUMID, e.g. `Spotify.exe` or `Microsoft.ZuneMusic_8wekyb3d8bbwe!Microsoft.ZuneMusic`
+fn app_id(session: &GlobalSystemMediaTransportControlsSession) -> Option<String> {
+    session
+        .SourceAppUserModelId()
+        .ok()
+        .map(|id| id.to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// A readable name from an AUMID: the executable stem or the package name
+fn display_name(app_id: &str) -> String {
+    let name = app_id.split('!').next_back().unwrap_or(app_id);
+    let name = name.trim_end_matches(".exe").trim_end_matches(".EXE");
+    name.rsplit('.').next().unwrap_or(name).to_string()
+}
+
+pub fn playing_players() -> Result<Vec<MediaPlayer>, String> {
+    with_com(|| {
+        Ok(sessions()?
+            .iter()
+            .filter(|session| is_playing(session))
+            .filter_map(app_id)
+            .map(|id| MediaPlayer {
+                name: display_name(&id),
+                id,
+            })
+            .collect())
+    })
+}
+
+/// Find the player's session again, since sessions can't cross threads
+fn control(player: &MediaPlayer, play: bool) -> Result<(), String> {
+    with_com(|| {
+        let session = sessions()?
+            .into_iter()
+            .find(|session| app_id(session).as_deref() == Some(player.id.as_str()))
+            .ok_or_else(|| "The player's media session has ended".to_string())?;
+        let request = if play {
+            session.TryPlayAsync()
+        } else {
+            session.TryPauseAsync()
+        };
+        let accepted = request.and_then(|request| request.get()).map_err(to_error)?;
+        if accepted {
+            Ok(())
+        } else {
+            Err("The player rejected the request".to_string())
+        }
+    })
+}
+
+pub fn pause(player: &MediaPlayer) -> Result<(), String> {
+    control(player, false)
+}
+
+pub fn resume(player: &MediaPlayer) -> Result<(), String> {
+    control(player, true)
+}
@@ -0,0 +1,472 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Restore is skipped when the volume has moved further than this from the
+/// ducked level, since the user changed it themselves
+const VOLUME_TOLERANCE: f32 = 0.02;
+
+/// The output volume before and after ducking, both from 0.0 to 1.0
+#[derive(Debug, Clone, Copy)]
+struct Ducked {
+    original: f32,
+    ducked: f32,
+}
+
+/// System output volume lowered for the current recording
+pub struct VolumeDucking {
+    ducked: Mutex<Option<Ducked>>,
+}
+
+impl VolumeDucking {
+    pub fn new() -> Self {
+        Self {
+            ducked: Mutex::new(None),
+        }
+    }
+
+    /// Lower the default output device's volume by `amount`, a fraction of
+    /// the current volume
+    ///
+    /// Ducking again before restoring keeps the first original volume, so
+    /// repeated calls don't compound.
+    pub fn duck(&self, amount: f32) -> Result<(), String> {
+        if !(0.0..=1.0).contains(&amount) {
+            return Err(format!("Duck amount must be between 0 and 1, got {}", amount));
+        }
+        let mut ducked = self.ducked.lock().unwrap();
+        let original = match *ducked {
+            Some(previous) => previous.original,
+            None => platform::output_volume()?,
+        };
+        let level = original * (1.0 - amount);
+        platform::set_output_volume(level)?;
+        println!("[Media] Ducked output volume from {:.2} to {:.2}", original, level);
+        *ducked = Some(Ducked {
+            original,
+            ducked: level,
+        });
+        Ok(())
+    }
+
+    /// Put the volume back to what it was before `duck`
+    ///
+    /// Left alone if the user has changed the volume since, and a no-op when
+    /// nothing was ducked.
+    pub fn restore(&self) -> Result<(), String> {
+        let Some(ducked) = self.ducked.lock().unwrap().take() else {
+            return Ok(());
+        };
+        let current = platform::output_volume()?;
+        if (current - ducked.ducked).abs() > VOLUME_TOLERANCE {
+            println!("[Media] Output volume was changed while ducked, leaving it");
+            return Ok(());
+        }
+        platform::set_output_volume(ducked.original)
+    }
+}
+
+impl Default for VolumeDucking {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// System output muted for the current recording
+///
+/// Holds whether the output was already muted, so restoring never unmutes
+/// speakers the user muted themselves.
+pub struct OutputMute {
+    was_muted: Mutex<Option<bool>>,
+}
+
+impl OutputMute {
+    pub fn new() -> Self {
+        Self {
+            was_muted: Mutex::new(None),
+        }
+    }
+
+    /// Mute the default output device
+    pub fn mute(&self) -> Result<(), String> {
+        let mut was_muted = self.was_muted.lock().unwrap();
+        if was_muted.is_none() {
+            *was_muted = Some(platform::is_output_muted()?);
+        }
+        platform::set_output_muted(true)?;
+        println!("[Media] Muted system output");
+        Ok(())
+    }
+
+    /// Unmute the output `mute` muted; a no-op when nothing was muted
+    pub fn restore(&self) -> Result<(), String> {
+        match self.was_muted.lock().unwrap().take() {
+            Some(false) => platform::set_output_muted(false),
+            Some(true) | None => Ok(()),
+        }
+    }
+}
+
+impl Default for OutputMute {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lower the system output volume while recording, by `amount` (0.0 to 1.0)
+/// of the current volume
+#[tauri::command]
+pub async fn duck_system_volume(app_handle: AppHandle, amount: f32) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || app_handle.state::<VolumeDucking>().duck(amount))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Restore the system output volume `duck_system_volume` lowered
+#[tauri::command]
+pub async fn restore_system_volume(app_handle: AppHandle) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || app_handle.state::<VolumeDucking>().restore())
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Mute the system output while recording
+#[tauri::command]
+pub async fn mute_system_output(app_handle: AppHandle) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || app_handle.state::<OutputMute>().mute())
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Unmute the system output `mute_system_output` muted
+#[tauri::command]
+pub async fn restore_system_output(app_handle: AppHandle) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || app_handle.state::<OutputMute>().restore())
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::c_void;
+
+    #[link(name = "CoreAudio", kind = "framework")]
+    extern "C" {
+        fn AudioObjectHasProperty(object: u32, address: *const PropertyAddress) -> u8;
+        fn AudioObjectGetPropertyData(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: *mut u32,
+            data: *mut c_void,
+        ) -> i32;
+        fn AudioObjectSetPropertyData(
+            object: u32,
+            address: *const PropertyAddress,
+            qualifier_size: u32,
+            qualifier: *const c_void,
+            data_size: u32,
+            data: *const c_void,
+        ) -> i32;
+    }
+
+    /// `AudioObjectPropertyAddress`
+    #[repr(C)]
+    struct PropertyAddress {
+        selector: u32,
+        scope: u32,
+        element: u32,
+    }
+
+    const fn four_char_code(code: &[u8; 4]) -> u32 {
+        u32::from_be_bytes(*code)
+    }
+
+    const SYSTEM_OBJECT: u32 = 1;
+    const DEFAULT_OUTPUT_DEVICE: u32 = four_char_code(b"dOut");
+    const VOLUME_SCALAR: u32 = four_char_code(b"volm");
+    const MUTE: u32 = four_char_code(b"mute");
+    const SCOPE_GLOBAL: u32 = four_char_code(b"glob");
+    const SCOPE_OUTPUT: u32 = four_char_code(b"outp");
+    const ELEMENT_MAIN: u32 = 0;
+
+    /// The main element when the device has a main volume, otherwise the
+    /// left and right channels
+    const VOLUME_ELEMENTS: [&[u32]; 2] = [&[ELEMENT_MAIN], &[1, 2]];
+
+    fn address(selector: u32, scope: u32, element: u32) -> PropertyAddress {
+        PropertyAddress {
+            selector,
+            scope,
+            element,
+        }
+    }
+
+    fn has_property(object: u32, address: &PropertyAddress) -> bool {
+        unsafe { AudioObjectHasProperty(object, address) != 0 }
+    }
+
+    /// Read a fixed-size property
+    ///
+    /// # Safety
+    /// `T` must match the property's data type.
+    unsafe fn get<T: Default>(object: u32, address: &PropertyAddress) -> Result<T, String> {
+        let mut value = T::default();
+        let mut size = std::mem::size_of::<T>() as u32;
+        let status = AudioObjectGetPropertyData(
+            object,
+            address,
+            0,
+            std::ptr::null(),
+            &mut size,
+            &mut value as *mut T as *mut c_void,
+        );
+        if status == 0 {
+            Ok(value)
+        } else {
+            Err(format!("CoreAudio error {}", status))
+        }
+    }
+
+    /// Write a fixed-size property
+    ///
+    /// # Safety
+    /// `T` must match the property's data type.
+    unsafe fn set<T>(object: u32, address: &PropertyAddress, value: T) -> Result<(), String> {
+        let status = AudioObjectSetPropertyData(
+            object,
+            address,
+            0,
+            std::ptr::null(),
+            std::mem::size_of::<T>() as u32,
+            &value as *const T as *const c_void,
+        );
+        if status == 0 {
+            Ok(())
+        } else {
+            Err(format!("CoreAudio error {}", status))
+        }
+    }
+
+    fn default_output_device() -> Result<u32, String> {
+        let address = address(DEFAULT_OUTPUT_DEVICE, SCOPE_GLOBAL, ELEMENT_MAIN);
+        let device: u32 = unsafe { get(SYSTEM_OBJECT, &address)? };
+        if device == 0 {
+            return Err("No output device".to_string());
+        }
+        Ok(device)
+    }
+
+    /// The volume properties this device exposes
+    fn volume_addresses(device: u32) -> Result<Vec<PropertyAddress>, String> {
+        VOLUME_ELEMENTS
+            .iter()
+            .map(|elements| {
+                elements
+                    .iter()
+                    .map(|&element| address(VOLUME_SCALAR, SCOPE_OUTPUT, element))
+                    .collect::<Vec<_>>()
+            })
+            .find(|addresses| addresses.iter().all(|address| has_property(device, address)))
+            .ok_or_else(|| "The output device has no volume control".to_string())
+    }
+
+    pub fn output_volume() -> Result<f32, String> {
+        let device = default_output_device()?;
+        let addresses = volume_addresses(device)?;
+        let mut total = 0.0;
+        for address in &addresses {
+            total += unsafe { get::<f32>(device, address)? };
+        }
+        Ok(total / addresses.len() as f32)
+    }
+
+    pub fn set_output_volume(level: f32) -> Result<(), String> {
+        let device = default_output_device()?;
+        for address in volume_addresses(device)? {
+            unsafe { set(device, &address, level.clamp(0.0, 1.0))? };
+        }
+        Ok(())
+    }
+
+    fn mute_address(device: u32) -> Result<PropertyAddress, String> {
+        let address = address(MUTE, SCOPE_OUTPUT, ELEMENT_MAIN);
+        if has_property(device, &address) {
+            Ok(address)
+        } else {
+            Err("The output device can't be muted".to_string())
+        }
+    }
+
+    pub fn is_output_muted() -> Result<bool, String> {
+        let device = default_output_device()?;
+        let muted: u32 = unsafe { get(device, &mute_address(device)?)? };
+        Ok(muted != 0)
+    }
+
+    pub fn set_output_muted(muted: bool) -> Result<(), String> {
+        let device = default_output_device()?;
+        unsafe { set(device, &mute_address(device)?, u32::from(muted)) }
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume;
+    use windows::Win32::Media::Audio::{eConsole, eRender, IMMDeviceEnumerator, MMDeviceEnumerator};
+    use windows::Win32::System::Com::{CoCreateInstance, CLSCTX_ALL};
+
+    use super::super::smtc::with_com;
+
+    fn to_error(e: windows::core::Error) -> String {
+        format!("Endpoint volume failed: {}", e)
+    }
+
+    /// The default playback device's volume control
+    fn endpoint_volume() -> Result<IAudioEndpointVolume, String> {
+        unsafe {
+            let enumerator: IMMDeviceEnumerator =
+                CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(to_error)?;
+            let device = enumerator
+                .GetDefaultAudioEndpoint(eRender, eConsole)
+                .map_err(to_error)?;
+            device.Activate(CLSCTX_ALL, None).map_err(to_error)
+        }
+    }
+
+    pub fn output_volume() -> Result<f32, String> {
+        with_com(|| unsafe {
+            endpoint_volume()?
+                .GetMasterVolumeLevelScalar()
+                .map_err(to_error)
+        })
+    }
+
+    pub fn set_output_volume(level: f32) -> Result<(), String> {
+        with_com(|| unsafe {
+            endpoint_volume()?
+                .SetMasterVolumeLevelScalar(level.clamp(0.0, 1.0), std::ptr::null())
+                .map_err(to_error)
+        })
+    }
+
+    pub fn is_output_muted() -> Result<bool, String> {
+        with_com(|| unsafe {
+            endpoint_volume()?
+                .GetMute()
+                .map(|muted| muted.as_bool())
+                .map_err(to_error)
+        })
+    }
+
+    pub fn set_output_muted(muted: bool) -> Result<(), String> {
+        with_com(|| unsafe {
+            endpoint_volume()?
+                .SetMute(muted, std::ptr::null())
+                .map_err(to_error)
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::io::ErrorKind;
+    use std::process::Command;
+
+    /// Run a mixer command, with `Ok(None)` when the tool isn't installed
+    fn run(program: &str, args: &[&str]) -> Result<Option<String>, String> {
+        let output = match Command::new(program).args(args).output() {
+            Ok(output) => output,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("Failed to run {}: {}", program, e)),
+        };
+        if !output.status.success() {
+            return Err(format!(
+                "{} failed: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()))
+    }
+
+    fn no_mixer() -> String {
+        "Neither wpctl (PipeWire) nor pactl (PulseAudio) is installed".to_string()
+    }
+
+    /// PipeWire's `wpctl`, falling back to `pactl` for PulseAudio (which
+    /// PipeWire also answers through pipewire-pulse)
+    pub fn output_volume() -> Result<f32, String> {
+        // "Volume: 0.40", with " [MUTED]" appended when muted
+        if let Some(output) = run("wpctl", &["get-volume", "@DEFAULT_AUDIO_SINK@"])? {
+            return output
+                .split_whitespace()
+                .nth(1)
+                .and_then(|volume| volume.parse().ok())
+                .ok_or_else(|| format!("Unexpected wpctl output: {}", output.trim()));
+        }
+        // "Volume: front-left: 26214 /  40% / -23.88 dB,   front-right: ..."
+        if let Some(output) = run("pactl", &["get-sink-volume", "@DEFAULT_SINK@"])? {
+            return output
+                .split_whitespace()
+                .find_map(|word| word.strip_suffix('%')?.parse::<f32>().ok())
+                .map(|percent| percent / 100.0)
+                .ok_or_else(|| format!("Unexpected pactl output: {}", output.trim()));
+        }
+        Err(no_mixer())
+    }
+
+    pub fn set_output_volume(level: f32) -> Result<(), String> {
+        let level = level.clamp(0.0, 1.0);
+        let volume = format!("{:.3}", level);
+        if run("wpctl", &["set-volume", "@DEFAULT_AUDIO_SINK@", &volume])?.is_some() {
+            return Ok(());
+        }
+        let percent = format!("{}%", (level * 100.0).round());
+        if run("pactl", &["set-sink-volume", "@DEFAULT_SINK@", &percent])?.is_some() {
+            return Ok(());
+        }
+        Err(no_mixer())
+    }
+
+    pub fn is_output_muted() -> Result<bool, String> {
+        if let Some(output) = run("wpctl", &["get-volume", "@DEFAULT_AUDIO_SINK@"])? {
+            return Ok(output.contains("[MUTED]"));
+        }
+        // "Mute: yes"
+        if let Some(output) = run("pactl", &["get-sink-mute", "@DEFAULT_SINK@"])? {
+            return Ok(output.trim().ends_with("yes"));
+        }
+        Err(no_mixer())
+    }
+
+    pub fn set_output_muted(muted: bool) -> Result<(), String> {
+        let muted = if muted { "1" } else { "0" };
+        if run("wpctl", &["set-mute", "@DEFAULT_AUDIO_SINK@", muted])?.is_some() {
+            return Ok(());
+        }
+        if run("pactl", &["set-sink-mute", "@DEFAULT_SINK@", muted])?.is_some() {
+            return Ok(());
+        }
+        Err(no_mixer())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    pub fn output_volume() -> Result<f32, String> {
+        Err("Output volume control is not supported on this platform".to_string())
+    }
+
+    pub fn set_output_volume(_level: f32) -> Result<(), String> {
+        Err("Output volume control is not supported on this platform".to_string())
+    }
+
+    pub fn is_output_muted() -> Result<bool, String> {
+        Err("Output volume control is not supported on this platform".to_string())
+    }
+
+    pub fn set_output_muted(_muted: bool) -> Result<(), String> {
+        Err("Output volume control is not supported on this platform".to_string())
+    }
+}
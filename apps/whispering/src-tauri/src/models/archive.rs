@@ -0,0 +1,74 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use super::cache::models_dir;
+use crate::transcription::is_vosk_model;
+
+/// Unpack a downloaded model archive (e.g. a Vosk `.zip`) next to it and
+/// delete the archive.
+///
+/// Vosk publishes each model as a zip containing a single top-level folder;
+/// that folder is what gets passed to `transcribe_audio_vosk`. Only archives
+/// inside the app's models directory are accepted.
+///
+/// # Returns
+/// * `Ok(String)` - Path of the extracted model folder
+/// * `Err(String)` - Error message if the archive is outside the models directory or invalid
+#[tauri::command]
+pub async fn extract_model_archive(
+    archive_path: String,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let root = models_dir(&app_handle)?
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve models directory: {}", e))?;
+    let archive = PathBuf::from(&archive_path)
+        .canonicalize()
+        .map_err(|e| format!("Archive not found at {}: {}", archive_path, e))?;
+    if !archive.starts_with(&root) {
+        return Err(format!("{} is not inside the models directory", archive_path));
+    }
+
+    tokio::task::spawn_blocking(move || extract(&archive))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+fn extract(archive_path: &Path) -> Result<String, String> {
+    let destination = archive_path
+        .parent()
+        .ok_or_else(|| format!("Invalid archive path {:?}", archive_path))?;
+
+    let file = fs::File::open(archive_path)
+        .map_err(|e| format!("Failed to open {:?}: {}", archive_path, e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Failed to read archive: {}", e))?;
+
+    // The folder the archive unpacks into, e.g. "vosk-model-small-en-us-0.15"
+    let top_level = archive
+        .file_names()
+        .filter_map(|name| name.split('/').next())
+        .find(|name| !name.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| "Archive is empty".to_string())?;
+
+    archive
+        .extract(destination)
+        .map_err(|e| format!("Failed to extract archive: {}", e))?;
+
+    let model_path = destination.join(&top_level);
+    if !is_vosk_model(&model_path) {
+        eprintln!(
+            "[Model Archive] {:?} doesn't look like a Vosk model, keeping it anyway",
+            model_path
+        );
+    }
+
+    if let Err(e) = fs::remove_file(archive_path) {
+        eprintln!("[Model Archive] Failed to delete {:?}: {}", archive_path, e);
+    }
+
+    println!("[Model Archive] Extracted {:?}", model_path);
+    Ok(model_path.to_string_lossy().to_string())
+}
@@ -0,0 +1,240 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+
+use crate::transcription::{ggml, ModelManager};
+
+/// Engines with a model folder under `{appDataDir}/models/`
+const ENGINE_DIRS: [&str; 3] = ["whisper", "parakeet", "vosk"];
+
+/// Records when each model was last used, keyed by canonical path
+const USAGE_FILE: &str = "usage.json";
+
+/// Serializes read-modify-write cycles of the usage file, which concurrent
+/// transcriptions would otherwise clobber
+static USAGE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A downloaded model on disk - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalModel {
+    pub name: String,
+    pub engine: String,
+    pub path: String,
+    pub size_bytes: u64,
+    /// Weight quantization (e.g. "q5_0", "f16", "int8")
+    pub quantization: Option<String>,
+    /// Unix time in milliseconds the model was last loaded for transcription
+    pub last_used_ms: Option<u64>,
+}
+
+/// Root folder all downloaded models live in
+pub fn models_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("models"))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+/// Remember that a model was just used so `list_local_models` can report it.
+/// Failures are only logged since this is purely informational.
+pub fn record_model_use(app: &AppHandle, model_path: &str) {
+    let result = models_dir(app).and_then(|dir| {
+        let _guard = USAGE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut usage = read_usage(&dir);
+        usage.insert(usage_key(Path::new(model_path)), now_ms());
+        write_usage(&dir, &usage)
+    });
+
+    if let Err(e) = result {
+        eprintln!("[Model Cache] Failed to record model use: {}", e);
+    }
+}
+
+/// Lists every downloaded model with its size, quantization, and last use.
+///
+/// Whisper models are single `.bin` files, Parakeet and Vosk models are
+/// directories, so directory sizes are summed recursively.
+///
+/// # Returns
+/// * `Ok(Vec<LocalModel>)` - Models sorted by size, largest first
+/// * `Err(String)` - Error message if the models directory can't be read
+#[tauri::command]
+pub async fn list_local_models(app_handle: AppHandle) -> Result<Vec<LocalModel>, String> {
+    let root = models_dir(&app_handle)?;
+
+    tokio::task::spawn_blocking(move || {
+        let usage = read_usage(&root);
+        let mut models = Vec::new();
+
+        for engine in ENGINE_DIRS {
+            let engine_dir = root.join(engine);
+            if !engine_dir.is_dir() {
+                continue;
+            }
+
+            let entries = fs::read_dir(&engine_dir)
+                .map_err(|e| format!("Failed to read {:?}: {}", engine_dir, e))?;
+
+            for entry in entries.filter_map(|entry| entry.ok()) {
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !is_model_name(&name) {
+                    continue;
+                }
+
+                models.push(LocalModel {
+                    quantization: detect_quantization(&path),
+                    last_used_ms: usage.get(&usage_key(&path)).copied(),
+                    size_bytes: size_on_disk(&path),
+                    engine: engine.to_string(),
+                    path: path.to_string_lossy().to_string(),
+                    name,
+                });
+            }
+        }
+
+        models.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes));
+        Ok::<Vec<LocalModel>, String>(models)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Deletes a downloaded model (file or directory) to reclaim disk space.
+///
+/// Only models listed by `list_local_models` are accepted: the path must
+/// resolve to a model directly inside one of the engine folders, so engine
+/// folders, the usage file and anything outside the models directory are
+/// refused. If the model is currently loaded it is unloaded first so the
+/// memory is freed too.
+#[tauri::command]
+pub async fn delete_local_model(
+    model_path: String,
+    app_handle: AppHandle,
+    model_manager: State<'_, ModelManager>,
+) -> Result<(), String> {
+    let root = models_dir(&app_handle)?;
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve models directory: {}", e))?;
+    let path = PathBuf::from(&model_path)
+        .canonicalize()
+        .map_err(|e| format!("Model not found at {}: {}", model_path, e))?;
+
+    let in_engine_dir = path.parent().map_or(false, |parent| {
+        ENGINE_DIRS.iter().any(|engine| parent == root.join(engine))
+    });
+    let is_model = path
+        .file_name()
+        .map_or(false, |name| is_model_name(&name.to_string_lossy()));
+    if !in_engine_dir || !is_model {
+        return Err(format!("{} is not a downloaded model", model_path));
+    }
+
+    let is_loaded = model_manager.loaded_model().map_or(false, |loaded| {
+        PathBuf::from(loaded.model_path).canonicalize().ok().as_ref() == Some(&path)
+    });
+    if is_loaded {
+        model_manager.unload_model();
+    }
+
+    let removed = if path.is_dir() {
+        fs::remove_dir_all(&path)
+    } else {
+        fs::remove_file(&path)
+    };
+    removed.map_err(|e| format!("Failed to delete {}: {}", model_path, e))?;
+
+    let _guard = USAGE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut usage = read_usage(&root);
+    if usage.remove(&path.to_string_lossy().to_string()).is_some() {
+        let _ = write_usage(&root, &usage);
+    }
+
+    println!("[Model Cache] Deleted {}", model_path);
+    Ok(())
+}
+
+/// Whether an entry in an engine folder is a model, rather than an
+/// in-progress download, an archive awaiting extraction, or a CoreML encoder
+/// that belongs to a GGML model
+fn is_model_name(name: &str) -> bool {
+    !(name.ends_with(".part") || name.ends_with(".zip") || name.ends_with(".mlmodelc"))
+}
+
+/// Usage is keyed by canonical path so the same model reached through
+/// different spellings (symlinks, `..`, relative paths) shares one entry
+fn usage_key(path: &Path) -> String {
+    path.canonicalize()
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn size_on_disk(path: &Path) -> u64 {
+    if path.is_dir() {
+        fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| size_on_disk(&entry.path()))
+                    .sum()
+            })
+            .unwrap_or(0)
+    } else {
+        fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+    }
+}
+
+/// Quantization of a model: GGML files report it in their header, falling
+/// back to the "-q5_0" naming convention, and Parakeet directories contain
+/// `*.int8.onnx` weights
+fn detect_quantization(path: &Path) -> Option<String> {
+    if path.is_dir() {
+        let has_int8 = fs::read_dir(path).ok()?.filter_map(|e| e.ok()).any(|entry| {
+            entry
+                .file_name()
+                .to_string_lossy()
+                .contains(".int8.")
+        });
+        return has_int8.then(|| "int8".to_string());
+    }
+
+    if let Ok(header) = ggml::read_header(path) {
+        return Some(header.quantization().to_string());
+    }
+
+    let stem = path.file_stem()?.to_string_lossy().to_lowercase();
+    let suffix = stem.rsplit('-').next()?;
+    let is_quantized = (suffix.starts_with('q') || suffix.starts_with('f'))
+        && suffix.chars().nth(1).map_or(false, |c| c.is_ascii_digit());
+
+    is_quantized.then(|| suffix.to_string())
+}
+
+fn read_usage(root: &Path) -> HashMap<String, u64> {
+    fs::read_to_string(root.join(USAGE_FILE))
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_usage(root: &Path, usage: &HashMap<String, u64>) -> Result<(), String> {
+    fs::create_dir_all(root).map_err(|e| format!("Failed to create {:?}: {}", root, e))?;
+    let contents =
+        serde_json::to_string(usage).map_err(|e| format!("Failed to serialize usage: {}", e))?;
+    fs::write(root.join(USAGE_FILE), contents)
+        .map_err(|e| format!("Failed to write usage: {}", e))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
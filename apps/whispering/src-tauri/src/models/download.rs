@@ -0,0 +1,341 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+use tauri_plugin_http::reqwest::{header, Client, Response, StatusCode};
+use tokio::io::AsyncWriteExt;
+
+/// Event emitted as model bytes arrive
+pub const PROGRESS_EVENT: &str = "model-download-progress";
+
+/// Minimum time between progress events so large downloads don't flood IPC
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A model download requested by the frontend
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDownloadRequest {
+    /// Caller-chosen ID used to key progress events and cancellation
+    pub download_id: String,
+    /// Primary URL followed by mirrors, tried in order
+    pub urls: Vec<String>,
+    /// Final path of the model file
+    pub destination: String,
+    /// Expected SHA256 of the complete file (hex); skipped when absent
+    pub sha256: Option<String>,
+}
+
+/// Download progress for a single model - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDownloadProgress {
+    pub download_id: String,
+    pub url: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+}
+
+/// In-flight model downloads, keyed by download ID, so they can be cancelled
+pub struct ModelDownloads {
+    downloads: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl ModelDownloads {
+    pub fn new() -> Self {
+        Self {
+            downloads: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// Outcome of trying a single URL
+enum Attempt {
+    Completed,
+    Cancelled,
+    Failed(String),
+}
+
+/// Download a model file with resume support, mirror fallback, and checksum verification.
+///
+/// Bytes are written to `<destination>.part`, which survives failures and app
+/// restarts so the next attempt continues with an HTTP range request instead of
+/// starting over. The part file is only renamed into place once the download is
+/// complete and its SHA256 matches, so a flaky connection can never leave a
+/// truncated or corrupt model where the transcription engine will load it.
+///
+/// # Returns
+/// * `Ok(String)` - Path of the verified model file
+/// * `Err(String)` - Error message if every URL failed, the checksum didn't match, or it was cancelled
+#[tauri::command]
+pub async fn download_model(
+    request: ModelDownloadRequest,
+    app_handle: AppHandle,
+    downloads: State<'_, ModelDownloads>,
+) -> Result<String, String> {
+    if request.urls.is_empty() {
+        return Err("No download URLs provided".to_string());
+    }
+
+    let destination = PathBuf::from(&request.destination);
+    if let Some(parent) = destination.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create model directory: {}", e))?;
+    }
+    let part_path = part_path(&destination);
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    downloads
+        .downloads
+        .lock()
+        .unwrap()
+        .insert(request.download_id.clone(), cancelled.clone());
+
+    let result = download_from_mirrors(&request, &part_path, &cancelled, &app_handle).await;
+
+    downloads
+        .downloads
+        .lock()
+        .unwrap()
+        .remove(&request.download_id);
+
+    result?;
+
+    tokio::fs::rename(&part_path, &destination)
+        .await
+        .map_err(|e| format!("Failed to move downloaded model into place: {}", e))?;
+
+    println!("[Model Download] Saved {}", request.destination);
+    Ok(request.destination)
+}
+
+/// Cancel an in-flight model download. The partial file is kept so the
+/// download can be resumed later. Returns false if no such download is running.
+#[tauri::command]
+pub fn cancel_model_download(download_id: String, downloads: State<'_, ModelDownloads>) -> bool {
+    match downloads.downloads.lock().unwrap().get(&download_id) {
+        Some(cancelled) => {
+            cancelled.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+async fn download_from_mirrors(
+    request: &ModelDownloadRequest,
+    part_path: &Path,
+    cancelled: &AtomicBool,
+    app_handle: &AppHandle,
+) -> Result<(), String> {
+    let client = Client::new();
+    let mut errors = Vec::new();
+
+    for url in &request.urls {
+        match download_url(&client, url, request, part_path, cancelled, app_handle).await {
+            Attempt::Completed => return Ok(()),
+            Attempt::Cancelled => return Err("Download cancelled".to_string()),
+            Attempt::Failed(e) => {
+                eprintln!("[Model Download] {} failed: {}", url, e);
+                errors.push(format!("{}: {}", url, e));
+            }
+        }
+    }
+
+    Err(format!("All download URLs failed:\n{}", errors.join("\n")))
+}
+
+async fn download_url(
+    client: &Client,
+    url: &str,
+    request: &ModelDownloadRequest,
+    part_path: &Path,
+    cancelled: &AtomicBool,
+    app_handle: &AppHandle,
+) -> Attempt {
+    let (mut response, resume, mut downloaded_bytes) = loop {
+        let existing_bytes = tokio::fs::metadata(part_path)
+            .await
+            .map(|m| m.len())
+            .unwrap_or(0);
+
+        let mut builder = client.get(url);
+        if existing_bytes > 0 {
+            builder = builder.header(header::RANGE, format!("bytes={}-", existing_bytes));
+        }
+
+        let response = match builder.send().await {
+            Ok(response) => response,
+            Err(e) => return Attempt::Failed(e.to_string()),
+        };
+
+        // Server may ignore the range and send the whole file, or report the part file is
+        // already complete
+        match response.status() {
+            StatusCode::PARTIAL_CONTENT => break (response, true, existing_bytes),
+            StatusCode::RANGE_NOT_SATISFIABLE if existing_bytes > 0 => {
+                let checked = match complete_length(&response) {
+                    Some(total) if total != existing_bytes => Err(format!(
+                        "part file has {} bytes, the model has {}",
+                        existing_bytes, total
+                    )),
+                    None if request.sha256.is_none() => {
+                        Err("the server didn't say how large the model is".to_string())
+                    }
+                    _ => verify_checksum(request, part_path).await,
+                };
+                let Err(e) = checked else {
+                    return Attempt::Completed;
+                };
+                // Start over rather than resume a part file that can't be right
+                eprintln!("[Model Download] Discarding {:?}: {}", part_path, e);
+                if let Err(e) = tokio::fs::remove_file(part_path).await {
+                    return Attempt::Failed(format!("Failed to remove {:?}: {}", part_path, e));
+                }
+            }
+            status if status.is_success() => break (response, false, 0),
+            status => return Attempt::Failed(format!("HTTP {}", status)),
+        }
+    };
+    let total_bytes = response.content_length().map(|len| len + downloaded_bytes);
+
+    let file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resume)
+        .truncate(!resume)
+        .open(part_path)
+        .await;
+    let mut file = match file {
+        Ok(file) => file,
+        Err(e) => return Attempt::Failed(format!("Failed to open {:?}: {}", part_path, e)),
+    };
+
+    let mut last_progress = Instant::now();
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            let _ = file.flush().await;
+            return Attempt::Cancelled;
+        }
+
+        let chunk = match response.chunk().await {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => {
+                let _ = file.flush().await;
+                return Attempt::Failed(e.to_string());
+            }
+        };
+
+        if let Err(e) = file.write_all(&chunk).await {
+            return Attempt::Failed(format!("Failed to write model file: {}", e));
+        }
+        downloaded_bytes += chunk.len() as u64;
+
+        if last_progress.elapsed() >= PROGRESS_INTERVAL {
+            last_progress = Instant::now();
+            emit_progress(app_handle, request, url, downloaded_bytes, total_bytes);
+        }
+    }
+
+    if let Err(e) = file.flush().await {
+        return Attempt::Failed(format!("Failed to write model file: {}", e));
+    }
+    emit_progress(app_handle, request, url, downloaded_bytes, total_bytes);
+
+    if let Some(total) = total_bytes.filter(|&total| downloaded_bytes < total) {
+        return Attempt::Failed(format!(
+            "Connection closed after {} of {} bytes",
+            downloaded_bytes, total
+        ));
+    }
+
+    match verify_checksum(request, part_path).await {
+        Ok(()) => Attempt::Completed,
+        Err(e) => {
+            // A bad part file would otherwise be resumed forever
+            let _ = tokio::fs::remove_file(part_path).await;
+            Attempt::Failed(e)
+        }
+    }
+}
+
+/// Full size of the file from a 416 response's `Content-Range: bytes */<size>`
+fn complete_length(response: &Response) -> Option<u64> {
+    response
+        .headers()
+        .get(header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Check the part file against the expected SHA256, when one was given
+async fn verify_checksum(request: &ModelDownloadRequest, part_path: &Path) -> Result<(), String> {
+    let Some(expected) = &request.sha256 else {
+        return Ok(());
+    };
+    let actual = sha256_file(part_path.to_path_buf()).await?;
+    if !actual.eq_ignore_ascii_case(expected.trim()) {
+        return Err(format!(
+            "Checksum mismatch for {}: expected {}, got {}",
+            request.destination, expected, actual
+        ));
+    }
+    Ok(())
+}
+
+fn emit_progress(
+    app_handle: &AppHandle,
+    request: &ModelDownloadRequest,
+    url: &str,
+    downloaded_bytes: u64,
+    total_bytes: Option<u64>,
+) {
+    let _ = app_handle.emit(
+        PROGRESS_EVENT,
+        ModelDownloadProgress {
+            download_id: request.download_id.clone(),
+            url: url.to_string(),
+            downloaded_bytes,
+            total_bytes,
+        },
+    );
+}
+
+fn part_path(destination: &Path) -> PathBuf {
+    let mut file_name = destination
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    file_name.push(".part");
+    destination.with_file_name(file_name)
+}
+
+/// Hash a file on a blocking thread - model files are gigabytes
+async fn sha256_file(path: PathBuf) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut file = std::fs::File::open(&path)
+            .map_err(|e| format!("Failed to open {:?} for verification: {}", path, e))?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)
+            .map_err(|e| format!("Failed to read {:?} for verification: {}", path, e))?;
+
+        Ok::<String, String>(
+            hasher
+                .finalize()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+        )
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
@@ -0,0 +1,142 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::hardware;
+use crate::transcription::{get_compute_backends, ggml};
+
+const MB: u64 = 1024 * 1024;
+
+/// whisper.cpp pads its attention caches to a multiple of 256 positions
+const CACHE_PADDING: u64 = 256;
+
+/// Allocator slack, the whisper.cpp runtime and ggml's graph metadata
+const RUNTIME_OVERHEAD_BYTES: u64 = 64 * MB;
+
+/// Leave headroom for the rest of the app and the OS, as when recommending
+const MEMORY_BUDGET_FRACTION: f64 = 0.8;
+
+/// Expected memory use of a whisper.cpp model against what's free - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelMemoryEstimate {
+    /// Model weights, which take as much memory as they do disk space
+    pub weights_bytes: u64,
+    /// Self- and cross-attention key/value caches
+    pub kv_cache_bytes: u64,
+    /// Encoder and decoder scratch buffers, dominated by encoder attention
+    pub compute_bytes: u64,
+    pub total_bytes: u64,
+    /// Whether whisper.cpp will put the model on a GPU (Metal, CUDA, Vulkan)
+    pub uses_gpu: bool,
+    /// Free VRAM of the NVIDIA GPU with the most of it on CUDA builds,
+    /// otherwise free system RAM (including on Apple Silicon, where the GPU
+    /// shares it)
+    pub available_bytes: u64,
+    pub fits: bool,
+    /// Why loading is likely to fail, for the app to show before trying
+    pub warning: Option<String>,
+}
+
+/// Estimate how much memory a whisper.cpp model needs from its GGML header,
+/// and whether this machine has that much free
+///
+/// Loading a model too large for RAM or VRAM can take the whole app down, so
+/// this lets the UI warn first. Sizes follow whisper.cpp's own allocations
+/// (weights, padded KV caches, encoder/decoder compute buffers) and are
+/// approximate; GGUF and non-whisper files are rejected with the same errors
+/// the loader would give.
+#[tauri::command]
+pub async fn estimate_model_memory(model_path: String) -> Result<ModelMemoryEstimate, String> {
+    tokio::task::spawn_blocking(move || estimate(&PathBuf::from(model_path)))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+fn estimate(model_path: &Path) -> Result<ModelMemoryEstimate, String> {
+    let header = ggml::read_header(model_path)?;
+    let weights_bytes = std::fs::metadata(model_path)
+        .map_err(|e| format!("Failed to read model {:?}: {}", model_path, e))?
+        .len();
+
+    let dim = |value: i32| value.max(0) as u64;
+    let padded = |value: i32| dim(value).div_ceil(CACHE_PADDING) * CACHE_PADDING;
+    let f16_bytes = 2;
+    let f32_bytes = 4;
+
+    // Keys and values for every decoder layer, stored as f16
+    let kv_self =
+        2 * dim(header.n_text_layer) * padded(header.n_text_ctx) * dim(header.n_text_state);
+    let kv_cross =
+        2 * dim(header.n_text_layer) * padded(header.n_audio_ctx) * dim(header.n_text_state);
+    let kv_pad = 2 * padded(header.n_audio_ctx) * dim(header.n_audio_state);
+    let kv_cache_bytes = (kv_self + kv_cross + kv_pad) * f16_bytes;
+
+    // The mel convolution works on twice the encoder's context
+    let conv = 2 * (2 * dim(header.n_audio_ctx)) * dim(header.n_audio_state) * f32_bytes;
+    // Encoder self-attention scores for every head, plus layer activations
+    let encode = (dim(header.n_audio_head) * dim(header.n_audio_ctx).pow(2)
+        + 4 * dim(header.n_audio_ctx) * dim(header.n_audio_state))
+        * f32_bytes;
+    // Logits over the vocabulary for every text position
+    let decode = dim(header.n_vocab) * dim(header.n_text_ctx) * f32_bytes;
+    let compute_bytes = conv + encode + decode + RUNTIME_OVERHEAD_BYTES;
+
+    let total_bytes = weights_bytes + kv_cache_bytes + compute_bytes;
+
+    // Same placement rules as model recommendations
+    let backends = get_compute_backends();
+    let (_, available_ram) = hardware::memory();
+    let vram = if backends.cuda {
+        hardware::nvidia_gpus()
+            .iter()
+            .filter_map(|gpu| gpu.free_vram_bytes)
+            .max()
+    } else {
+        None
+    };
+    let uses_gpu = backends.metal || backends.cuda || backends.vulkan;
+    let available_bytes = vram.unwrap_or(available_ram);
+    let budget = (available_bytes as f64 * MEMORY_BUDGET_FRACTION) as u64;
+    let fits = total_bytes <= budget;
+
+    let memory_kind = if vram.is_some() { "VRAM" } else { "memory" };
+    let warning = if total_bytes > available_bytes {
+        Some(format!(
+            "This model needs about {} MB but only {} MB of {} is free. Loading it will \
+             likely fail or crash; choose a smaller or more quantized model.",
+            total_bytes / MB,
+            available_bytes / MB,
+            memory_kind
+        ))
+    } else if !fits {
+        Some(format!(
+            "This model needs about {} MB of the {} MB of {} that is free, which may slow \
+             down or destabilize the system.",
+            total_bytes / MB,
+            available_bytes / MB,
+            memory_kind
+        ))
+    } else {
+        None
+    };
+
+    println!(
+        "[Model Memory] {:?} ({}): ~{} MB needed, {} MB {} available",
+        model_path,
+        header.quantization(),
+        total_bytes / MB,
+        available_bytes / MB,
+        memory_kind
+    );
+
+    Ok(ModelMemoryEstimate {
+        weights_bytes,
+        kv_cache_bytes,
+        compute_bytes,
+        total_bytes,
+        uses_gpu,
+        available_bytes,
+        fits,
+        warning,
+    })
+}
@@ -0,0 +1,11 @@
+pub mod archive;
+pub mod cache;
+pub mod download;
+pub mod memory;
+pub mod recommend;
+
+pub use archive::extract_model_archive;
+pub use cache::{delete_local_model, list_local_models};
+pub use download::{cancel_model_download, download_model, ModelDownloads};
+pub use memory::estimate_model_memory;
+pub use recommend::recommend_model;
@@ -0,0 +1,160 @@
+use serde::Serialize;
+
+use crate::hardware;
+use crate::transcription::get_compute_backends;
+
+const MB: u64 = 1024 * 1024;
+
+/// Leave headroom for the rest of the app and the OS
+const MEMORY_BUDGET_FRACTION: f64 = 0.8;
+
+/// Compute a model needs to transcribe at least as fast as real time
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum SpeedClass {
+    Any,
+    Cpu,
+    FastCpu,
+    Gpu,
+}
+
+struct Candidate {
+    model: &'static str,
+    quantization: &'static str,
+    file_name: &'static str,
+    /// Approximate resident memory while transcribing, weights plus buffers
+    memory_bytes: u64,
+    needs: SpeedClass,
+}
+
+/// Ordered best to worst; the first one the machine can run wins
+const CANDIDATES: [Candidate; 6] = [
+    Candidate {
+        model: "large-v3-turbo",
+        quantization: "f16",
+        file_name: "ggml-large-v3-turbo.bin",
+        memory_bytes: 1800 * MB,
+        needs: SpeedClass::Gpu,
+    },
+    Candidate {
+        model: "large-v3-turbo",
+        quantization: "q8_0",
+        file_name: "ggml-large-v3-turbo-q8_0.bin",
+        memory_bytes: 1100 * MB,
+        needs: SpeedClass::Gpu,
+    },
+    Candidate {
+        model: "large-v3-turbo",
+        quantization: "q5_0",
+        file_name: "ggml-large-v3-turbo-q5_0.bin",
+        memory_bytes: 800 * MB,
+        needs: SpeedClass::FastCpu,
+    },
+    Candidate {
+        model: "small",
+        quantization: "q8_0",
+        file_name: "ggml-small-q8_0.bin",
+        memory_bytes: 500 * MB,
+        needs: SpeedClass::Cpu,
+    },
+    Candidate {
+        model: "base",
+        quantization: "q8_0",
+        file_name: "ggml-base-q8_0.bin",
+        memory_bytes: 250 * MB,
+        needs: SpeedClass::Any,
+    },
+    Candidate {
+        model: "tiny",
+        quantization: "q8_0",
+        file_name: "ggml-tiny-q8_0.bin",
+        memory_bytes: 130 * MB,
+        needs: SpeedClass::Any,
+    },
+];
+
+/// Suggested whisper.cpp model for this machine - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelRecommendation {
+    pub model: String,
+    pub quantization: String,
+    /// File name of the official GGML release
+    pub file_name: String,
+    pub estimated_memory_bytes: u64,
+    pub reason: String,
+}
+
+/// Recommend the largest whisper.cpp model and quantization this machine can
+/// run in real time, based on available memory, CPU features, and GPU support.
+///
+/// GPU builds (Metal, CUDA, Vulkan) can afford f16 or q8_0 weights; CPU-only
+/// machines are steered toward q5_0 or smaller models, where memory bandwidth
+/// rather than compute is the bottleneck.
+#[tauri::command]
+pub async fn recommend_model() -> Result<ModelRecommendation, String> {
+    tokio::task::spawn_blocking(recommend)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+fn recommend() -> ModelRecommendation {
+    let backends = get_compute_backends();
+    let features = hardware::cpu_features();
+    let cores = hardware::physical_cores();
+    let (_, available_ram) = hardware::memory();
+
+    // Dedicated VRAM bounds the model on discrete GPUs; Apple Silicon shares RAM
+    let gpu_memory = if backends.cuda {
+        hardware::nvidia_gpus()
+            .iter()
+            .filter_map(|gpu| gpu.vram_bytes)
+            .max()
+    } else if backends.metal && hardware::has_unified_memory() {
+        Some(available_ram)
+    } else {
+        None
+    };
+    let has_gpu = backends.metal || backends.cuda || backends.vulkan;
+
+    let speed = if has_gpu {
+        SpeedClass::Gpu
+    } else if features.has_fast_simd() && cores >= 8 {
+        SpeedClass::FastCpu
+    } else if features.has_fast_simd() && cores >= 4 {
+        SpeedClass::Cpu
+    } else {
+        SpeedClass::Any
+    };
+    let memory = gpu_memory.unwrap_or(available_ram);
+    let budget = (memory as f64 * MEMORY_BUDGET_FRACTION) as u64;
+
+    let candidate = CANDIDATES
+        .iter()
+        .find(|c| c.needs <= speed && c.memory_bytes <= budget)
+        .unwrap_or(&CANDIDATES[CANDIDATES.len() - 1]);
+
+    let compute = match speed {
+        SpeedClass::Gpu => "GPU acceleration".to_string(),
+        SpeedClass::FastCpu | SpeedClass::Cpu => format!("{} CPU cores with SIMD", cores),
+        SpeedClass::Any => format!("{} CPU cores without AVX2/NEON", cores),
+    };
+    let reason = format!(
+        "{} and {} MB of {} memory available",
+        compute,
+        memory / MB,
+        if gpu_memory.is_some() { "GPU" } else { "system" }
+    );
+
+    println!(
+        "[Model Recommendation] {} ({}): {}",
+        candidate.model, candidate.quantization, reason
+    );
+
+    ModelRecommendation {
+        model: candidate.model.to_string(),
+        quantization: candidate.quantization.to_string(),
+        file_name: candidate.file_name.to_string(),
+        estimated_memory_bytes: candidate.memory_bytes,
+        reason,
+    }
+}
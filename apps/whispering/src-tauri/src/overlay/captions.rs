@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize};
+
+use super::overlay_window;
+
+/// Window label, matched by the captions overlay's capability
+pub const LABEL: &str = "live-captions";
+
+/// Caption band height and distance from the bottom of the screen, in logical
+/// pixels; it spans this fraction of the screen width
+const HEIGHT: f64 = 120.0;
+const MARGIN: f64 = 48.0;
+const WIDTH_FRACTION: f64 = 0.8;
+
+/// A transparent band across the bottom of the screen showing live
+/// transcription text as it streams in
+///
+/// The window listens for `live-transcription` events itself, so this only
+/// shows and hides it around live transcription sessions. Off until the
+/// frontend applies the user's setting.
+pub struct LiveCaptions {
+    enabled: AtomicBool,
+}
+
+impl LiveCaptions {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+        }
+    }
+
+    /// Show the captions along the bottom of the primary screen, when enabled
+    pub fn show(&self, app: &AppHandle) -> Result<(), String> {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let monitor = app
+            .primary_monitor()
+            .map_err(|e| format!("Failed to find the primary screen: {}", e))?
+            .ok_or_else(|| "No primary screen".to_string())?;
+        let scale = monitor.scale_factor();
+        let area = monitor.work_area();
+        let width = area.size.width as f64 * WIDTH_FRACTION;
+        let height = HEIGHT * scale;
+        let x = area.position.x as f64 + (area.size.width as f64 - width) / 2.0;
+        let y = area.position.y as f64 + area.size.height as f64 - height - MARGIN * scale;
+
+        let window = overlay_window(app, LABEL, "live-captions", width / scale, HEIGHT)
+            .map_err(|e| format!("Failed to create captions overlay: {}", e))?;
+        window
+            .set_size(PhysicalSize::new(width.round() as u32, height.round() as u32))
+            .and_then(|()| {
+                window.set_position(PhysicalPosition::new(x.round() as i32, y.round() as i32))
+            })
+            .and_then(|()| window.show())
+            .map_err(|e| format!("Failed to show captions overlay: {}", e))
+    }
+
+    /// Close the captions, so the next session starts without old text
+    pub fn hide(&self, app: &AppHandle) {
+        if let Some(window) = app.get_webview_window(LABEL) {
+            let _ = window.close();
+        }
+    }
+}
+
+impl Default for LiveCaptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Turn the live captions overlay on or off
+#[tauri::command]
+pub fn set_live_captions(
+    enabled: bool,
+    app_handle: AppHandle,
+    captions: tauri::State<'_, LiveCaptions>,
+) {
+    captions.enabled.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        captions.hide(&app_handle);
+    }
+}
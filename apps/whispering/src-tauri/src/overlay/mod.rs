@@ -0,0 +1,37 @@
+mod captions;
+mod recording;
+
+pub use captions::{set_live_captions, LiveCaptions};
+pub use recording::{set_recording_overlay, OverlayPosition, RecordingOverlay};
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+/// A transparent, always-on-top, click-through window showing `route`,
+/// created hidden when it doesn't already exist
+fn overlay_window(
+    app: &AppHandle,
+    label: &str,
+    route: &str,
+    width: f64,
+    height: f64,
+) -> tauri::Result<WebviewWindow> {
+    if let Some(window) = app.get_webview_window(label) {
+        return Ok(window);
+    }
+    let window = WebviewWindowBuilder::new(app, label, WebviewUrl::App(route.into()))
+        .title(label)
+        .inner_size(width, height)
+        .resizable(false)
+        .decorations(false)
+        .transparent(true)
+        .shadow(false)
+        .always_on_top(true)
+        .visible_on_all_workspaces(true)
+        .skip_taskbar(true)
+        .focused(false)
+        .visible(false)
+        .build()?;
+    // Clicks pass through to whatever is underneath
+    window.set_ignore_cursor_events(true)?;
+    Ok(window)
+}
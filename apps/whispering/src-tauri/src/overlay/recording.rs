@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, WebviewWindow};
+
+use super::overlay_window;
+use crate::recorder::LevelMeter;
+
+/// Window label, matched by the overlay's capability
+pub const LABEL: &str = "recording-overlay";
+
+/// Event emitted to the overlay window while recording
+pub const INDICATOR_EVENT: &str = "recording-indicator";
+
+/// Overlay size and distance from the screen edge, in logical pixels
+const WIDTH: f64 = 160.0;
+const HEIGHT: f64 = 36.0;
+const MARGIN: f64 = 24.0;
+
+/// How often the overlay's timer and level meter update
+const TICK: Duration = Duration::from_millis(66);
+
+/// Payload for `INDICATOR_EVENT` - sent to frontend
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingIndicator {
+    pub elapsed_ms: u64,
+    /// Peak input level of the latest audio, from 0.0 to 1.0
+    pub level: f32,
+}
+
+/// Where on the primary screen the overlay sits - from frontend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlayPosition {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomCenter,
+    BottomRight,
+}
+
+/// A small always-on-top, click-through window showing a red dot, the
+/// elapsed time and the input level while recording
+///
+/// Shown and hidden by the recorder commands; off until the frontend applies
+/// the user's setting.
+pub struct RecordingOverlay {
+    enabled: AtomicBool,
+    position: Mutex<OverlayPosition>,
+    /// Stop flag for the thread feeding the current overlay
+    ticker: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl RecordingOverlay {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            position: Mutex::new(OverlayPosition::default()),
+            ticker: Mutex::new(None),
+        }
+    }
+
+    /// Show the overlay and start updating it from `level`, when enabled
+    pub fn show(&self, app: &AppHandle, level: LevelMeter) -> Result<(), String> {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let window = overlay_window(app, LABEL, "recording-overlay", WIDTH, HEIGHT)
+            .map_err(|e| format!("Failed to create recording overlay: {}", e))?;
+        place(app, &window, *self.position.lock().unwrap())?;
+        window
+            .show()
+            .map_err(|e| format!("Failed to show recording overlay: {}", e))?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        if let Some(previous) = self.ticker.lock().unwrap().replace(stop.clone()) {
+            previous.store(true, Ordering::Relaxed);
+        }
+        let app = app.clone();
+        std::thread::spawn(move || {
+            let started = Instant::now();
+            while !stop.load(Ordering::Relaxed) {
+                let indicator = RecordingIndicator {
+                    elapsed_ms: started.elapsed().as_millis() as u64,
+                    level: level.current(),
+                };
+                let _ = app.emit_to(LABEL, INDICATOR_EVENT, indicator);
+                std::thread::sleep(TICK);
+            }
+        });
+        Ok(())
+    }
+
+    /// Stop updating the overlay and hide it
+    ///
+    /// The window is kept for the next recording rather than closed, so it
+    /// doesn't reload the page each time.
+    pub fn hide(&self, app: &AppHandle) {
+        if let Some(stop) = self.ticker.lock().unwrap().take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+        if let Some(window) = app.get_webview_window(LABEL) {
+            let _ = window.hide();
+        }
+    }
+}
+
+impl Default for RecordingOverlay {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Move the overlay to `position` within the primary screen's work area
+fn place(app: &AppHandle, window: &WebviewWindow, position: OverlayPosition) -> Result<(), String> {
+    let monitor = app
+        .primary_monitor()
+        .map_err(|e| format!("Failed to find the primary screen: {}", e))?
+        .ok_or_else(|| "No primary screen".to_string())?;
+    let scale = monitor.scale_factor();
+    let area = monitor.work_area();
+    let (width, height, margin) = (WIDTH * scale, HEIGHT * scale, MARGIN * scale);
+    let (left, top) = (area.position.x as f64, area.position.y as f64);
+    let (right, bottom) = (
+        left + area.size.width as f64 - width - margin,
+        top + area.size.height as f64 - height - margin,
+    );
+    let center = left + (area.size.width as f64 - width) / 2.0;
+
+    let (x, y) = match position {
+        OverlayPosition::TopLeft => (left + margin, top + margin),
+        OverlayPosition::TopCenter => (center, top + margin),
+        OverlayPosition::TopRight => (right, top + margin),
+        OverlayPosition::BottomLeft => (left + margin, bottom),
+        OverlayPosition::BottomCenter => (center, bottom),
+        OverlayPosition::BottomRight => (right, bottom),
+    };
+    window
+        .set_position(PhysicalPosition::new(x.round() as i32, y.round() as i32))
+        .map_err(|e| format!("Failed to position recording overlay: {}", e))
+}
+
+/// Turn the recording overlay on or off and choose where it appears
+#[tauri::command]
+pub fn set_recording_overlay(
+    enabled: bool,
+    position: Option<OverlayPosition>,
+    app_handle: AppHandle,
+    overlay: tauri::State<'_, RecordingOverlay>,
+) {
+    overlay.enabled.store(enabled, Ordering::Relaxed);
+    if let Some(position) = position {
+        *overlay.position.lock().unwrap() = position;
+    }
+    if !enabled {
+        overlay.hide(&app_handle);
+    }
+}
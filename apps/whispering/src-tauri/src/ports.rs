@@ -0,0 +1,94 @@
+use serde::Serialize;
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use thiserror::Error;
+
+use crate::supervisor::Supervisor;
+
+/// Why a local port can't be used - sent to frontend
+#[derive(Error, Debug, Serialize)]
+#[serde(tag = "name")]
+pub enum PortError {
+    /// Held by a server the app runs, so it can be stopped or reused instead
+    #[error("Port {port} is in use by {process}, which this app started")]
+    InUseByOwnProcess { port: u16, process: String },
+
+    #[error("Port {port} is taken by another app")]
+    InUseByOtherApp { port: u16 },
+
+    #[error("No free port between {start} and {end}")]
+    NoFreePort { start: u16, end: u16 },
+
+    #[error("Couldn't check ports: {message}")]
+    CheckFailed { message: String },
+}
+
+/// Why `port` can't be used, or `None` when it's free
+///
+/// Ports of supervised processes count as taken between restarts too.
+/// `ignore` skips the supervised process with that id, for checks made while
+/// it starts.
+pub fn port_in_use(app_handle: &AppHandle, port: u16, ignore: Option<&str>) -> Option<PortError> {
+    let supervised = app_handle
+        .state::<Supervisor>()
+        .port_owner(port)
+        .filter(|id| Some(id.as_str()) != ignore);
+    if let Some(process) = supervised {
+        return Some(PortError::InUseByOwnProcess { port, process });
+    }
+    (!is_free(port)).then_some(PortError::InUseByOtherApp { port })
+}
+
+/// Any free local port, chosen by the OS
+pub fn any_free_port() -> Result<u16, PortError> {
+    TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|address| address.port())
+        .map_err(|e| PortError::CheckFailed {
+            message: format!("Failed to find a free port: {}", e),
+        })
+}
+
+/// Whether nothing listens on `port` locally
+///
+/// Binding alone isn't enough: on macOS a loopback bind succeeds next to a
+/// server listening on all interfaces, so a connection attempt checks that.
+fn is_free(port: u16) -> bool {
+    if TcpListener::bind((Ipv4Addr::LOCALHOST, port)).is_err() {
+        return false;
+    }
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    TcpStream::connect_timeout(&address, Duration::from_millis(200)).is_err()
+}
+
+/// Check that a port is free before launching a local server on it
+///
+/// Fails with `InUseByOwnProcess` when one of the app's own servers holds
+/// it, and `InUseByOtherApp` when something else does.
+#[tauri::command]
+pub async fn check_port(port: u16, app_handle: AppHandle) -> Result<(), PortError> {
+    tokio::task::spawn_blocking(move || match port_in_use(&app_handle, port, None) {
+        Some(error) => Err(error),
+        None => Ok(()),
+    })
+    .await
+    .map_err(|e| PortError::CheckFailed {
+        message: format!("Task join error: {}", e),
+    })?
+}
+
+/// Find the first free port from `start` to `end` inclusive, skipping those
+/// reserved by the app's own servers
+#[tauri::command]
+pub async fn find_free_port(start: u16, end: u16, app_handle: AppHandle) -> Result<u16, PortError> {
+    tokio::task::spawn_blocking(move || {
+        (start.max(1)..=end)
+            .find(|&port| port_in_use(&app_handle, port, None).is_none())
+            .ok_or(PortError::NoFreePort { start, end })
+    })
+    .await
+    .map_err(|e| PortError::CheckFailed {
+        message: format!("Task join error: {}", e),
+    })?
+}
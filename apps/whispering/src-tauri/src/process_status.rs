@@ -0,0 +1,51 @@
+use serde::Serialize;
+use sysinfo::{Pid, ProcessesToUpdate, System, MINIMUM_CPU_UPDATE_INTERVAL};
+
+/// Whether a process is alive and what it's using - sent to frontend
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessStatus {
+    pub pid: u32,
+    /// False once the process has exited, including zombies not yet reaped
+    pub running: bool,
+    pub name: Option<String>,
+    /// CPU use over a short sample, where 100 is one full core
+    pub cpu_percent: f32,
+    /// Resident memory
+    pub memory_bytes: u64,
+    pub uptime_seconds: u64,
+}
+
+/// Report whether a process is running, with its CPU use, resident memory
+/// and uptime, so the UI can show how a local transcription server is doing
+///
+/// CPU use needs two samples, so this takes a fraction of a second.
+#[tauri::command]
+pub async fn get_process_status(pid: u32) -> Result<ProcessStatus, String> {
+    tokio::task::spawn_blocking(move || {
+        let pid = Pid::from_u32(pid);
+        let mut system = System::new();
+        system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        if system.process(pid).is_some() {
+            std::thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+            system.refresh_processes(ProcessesToUpdate::Some(&[pid]), true);
+        }
+
+        let Some(process) = system.process(pid) else {
+            return ProcessStatus {
+                pid: pid.as_u32(),
+                ..Default::default()
+            };
+        };
+        ProcessStatus {
+            pid: pid.as_u32(),
+            running: process.status() != sysinfo::ProcessStatus::Zombie,
+            name: Some(process.name().to_string_lossy().to_string()),
+            cpu_percent: process.cpu_usage(),
+            memory_bytes: process.memory(),
+            uptime_seconds: process.run_time(),
+        }
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))
+}
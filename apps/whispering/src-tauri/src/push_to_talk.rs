@@ -0,0 +1,158 @@
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::recorder::{self, AudioRecording};
+
+/// Event emitted as push-to-talk drives the recorder, so the frontend can
+/// follow along and transcribe what was recorded
+pub const PUSH_TO_TALK_EVENT: &str = "push-to-talk";
+
+/// Holds shorter than this are taken as accidental taps and discarded
+const MIN_HOLD: Duration = Duration::from_millis(200);
+
+/// Payload for `PUSH_TO_TALK_EVENT` - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PushToTalkRecording {
+    Started,
+    Stopped { recording: AudioRecording },
+    /// Released before `MIN_HOLD`, so the recording was cancelled
+    Tapped,
+}
+
+/// A press of the shortcut that hasn't been released yet
+struct Hold {
+    pressed_at: Instant,
+    /// Resolves to whether the recorder started
+    start: JoinHandle<bool>,
+}
+
+/// Hold-to-record handled in the global shortcut layer: pressing the
+/// shortcut starts the recorder and releasing it stops it, without a round
+/// trip through the webview
+///
+/// The shortcut is registered here rather than by the frontend's global
+/// shortcut manager, which must leave it alone while it's set.
+pub struct PushToTalk {
+    shortcut: Mutex<Option<Shortcut>>,
+    hold: Mutex<Option<Hold>>,
+}
+
+impl PushToTalk {
+    pub fn new() -> Self {
+        Self {
+            shortcut: Mutex::new(None),
+            hold: Mutex::new(None),
+        }
+    }
+
+    /// Start recording, ignoring key repeat while the shortcut is held
+    fn press(&self, app: &AppHandle) {
+        let mut hold = self.hold.lock().unwrap();
+        if hold.is_some() {
+            return;
+        }
+        let app = app.clone();
+        let start = tauri::async_runtime::spawn(async move {
+            match recorder::start_native_recording(&app).await {
+                Ok(()) => {
+                    let _ = app.emit(PUSH_TO_TALK_EVENT, PushToTalkRecording::Started);
+                    true
+                }
+                Err(e) => {
+                    eprintln!("[PushToTalk] Failed to start recording: {}", e);
+                    false
+                }
+            }
+        });
+        *hold = Some(Hold {
+            pressed_at: Instant::now(),
+            start,
+        });
+    }
+
+    /// Stop recording once it has started, or cancel it if the shortcut was
+    /// only tapped
+    fn release(&self, app: &AppHandle) {
+        let Some(hold) = self.hold.lock().unwrap().take() else {
+            return;
+        };
+        let tapped = hold.pressed_at.elapsed() < MIN_HOLD;
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            // A quick release can arrive before the recorder has started
+            if !matches!(hold.start.await, Ok(true)) {
+                return;
+            }
+            if tapped {
+                match recorder::cancel_recording(app.state(), app.clone()).await {
+                    Ok(()) => {
+                        let _ = app.emit(PUSH_TO_TALK_EVENT, PushToTalkRecording::Tapped);
+                    }
+                    Err(e) => eprintln!("[PushToTalk] Failed to cancel recording: {}", e),
+                }
+                return;
+            }
+            match recorder::stop_recording(None, app.state(), app.clone()).await {
+                Ok(recording) => {
+                    let stopped = PushToTalkRecording::Stopped { recording };
+                    let _ = app.emit(PUSH_TO_TALK_EVENT, stopped);
+                }
+                Err(e) => eprintln!("[PushToTalk] Failed to stop recording: {}", e),
+            }
+        });
+    }
+}
+
+impl Default for PushToTalk {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn handle_shortcut(app: &AppHandle, _shortcut: &Shortcut, event: ShortcutEvent) {
+    let push_to_talk = app.state::<PushToTalk>();
+    match event.state {
+        ShortcutState::Pressed => push_to_talk.press(app),
+        ShortcutState::Released => push_to_talk.release(app),
+    }
+}
+
+/// Set the push-to-talk shortcut, replacing the previous one, or clear it
+/// with `None`
+///
+/// Accepts the same accelerators as the frontend, including the legacy
+/// "CommandOrControl" modifier.
+#[tauri::command]
+pub fn set_push_to_talk_shortcut(
+    accelerator: Option<String>,
+    app_handle: AppHandle,
+    push_to_talk: tauri::State<'_, PushToTalk>,
+) -> Result<(), String> {
+    let shortcut: Option<Shortcut> = accelerator
+        .map(|accelerator| {
+            accelerator
+                .parse()
+                .map_err(|e| format!("Invalid push-to-talk shortcut '{}': {}", accelerator, e))
+        })
+        .transpose()?;
+
+    let mut current = push_to_talk.shortcut.lock().unwrap();
+    if let Some(previous) = current.take() {
+        if let Err(e) = app_handle.global_shortcut().unregister(previous) {
+            eprintln!("[PushToTalk] Failed to unregister previous shortcut: {}", e);
+        }
+    }
+    if let Some(shortcut) = shortcut {
+        app_handle
+            .global_shortcut()
+            .on_shortcut(shortcut, handle_shortcut)
+            .map_err(|e| format!("Failed to register push-to-talk shortcut: {}", e))?;
+        *current = Some(shortcut);
+    }
+    Ok(())
+}
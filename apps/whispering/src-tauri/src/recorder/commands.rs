@@ -1,22 +1,123 @@
+use crate::media::{DisabledPlayers, MediaSession, OutputMute};
+#[cfg(desktop)]
+use crate::overlay::RecordingOverlay;
+#[cfg(desktop)]
+use crate::recorder::recorder::LevelMeter;
 use crate::recorder::recorder::{AudioRecording, RecorderState, Result};
+#[cfg(desktop)]
+use crate::tray::TrayMeter;
+use serde::Deserialize;
 use std::path::PathBuf;
 use std::sync::Mutex;
-use tauri::State;
-use tracing::{debug, info};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, State};
+use tracing::{debug, info, warn};
+
+/// What happens around each recording in a session - from frontend
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionOptions {
+    /// Pause playing media when recording starts, resuming it when recording
+    /// stops or is cancelled
+    pub pause_media: bool,
+    /// Mute system output while recording, unmuting it when recording stops
+    /// or is cancelled
+    pub mute_output: bool,
+}
+
+/// Where and how the frontend last opened a recording session
+#[derive(Debug, Clone)]
+struct SessionConfig {
+    device_identifier: String,
+    output_folder: PathBuf,
+    sample_rate: Option<u32>,
+}
 
 /// Application state containing the recorder
 pub struct AppData {
     pub recorder: Mutex<RecorderState>,
+    pub session_options: Mutex<SessionOptions>,
+    /// Reused by recordings started from Rust, which open their own session
+    last_session: Mutex<Option<SessionConfig>>,
 }
 
 impl AppData {
     pub fn new() -> Self {
         Self {
             recorder: Mutex::new(RecorderState::new()),
+            session_options: Mutex::new(SessionOptions::default()),
+            last_session: Mutex::new(None),
+        }
+    }
+
+    fn session_options(&self) -> Result<SessionOptions> {
+        self.session_options
+            .lock()
+            .map(|options| *options)
+            .map_err(|e| format!("Failed to lock session options: {}", e))
+    }
+}
+
+/// Apply the session options just before recording starts, so media and
+/// speaker output don't bleed into the start of the take
+///
+/// Failures are logged rather than returned; a player that won't pause
+/// shouldn't stop the recording.
+fn before_recording(app_handle: &AppHandle, options: SessionOptions) {
+    if options.pause_media {
+        let disabled = app_handle.state::<DisabledPlayers>().for_pausing(app_handle);
+        if let Err(e) = app_handle.state::<MediaSession>().pause_active(&disabled) {
+            warn!("Failed to pause media: {}", e);
+        }
+    }
+    if options.mute_output {
+        if let Err(e) = app_handle.state::<OutputMute>().mute() {
+            warn!("Failed to mute system output: {}", e);
         }
     }
 }
 
+/// Undo `before_recording`, whether the recording stopped, was cancelled or
+/// failed to start
+fn after_recording(app_handle: &AppHandle, options: SessionOptions) {
+    if options.mute_output {
+        if let Err(e) = app_handle.state::<OutputMute>().restore() {
+            warn!("Failed to restore system output: {}", e);
+        }
+    }
+    if options.pause_media {
+        app_handle.state::<MediaSession>().resume();
+    }
+}
+
+/// Run a recording hook off the async runtime, since player IPC and mixer
+/// tools block
+async fn run_hook(
+    app_handle: &AppHandle,
+    options: SessionOptions,
+    hook: fn(&AppHandle, SessionOptions),
+) {
+    let app_handle = app_handle.clone();
+    if let Err(e) = tokio::task::spawn_blocking(move || hook(&app_handle, options)).await {
+        warn!("Task join error: {}", e);
+    }
+}
+
+/// Show the recording overlay and tray meter, each when enabled
+#[cfg(desktop)]
+fn show_indicators(app_handle: &AppHandle, level: LevelMeter) {
+    app_handle.state::<TrayMeter>().start(app_handle, level.clone());
+    if let Err(e) = app_handle.state::<RecordingOverlay>().show(app_handle, level) {
+        warn!("Failed to show recording overlay: {}", e);
+    }
+}
+
+#[cfg(desktop)]
+fn hide_indicators(app_handle: &AppHandle) {
+    app_handle.state::<TrayMeter>().stop();
+    app_handle.state::<RecordingOverlay>().hide(app_handle);
+}
+
 #[tauri::command]
 pub async fn enumerate_recording_devices(state: State<'_, AppData>) -> Result<Vec<String>> {
     debug!("Enumerating recording devices");
@@ -33,6 +134,7 @@ pub async fn init_recording_session(
     recording_id: String,
     output_folder: String,
     sample_rate: Option<u32>,
+    options: Option<SessionOptions>,
     state: State<'_, AppData>,
     _app_handle: tauri::AppHandle,
 ) -> Result<()> {
@@ -40,6 +142,7 @@ pub async fn init_recording_session(
         "Initializing recording session: device={}, id={}, folder={}, sample_rate={:?}",
         device_identifier, recording_id, output_folder, sample_rate
     );
+    debug!("Recording session options: {:?}", options);
 
     // Use the provided output folder
     let recordings_dir = PathBuf::from(output_folder);
@@ -60,47 +163,149 @@ pub async fn init_recording_session(
         .recorder
         .lock()
         .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.init_session(device_identifier, recordings_dir, recording_id, sample_rate)
+    recorder.init_session(
+        device_identifier.clone(),
+        recordings_dir.clone(),
+        recording_id,
+        sample_rate,
+    )?;
+
+    let mut last_session = state
+        .last_session
+        .lock()
+        .map_err(|e| format!("Failed to lock session config: {}", e))?;
+    *last_session = Some(SessionConfig {
+        device_identifier,
+        output_folder: recordings_dir,
+        sample_rate,
+    });
+
+    let mut session_options = state
+        .session_options
+        .lock()
+        .map_err(|e| format!("Failed to lock session options: {}", e))?;
+    *session_options = options.unwrap_or_default();
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn start_recording(state: State<'_, AppData>) -> Result<()> {
+pub async fn start_recording(state: State<'_, AppData>, app_handle: AppHandle) -> Result<()> {
     info!("Starting recording");
-    let mut recorder = state
-        .recorder
+    let options = state.session_options()?;
+    run_hook(&app_handle, options, before_recording).await;
+    let (started, level) = {
+        let mut recorder = state
+            .recorder
+            .lock()
+            .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+        (recorder.start_recording(), recorder.level_meter())
+    };
+    if started.is_err() {
+        run_hook(&app_handle, options, after_recording).await;
+        return started;
+    }
+
+    #[cfg(desktop)]
+    show_indicators(&app_handle, level);
+    #[cfg(not(desktop))]
+    let _ = level;
+    started
+}
+
+/// Start recording from a native trigger such as a shortcut or the tray
+///
+/// The frontend closes its session after every recording, so a fresh one is
+/// opened here with the device, folder and sample rate of the last session
+/// and a new recording ID. Recording then doesn't depend on the webview
+/// being responsive, once it has set up a session at least once.
+pub async fn start_native_recording(app_handle: &AppHandle) -> Result<()> {
+    let state = app_handle.state::<AppData>();
+    let config = state
+        .last_session
         .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.start_recording()
+        .map_err(|e| format!("Failed to lock session config: {}", e))?
+        .clone()
+        .ok_or_else(|| "No recording device set up yet; record once from the app".to_string())?;
+    {
+        let mut recorder = state
+            .recorder
+            .lock()
+            .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+        if recorder.get_current_recording_id().is_some() {
+            return Err("Already recording".to_string());
+        }
+        let recording_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis())
+            .to_string();
+        info!("Opening a recording session for {}", recording_id);
+        recorder.init_session(
+            config.device_identifier,
+            config.output_folder,
+            recording_id,
+            config.sample_rate,
+        )?;
+    }
+    start_recording(state, app_handle.clone()).await
 }
 
 #[tauri::command]
-pub async fn stop_recording(state: State<'_, AppData>) -> Result<AudioRecording> {
+pub async fn stop_recording(
+    normalize_loudness: Option<bool>,
+    state: State<'_, AppData>,
+    app_handle: AppHandle,
+) -> Result<AudioRecording> {
     info!("Stopping recording");
-    let mut recorder = state
-        .recorder
-        .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.stop_recording()
+    let options = state.session_options()?;
+    let recording = {
+        let mut recorder = state
+            .recorder
+            .lock()
+            .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+        recorder.stop_recording(normalize_loudness.unwrap_or(false))
+    };
+    #[cfg(desktop)]
+    hide_indicators(&app_handle);
+    run_hook(&app_handle, options, after_recording).await;
+    recording
 }
 
 #[tauri::command]
-pub async fn cancel_recording(state: State<'_, AppData>) -> Result<()> {
+pub async fn cancel_recording(state: State<'_, AppData>, app_handle: AppHandle) -> Result<()> {
     info!("Cancelling recording");
-    let mut recorder = state
-        .recorder
-        .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.cancel_recording()
+    let options = state.session_options()?;
+    let cancelled = {
+        let mut recorder = state
+            .recorder
+            .lock()
+            .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+        recorder.cancel_recording()
+    };
+    #[cfg(desktop)]
+    hide_indicators(&app_handle);
+    run_hook(&app_handle, options, after_recording).await;
+    cancelled
 }
 
 #[tauri::command]
-pub async fn close_recording_session(state: State<'_, AppData>) -> Result<()> {
+pub async fn close_recording_session(
+    state: State<'_, AppData>,
+    app_handle: AppHandle,
+) -> Result<()> {
     info!("Closing recording session");
-    let mut recorder = state
-        .recorder
-        .lock()
-        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.close_session()
+    let options = state.session_options()?;
+    let closed = {
+        let mut recorder = state
+            .recorder
+            .lock()
+            .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+        recorder.close_session()
+    };
+    // Closing mid-recording discards it, so clean up after it the same way
+    #[cfg(desktop)]
+    hide_indicators(&app_handle);
+    run_hook(&app_handle, options, after_recording).await;
+    closed
 }
 
 #[tauri::command]
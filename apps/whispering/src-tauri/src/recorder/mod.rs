@@ -5,8 +5,9 @@ pub mod wav_writer;
 // Export everything from commands for easy access
 pub use commands::{
     cancel_recording, close_recording_session, enumerate_recording_devices,
-    get_current_recording_id, init_recording_session, start_recording, stop_recording, AppData,
+    get_current_recording_id, init_recording_session, start_native_recording, start_recording,
+    stop_recording, AppData, SessionOptions,
 };
 
 // Export key types from recorder
-pub use recorder::AudioRecording;
+pub use recorder::{AudioRecording, LevelMeter};
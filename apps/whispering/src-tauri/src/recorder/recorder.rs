@@ -3,7 +3,7 @@ use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{Device, SampleFormat, Stream};
 use serde::Serialize;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread::{self, JoinHandle};
 use tracing::{debug, error, info};
@@ -30,12 +30,34 @@ enum RecorderCmd {
     Shutdown,
 }
 
+/// Peak input level (0.0 to 1.0) of the latest audio buffer, written by the
+/// audio callback while recording
+///
+/// Read without resetting, so the overlay and the tray can both follow it.
+#[derive(Debug, Clone, Default)]
+pub struct LevelMeter(Arc<AtomicU32>);
+
+impl LevelMeter {
+    fn record(&self, peak: f32) {
+        self.0.store(peak.min(1.0).to_bits(), Ordering::Relaxed);
+    }
+
+    pub fn current(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
+
+fn peak(samples: impl Iterator<Item = f32>) -> f32 {
+    samples.fold(0.0, |peak, sample| peak.max(sample.abs()))
+}
+
 /// Simplified recorder state
 pub struct RecorderState {
     cmd_tx: Option<mpsc::Sender<RecorderCmd>>,
     worker_handle: Option<JoinHandle<()>>,
     writer: Option<Arc<Mutex<WavWriter>>>,
     is_recording: Arc<AtomicBool>,
+    level: LevelMeter,
     sample_rate: u32,
     channels: u16,
     file_path: Option<PathBuf>,
@@ -48,6 +70,7 @@ impl RecorderState {
             worker_handle: None,
             writer: None,
             is_recording: Arc::new(AtomicBool::new(false)),
+            level: LevelMeter::default(),
             sample_rate: 0,
             channels: 0,
             file_path: None,
@@ -112,6 +135,7 @@ impl RecorderState {
         // Clone for the worker thread
         let writer_clone = writer.clone();
         let is_recording_clone = is_recording.clone();
+        let level = self.level.clone();
 
         // Create the worker thread that owns the stream
         let worker = thread::spawn(move || {
@@ -122,6 +146,7 @@ impl RecorderState {
                 sample_format,
                 is_recording_clone,
                 writer_clone,
+                level,
             ) {
                 Ok(s) => s,
                 Err(e) => {
@@ -193,7 +218,10 @@ impl RecorderState {
     }
 
     /// Stop recording - return file info
-    pub fn stop_recording(&mut self) -> Result<AudioRecording> {
+    ///
+    /// With `normalize_loudness`, the saved file is brought to the EBU R128
+    /// loudness target so quiet recordings transcribe as well as loud ones.
+    pub fn stop_recording(&mut self, normalize_loudness: bool) -> Result<AudioRecording> {
         // Send stop command to worker thread and wait for confirmation
         if let Some(tx) = &self.cmd_tx {
             let (reply_tx, reply_rx) = mpsc::channel();
@@ -211,6 +239,12 @@ impl RecorderState {
                 .map_err(|e| format!("Failed to lock writer: {}", e))?;
             w.finalize()
                 .map_err(|e| format!("Failed to finalize WAV: {}", e))?;
+            if normalize_loudness {
+                // The raw recording is still usable, so don't fail the stop
+                if let Err(e) = w.normalize_loudness() {
+                    error!("Failed to normalize recording loudness: {}", e);
+                }
+            }
             w.get_metadata()
         } else {
             (self.sample_rate, self.channels, 0.0)
@@ -281,6 +315,11 @@ impl RecorderState {
         Ok(())
     }
 
+    /// The input level meter, shared across sessions
+    pub fn level_meter(&self) -> LevelMeter {
+        self.level.clone()
+    }
+
     /// Get current recording ID if actively recording
     pub fn get_current_recording_id(&self) -> Option<String> {
         if self.is_recording.load(Ordering::Acquire) {
@@ -296,7 +335,7 @@ impl RecorderState {
 }
 
 /// Find a recording device by name
-fn find_device(host: &cpal::Host, device_name: &str) -> Result<Device> {
+pub(crate) fn find_device(host: &cpal::Host, device_name: &str) -> Result<Device> {
     // Handle "default" device
     if device_name.to_lowercase() == "default" {
         return host
@@ -319,7 +358,7 @@ fn find_device(host: &cpal::Host, device_name: &str) -> Result<Device> {
 }
 
 /// Get optimal configuration for voice recording
-fn get_optimal_config(
+pub(crate) fn get_optimal_config(
     device: &Device,
     preferred_sample_rate: Option<u32>,
 ) -> Result<cpal::SupportedStreamConfig> {
@@ -416,6 +455,7 @@ fn build_input_stream(
     sample_format: SampleFormat,
     is_recording: Arc<AtomicBool>,
     writer: Arc<Mutex<WavWriter>>,
+    level: LevelMeter,
 ) -> Result<Stream> {
     let err_fn = |err| error!("Audio stream error: {}", err);
 
@@ -425,6 +465,7 @@ fn build_input_stream(
                 config,
                 move |data: &[f32], _: &_| {
                     if is_recording.load(Ordering::Relaxed) {
+                        level.record(peak(data.iter().copied()));
                         if let Ok(mut w) = writer.lock() {
                             let _ = w.write_samples_f32(data);
                         }
@@ -439,6 +480,7 @@ fn build_input_stream(
                 config,
                 move |data: &[i16], _: &_| {
                     if is_recording.load(Ordering::Relaxed) {
+                        level.record(peak(data.iter().map(|&s| s as f32 / 32768.0)));
                         if let Ok(mut w) = writer.lock() {
                             let _ = w.write_samples_i16(data);
                         }
@@ -453,6 +495,7 @@ fn build_input_stream(
                 config,
                 move |data: &[u16], _: &_| {
                     if is_recording.load(Ordering::Relaxed) {
+                        level.record(peak(data.iter().map(|&s| (s as f32 - 32768.0) / 32768.0)));
                         if let Ok(mut w) = writer.lock() {
                             let _ = w.write_samples_u16(data);
                         }
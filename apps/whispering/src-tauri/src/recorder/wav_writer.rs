@@ -1,5 +1,5 @@
 use std::fs::File;
-use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
 use std::path::PathBuf;
 use std::time::Instant;
 use tracing::{debug, info};
@@ -178,6 +178,48 @@ impl WavWriter {
         Ok(())
     }
 
+    /// Normalize the finalized recording to the EBU R128 loudness target
+    ///
+    /// Samples are rewritten in place so the header this writer maintains stays
+    /// valid. Returns the gain applied in dB, or None if the recording was too
+    /// short or silent to measure.
+    pub fn normalize_loudness(&mut self) -> io::Result<Option<f64>> {
+        self.writer.flush()?;
+        let data_start = self.data_chunk_size_pos + 4;
+        let data_len = (self.samples_written * self.bytes_per_sample as u64) as usize;
+
+        let mut data = vec![0u8; data_len];
+        let mut reader = File::open(&self.file_path)?;
+        reader.seek(SeekFrom::Start(data_start))?;
+        reader.read_exact(&mut data)?;
+
+        let mut samples: Vec<f32> = data
+            .chunks_exact(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+
+        let gain_db = crate::loudness::normalize(
+            &mut samples,
+            self.channels as usize,
+            self.sample_rate,
+            crate::loudness::TARGET_LUFS,
+        );
+        if gain_db.is_none() {
+            return Ok(None);
+        }
+
+        let end = self.writer.stream_position()?;
+        self.writer.seek(SeekFrom::Start(data_start))?;
+        for sample in &samples {
+            self.writer.write_all(&sample.to_le_bytes())?;
+        }
+        self.writer.seek(SeekFrom::Start(end))?;
+        self.writer.flush()?;
+
+        info!("Normalized loudness of {:?}", self.file_path);
+        Ok(gain_db)
+    }
+
     /// Get the current duration in seconds
     pub fn get_duration_seconds(&self) -> f32 {
         self.samples_written as f32 / (self.sample_rate as f32 * self.channels as f32)
@@ -0,0 +1,68 @@
+mod mouse;
+mod toggle;
+
+pub use mouse::{set_mouse_shortcuts, MouseShortcuts};
+pub use toggle::{set_toggle_recording_shortcut, ToggleShortcut};
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::recorder::{self, AppData, AudioRecording};
+
+/// Event emitted when a native shortcut changes the recorder, so the frontend
+/// can follow along and transcribe what was recorded
+pub const SHORTCUT_RECORDING_EVENT: &str = "shortcut-recording";
+
+/// Payload for `SHORTCUT_RECORDING_EVENT` - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ShortcutRecording {
+    Started,
+    Stopped { recording: AudioRecording },
+}
+
+/// What a native shortcut does to the recorder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Start,
+    Stop,
+    Toggle,
+}
+
+/// Run `action` on the async runtime, so input hooks return right away
+///
+/// Starting while recording and stopping while idle do nothing, since
+/// gestures like scrolling fire many events for one intent.
+fn run(app: &AppHandle, action: Action) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let recording = matches!(
+            recorder::get_current_recording_id(app.state::<AppData>()).await,
+            Ok(Some(_))
+        );
+        match (action, recording) {
+            (Action::Start | Action::Toggle, false) => start(&app).await,
+            (Action::Stop | Action::Toggle, true) => stop(&app).await,
+            _ => {}
+        }
+    });
+}
+
+async fn start(app: &AppHandle) {
+    match recorder::start_native_recording(app).await {
+        Ok(()) => {
+            let _ = app.emit(SHORTCUT_RECORDING_EVENT, ShortcutRecording::Started);
+        }
+        Err(e) => eprintln!("[Shortcuts] Failed to start recording: {}", e),
+    }
+}
+
+async fn stop(app: &AppHandle) {
+    match recorder::stop_recording(None, app.state(), app.clone()).await {
+        Ok(recording) => {
+            let stopped = ShortcutRecording::Stopped { recording };
+            let _ = app.emit(SHORTCUT_RECORDING_EVENT, stopped);
+        }
+        Err(e) => eprintln!("[Shortcuts] Failed to stop recording: {}", e),
+    }
+}
@@ -0,0 +1,397 @@
+use serde::Deserialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use super::{run, Action};
+
+/// One scroll flick sends a burst of wheel events; after a gesture fires,
+/// the rest of the burst is swallowed without acting again
+const SCROLL_COOLDOWN: Duration = Duration::from_millis(600);
+
+/// An extra mouse button - from frontend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MouseButton {
+    /// Button 4, usually "back" on the side of the mouse
+    Back,
+    /// Button 5, usually "forward" on the side of the mouse
+    Forward,
+}
+
+/// Modifier key held for a scroll gesture - from frontend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ScrollModifier {
+    Shift,
+    Control,
+    /// Option on macOS
+    Alt,
+    /// The Windows key on Windows
+    Command,
+}
+
+/// Mouse gestures that drive the recorder - from frontend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MouseBindings {
+    /// Button that toggles recording
+    pub toggle_button: Option<MouseButton>,
+    /// Modifier that, held while scrolling, starts recording on scroll up and
+    /// stops it on scroll down
+    pub scroll_modifier: Option<ScrollModifier>,
+}
+
+/// Mouse buttons and scroll gestures bound to recording, seen through a
+/// system-wide input hook
+///
+/// The hook is installed the first time a binding is set and kept for the
+/// life of the app; without bindings it passes every event straight on.
+/// Bound events are swallowed, so a bound back button doesn't also go back in
+/// the browser.
+pub struct MouseShortcuts {
+    bindings: Mutex<MouseBindings>,
+    /// When the last scroll gesture fired
+    last_scroll: Mutex<Option<Instant>>,
+    installed: Mutex<bool>,
+}
+
+impl MouseShortcuts {
+    pub fn new() -> Self {
+        Self {
+            bindings: Mutex::new(MouseBindings::default()),
+            last_scroll: Mutex::new(None),
+            installed: Mutex::new(false),
+        }
+    }
+}
+
+impl Default for MouseShortcuts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The app, for the platform hooks, whose callbacks get no context
+static APP: OnceLock<AppHandle> = OnceLock::new();
+
+/// A mouse event seen by the platform hook
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+#[derive(Debug, Clone, Copy)]
+enum MouseEvent {
+    Button { button: MouseButton, pressed: bool },
+    Scroll { up: bool, modifiers: Modifiers },
+}
+
+/// Modifier keys held during a scroll
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+#[derive(Debug, Clone, Copy, Default)]
+struct Modifiers {
+    shift: bool,
+    control: bool,
+    alt: bool,
+    command: bool,
+}
+
+impl Modifiers {
+    fn has(self, modifier: ScrollModifier) -> bool {
+        match modifier {
+            ScrollModifier::Shift => self.shift,
+            ScrollModifier::Control => self.control,
+            ScrollModifier::Alt => self.alt,
+            ScrollModifier::Command => self.command,
+        }
+    }
+}
+
+/// Act on an event from the platform hook, returning whether it was bound
+/// and should be swallowed
+///
+/// Called on the hook thread for every mouse event, so it only looks at the
+/// bindings and hands recorder work to the async runtime.
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+fn handle(event: MouseEvent) -> bool {
+    let Some(app) = APP.get() else {
+        return false;
+    };
+    let shortcuts = app.state::<MouseShortcuts>();
+    let bindings = *shortcuts.bindings.lock().unwrap();
+    match event {
+        MouseEvent::Button { button, pressed } => {
+            if bindings.toggle_button != Some(button) {
+                return false;
+            }
+            if pressed {
+                run(app, Action::Toggle);
+            }
+            true
+        }
+        MouseEvent::Scroll { up, modifiers } => {
+            if !bindings.scroll_modifier.is_some_and(|modifier| modifiers.has(modifier)) {
+                return false;
+            }
+            let mut last_scroll = shortcuts.last_scroll.lock().unwrap();
+            if !last_scroll.is_some_and(|at| at.elapsed() < SCROLL_COOLDOWN) {
+                *last_scroll = Some(Instant::now());
+                run(app, if up { Action::Start } else { Action::Stop });
+            }
+            true
+        }
+    }
+}
+
+/// Set the mouse gestures bound to recording, installing the input hook on
+/// first use
+#[tauri::command]
+pub fn set_mouse_shortcuts(
+    bindings: MouseBindings,
+    app_handle: AppHandle,
+    mouse_shortcuts: tauri::State<'_, MouseShortcuts>,
+) -> Result<(), String> {
+    if bindings != MouseBindings::default() {
+        let mut installed = mouse_shortcuts.installed.lock().unwrap();
+        if !*installed {
+            let _ = APP.set(app_handle);
+            platform::install()?;
+            *installed = true;
+        }
+    }
+    *mouse_shortcuts.bindings.lock().unwrap() = bindings;
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::{handle, Modifiers, MouseButton, MouseEvent};
+    use std::ptr;
+    use std::sync::mpsc;
+    use windows_sys::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        GetAsyncKeyState, VK_CONTROL, VK_LWIN, VK_MENU, VK_RWIN, VK_SHIFT,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        CallNextHookEx, GetMessageW, SetWindowsHookExW, HC_ACTION, MSG, MSLLHOOKSTRUCT,
+        WH_MOUSE_LL, WM_MOUSEWHEEL, WM_XBUTTONDOWN, WM_XBUTTONUP, XBUTTON1, XBUTTON2,
+    };
+
+    /// Install a low-level mouse hook on its own thread, which has to pump
+    /// messages for the hook to be called
+    pub fn install() -> Result<(), String> {
+        let (tx, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("mouse-shortcuts".to_string())
+            .spawn(move || unsafe {
+                let hook = SetWindowsHookExW(WH_MOUSE_LL, Some(hook_proc), ptr::null_mut(), 0);
+                if hook.is_null() {
+                    let error = std::io::Error::last_os_error();
+                    let _ = tx.send(Err(format!("Failed to install mouse hook: {}", error)));
+                    return;
+                }
+                let _ = tx.send(Ok(()));
+                let mut msg: MSG = std::mem::zeroed();
+                while GetMessageW(&mut msg, ptr::null_mut(), 0, 0) > 0 {}
+            })
+            .map_err(|e| format!("Failed to start mouse hook thread: {}", e))?;
+        rx.recv()
+            .map_err(|e| format!("Mouse hook thread exited: {}", e))?
+    }
+
+    unsafe extern "system" fn hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        if code == HC_ACTION as i32 {
+            let info = &*(lparam as *const MSLLHOOKSTRUCT);
+            // The high word holds the X button, or the signed wheel delta
+            let high = (info.mouseData >> 16) as u16;
+            let message = wparam as u32;
+            let event = match message {
+                WM_XBUTTONDOWN | WM_XBUTTONUP => button(high).map(|button| MouseEvent::Button {
+                    button,
+                    pressed: message == WM_XBUTTONDOWN,
+                }),
+                WM_MOUSEWHEEL => Some(MouseEvent::Scroll {
+                    up: (high as i16) > 0,
+                    modifiers: modifiers(),
+                }),
+                _ => None,
+            };
+            if event.is_some_and(handle) {
+                return 1;
+            }
+        }
+        CallNextHookEx(ptr::null_mut(), code, wparam, lparam)
+    }
+
+    fn button(xbutton: u16) -> Option<MouseButton> {
+        match xbutton {
+            XBUTTON1 => Some(MouseButton::Back),
+            XBUTTON2 => Some(MouseButton::Forward),
+            _ => None,
+        }
+    }
+
+    fn modifiers() -> Modifiers {
+        // The high bit is set while the key is down
+        let down = |key: u16| unsafe { GetAsyncKeyState(key as i32) } < 0;
+        Modifiers {
+            shift: down(VK_SHIFT),
+            control: down(VK_CONTROL),
+            alt: down(VK_MENU),
+            command: down(VK_LWIN) || down(VK_RWIN),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::{handle, Modifiers, MouseButton, MouseEvent};
+    use core_foundation_sys::mach_port::{CFMachPortCreateRunLoopSource, CFMachPortRef};
+    use core_foundation_sys::runloop::{
+        kCFRunLoopCommonModes, CFRunLoopAddSource, CFRunLoopGetCurrent, CFRunLoopRun,
+    };
+    use std::ffi::c_void;
+    use std::ptr;
+    use std::sync::atomic::{AtomicPtr, Ordering};
+    use std::sync::mpsc;
+
+    type CGEventRef = *mut c_void;
+    type CGEventTapCallBack =
+        unsafe extern "C" fn(*mut c_void, u32, CGEventRef, *mut c_void) -> CGEventRef;
+
+    // CGEventTapLocation, CGEventTapPlacement and CGEventTapOptions
+    const SESSION_EVENT_TAP: u32 = 1;
+    const HEAD_INSERT_EVENT_TAP: u32 = 0;
+    const TAP_OPTION_DEFAULT: u32 = 0;
+
+    // CGEventType
+    const SCROLL_WHEEL: u32 = 22;
+    const OTHER_MOUSE_DOWN: u32 = 25;
+    const OTHER_MOUSE_UP: u32 = 26;
+    const TAP_DISABLED_BY_TIMEOUT: u32 = 0xFFFF_FFFE;
+
+    // CGEventField
+    const MOUSE_EVENT_BUTTON_NUMBER: u32 = 3;
+    const SCROLL_WHEEL_EVENT_DELTA_AXIS_1: u32 = 11;
+    const SCROLL_WHEEL_EVENT_DELTA_AXIS_2: u32 = 12;
+
+    // CGEventFlags
+    const FLAG_SHIFT: u64 = 0x0002_0000;
+    const FLAG_CONTROL: u64 = 0x0004_0000;
+    const FLAG_ALTERNATE: u64 = 0x0008_0000;
+    const FLAG_COMMAND: u64 = 0x0010_0000;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventTapCreate(
+            tap: u32,
+            place: u32,
+            options: u32,
+            events_of_interest: u64,
+            callback: CGEventTapCallBack,
+            user_info: *mut c_void,
+        ) -> CFMachPortRef;
+        fn CGEventTapEnable(tap: CFMachPortRef, enable: bool);
+        fn CGEventGetIntegerValueField(event: CGEventRef, field: u32) -> i64;
+        fn CGEventGetFlags(event: CGEventRef) -> u64;
+    }
+
+    /// The tap, to turn back on when macOS disables it after a slow callback
+    static TAP: AtomicPtr<c_void> = AtomicPtr::new(ptr::null_mut());
+
+    /// Create an event tap on its own thread's run loop
+    ///
+    /// Filtering events needs Accessibility permission, which text insertion
+    /// already asks for.
+    pub fn install() -> Result<(), String> {
+        let (tx, rx) = mpsc::channel();
+        std::thread::Builder::new()
+            .name("mouse-shortcuts".to_string())
+            .spawn(move || unsafe {
+                let mask = (1u64 << SCROLL_WHEEL) | (1 << OTHER_MOUSE_DOWN) | (1 << OTHER_MOUSE_UP);
+                let tap = CGEventTapCreate(
+                    SESSION_EVENT_TAP,
+                    HEAD_INSERT_EVENT_TAP,
+                    TAP_OPTION_DEFAULT,
+                    mask,
+                    tap_callback,
+                    ptr::null_mut(),
+                );
+                if tap.is_null() {
+                    let _ = tx.send(Err(
+                        "Failed to create mouse event tap; is Accessibility permission granted?"
+                            .to_string(),
+                    ));
+                    return;
+                }
+                TAP.store(tap.cast(), Ordering::Relaxed);
+                let source = CFMachPortCreateRunLoopSource(ptr::null(), tap, 0);
+                CFRunLoopAddSource(CFRunLoopGetCurrent(), source, kCFRunLoopCommonModes);
+                CGEventTapEnable(tap, true);
+                let _ = tx.send(Ok(()));
+                CFRunLoopRun();
+            })
+            .map_err(|e| format!("Failed to start mouse hook thread: {}", e))?;
+        rx.recv()
+            .map_err(|e| format!("Mouse hook thread exited: {}", e))?
+    }
+
+    unsafe extern "C" fn tap_callback(
+        _proxy: *mut c_void,
+        event_type: u32,
+        event: CGEventRef,
+        _user_info: *mut c_void,
+    ) -> CGEventRef {
+        let mouse_event = match event_type {
+            TAP_DISABLED_BY_TIMEOUT => {
+                CGEventTapEnable(TAP.load(Ordering::Relaxed).cast(), true);
+                None
+            }
+            OTHER_MOUSE_DOWN | OTHER_MOUSE_UP => {
+                button(CGEventGetIntegerValueField(event, MOUSE_EVENT_BUTTON_NUMBER)).map(
+                    |button| MouseEvent::Button {
+                        button,
+                        pressed: event_type == OTHER_MOUSE_DOWN,
+                    },
+                )
+            }
+            SCROLL_WHEEL => {
+                // Shift turns a wheel's vertical scroll into horizontal scroll
+                let field = |field| CGEventGetIntegerValueField(event, field);
+                let delta = match field(SCROLL_WHEEL_EVENT_DELTA_AXIS_1) {
+                    0 => field(SCROLL_WHEEL_EVENT_DELTA_AXIS_2),
+                    delta => delta,
+                };
+                let flags = CGEventGetFlags(event);
+                (delta != 0).then(|| MouseEvent::Scroll {
+                    up: delta > 0,
+                    modifiers: Modifiers {
+                        shift: flags & FLAG_SHIFT != 0,
+                        control: flags & FLAG_CONTROL != 0,
+                        alt: flags & FLAG_ALTERNATE != 0,
+                        command: flags & FLAG_COMMAND != 0,
+                    },
+                })
+            }
+            _ => None,
+        };
+        if mouse_event.is_some_and(handle) {
+            ptr::null_mut()
+        } else {
+            event
+        }
+    }
+
+    /// Map a button number, counting left as 0, to a side button
+    fn button(number: i64) -> Option<MouseButton> {
+        match number {
+            3 => Some(MouseButton::Back),
+            4 => Some(MouseButton::Forward),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    pub fn install() -> Result<(), String> {
+        Err("Mouse shortcuts are only supported on macOS and Windows".to_string())
+    }
+}
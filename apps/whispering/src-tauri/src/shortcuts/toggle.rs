@@ -0,0 +1,69 @@
+use std::sync::Mutex;
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use super::{run, Action};
+
+/// The keyboard shortcut that toggles recording straight from Rust
+///
+/// Registered here rather than by the frontend's global shortcut manager,
+/// which must leave it alone while it's set, so recording starts without a
+/// webview round trip and still works while the webview is busy or gone.
+pub struct ToggleShortcut {
+    shortcut: Mutex<Option<Shortcut>>,
+}
+
+impl ToggleShortcut {
+    pub fn new() -> Self {
+        Self {
+            shortcut: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for ToggleShortcut {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn handle_shortcut(app: &AppHandle, _shortcut: &Shortcut, event: ShortcutEvent) {
+    if matches!(event.state, ShortcutState::Pressed) {
+        run(app, Action::Toggle);
+    }
+}
+
+/// Set the shortcut that toggles recording, replacing the previous one, or
+/// clear it with `None`
+///
+/// Accepts the same accelerators as the frontend, including the legacy
+/// "CommandOrControl" modifier.
+#[tauri::command]
+pub fn set_toggle_recording_shortcut(
+    accelerator: Option<String>,
+    app_handle: AppHandle,
+    toggle_shortcut: tauri::State<'_, ToggleShortcut>,
+) -> Result<(), String> {
+    let shortcut: Option<Shortcut> = accelerator
+        .map(|accelerator| {
+            accelerator
+                .parse()
+                .map_err(|e| format!("Invalid recording shortcut '{}': {}", accelerator, e))
+        })
+        .transpose()?;
+
+    let mut current = toggle_shortcut.shortcut.lock().unwrap();
+    if let Some(previous) = current.take() {
+        if let Err(e) = app_handle.global_shortcut().unregister(previous) {
+            eprintln!("[Shortcuts] Failed to unregister previous shortcut: {}", e);
+        }
+    }
+    if let Some(shortcut) = shortcut {
+        app_handle
+            .global_shortcut()
+            .on_shortcut(shortcut, handle_shortcut)
+            .map_err(|e| format!("Failed to register recording shortcut: {}", e))?;
+        *current = Some(shortcut);
+    }
+    Ok(())
+}
@@ -0,0 +1,109 @@
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::ipc::Channel;
+
+/// Lines kept per process, enough to see what led up to a crash
+const HISTORY_LINES: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+/// A line of output from a supervised process - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogLine {
+    pub id: String,
+    pub stream: LogStream,
+    pub line: String,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: u64,
+}
+
+struct Watcher {
+    /// Only lines from this process, or every process when `None`
+    id: Option<String>,
+    channel: Channel<LogLine>,
+}
+
+/// Recent output of each supervised process, and the channels following it
+pub struct ProcessLogs {
+    history: Mutex<HashMap<String, VecDeque<LogLine>>>,
+    watchers: Mutex<Vec<Watcher>>,
+}
+
+impl ProcessLogs {
+    pub fn new() -> Self {
+        Self {
+            history: Mutex::new(HashMap::new()),
+            watchers: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Record a line and send it to every channel watching its process
+    pub fn push(&self, id: &str, stream: LogStream, line: String) {
+        let line = LogLine {
+            id: id.to_string(),
+            stream,
+            line,
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64),
+        };
+
+        // Channels fail to send once their webview has gone, so drop them
+        self.watchers.lock().unwrap().retain(|watcher| {
+            let watching = match &watcher.id {
+                Some(watched) => watched == id,
+                None => true,
+            };
+            !watching || watcher.channel.send(line.clone()).is_ok()
+        });
+
+        let mut history = self.history.lock().unwrap();
+        let lines = history.entry(id.to_string()).or_default();
+        if lines.len() == HISTORY_LINES {
+            lines.pop_front();
+        }
+        lines.push_back(line);
+    }
+
+    /// Recent lines of one process, or of all of them in time order
+    pub fn history(&self, id: Option<&str>) -> Vec<LogLine> {
+        let history = self.history.lock().unwrap();
+        match id {
+            Some(id) => history
+                .get(id)
+                .map(|lines| lines.iter().cloned().collect())
+                .unwrap_or_default(),
+            None => {
+                let mut lines: Vec<LogLine> = history.values().flatten().cloned().collect();
+                lines.sort_by_key(|line| line.timestamp);
+                lines
+            }
+        }
+    }
+
+    pub fn watch(&self, id: Option<String>, channel: Channel<LogLine>) {
+        self.watchers.lock().unwrap().push(Watcher { id, channel });
+    }
+
+    /// Stop sending to the channel with this id
+    pub fn unwatch(&self, channel_id: u32) {
+        self.watchers
+            .lock()
+            .unwrap()
+            .retain(|watcher| watcher.channel.id() != channel_id);
+    }
+}
+
+impl Default for ProcessLogs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
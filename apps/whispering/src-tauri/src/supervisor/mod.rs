@@ -0,0 +1,632 @@
+mod logs;
+
+pub use logs::{LogLine, LogStream, ProcessLogs};
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::graceful_shutdown::{own_process_group, stop_process_tree, track_process_tree};
+use crate::ports::port_in_use;
+#[cfg(target_os = "windows")]
+use crate::graceful_shutdown::CREATE_NEW_PROCESS_GROUP;
+
+/// Event emitted whenever a supervised process changes state
+pub const PROCESS_EVENT: &str = "supervised-process";
+
+/// How often each process is checked for exits and failed health checks
+const HEALTH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Consecutive failed health checks before a process counts as hung
+const MAX_HEALTH_FAILURES: u32 = 3;
+
+/// Delay before the first restart, doubled for each one after
+const RESTART_BACKOFF: Duration = Duration::from_secs(1);
+
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+const DEFAULT_MAX_RESTARTS: u32 = 5;
+
+/// Servers like speaches or ollama may load a model before they answer
+const DEFAULT_STARTUP_TIMEOUT_MS: u64 = 60_000;
+
+/// Time allowed to exit after being asked before the process tree is killed
+const DEFAULT_SHUTDOWN_TIMEOUT_MS: u64 = 5000;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// How to run and watch a local server - from frontend
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessConfig {
+    /// Names the process in events and commands, e.g. "speaches"
+    pub id: String,
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    pub cwd: Option<String>,
+    /// Port the server listens on locally; it's ready once this accepts
+    /// connections
+    pub port: Option<u16>,
+    /// Path answering 2xx when the server is healthy, e.g. "/health"
+    pub health_path: Option<String>,
+    /// Restart the process when it exits or hangs
+    #[serde(default = "enabled")]
+    pub restart: bool,
+    pub max_restarts: Option<u32>,
+    pub startup_timeout_ms: Option<u64>,
+    pub shutdown_timeout_ms: Option<u64>,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl ProcessConfig {
+    fn startup_timeout(&self) -> Duration {
+        Duration::from_millis(self.startup_timeout_ms.unwrap_or(DEFAULT_STARTUP_TIMEOUT_MS))
+    }
+
+    fn shutdown_timeout(&self) -> Duration {
+        Duration::from_millis(self.shutdown_timeout_ms.unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_MS))
+    }
+}
+
+/// Where a supervised process is in its lifecycle - sent to frontend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessState {
+    Starting,
+    Running,
+    /// Exited or hung, and waiting out the backoff before starting again
+    Restarting,
+    Stopped,
+    /// Couldn't start, or ran out of restarts
+    Failed,
+}
+
+/// A supervised process and its state - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupervisedProcess {
+    pub id: String,
+    pub state: ProcessState,
+    pub pid: Option<u32>,
+    pub port: Option<u16>,
+    pub restarts: u32,
+    /// Why the process last exited, restarted or failed
+    pub message: Option<String>,
+}
+
+struct Entry {
+    config: ProcessConfig,
+    child: Option<Child>,
+    state: ProcessState,
+    restarts: u32,
+    message: Option<String>,
+    /// Retires the monitor of an earlier start when it changes
+    generation: u64,
+}
+
+impl Entry {
+    fn snapshot(&self) -> SupervisedProcess {
+        SupervisedProcess {
+            id: self.config.id.clone(),
+            state: self.state,
+            pid: self.child.as_ref().map(Child::id),
+            port: self.config.port,
+            restarts: self.restarts,
+            message: self.message.clone(),
+        }
+    }
+}
+
+/// Local servers (speaches, whisper-server, ollama) kept running alongside
+/// the app
+///
+/// Each process gets a monitor thread that restarts it with backoff when it
+/// exits or stops answering health checks. Stopping asks the whole process
+/// tree to exit before killing it, so workers don't outlive their server.
+pub struct Supervisor {
+    processes: Mutex<HashMap<String, Entry>>,
+    generation: AtomicU64,
+    logs: ProcessLogs,
+}
+
+impl Supervisor {
+    pub fn new() -> Self {
+        Self {
+            processes: Mutex::new(HashMap::new()),
+            generation: AtomicU64::new(0),
+            logs: ProcessLogs::new(),
+        }
+    }
+
+    pub fn list(&self) -> Vec<SupervisedProcess> {
+        let processes = self.processes.lock().unwrap();
+        let mut list: Vec<_> = processes.values().map(Entry::snapshot).collect();
+        list.sort_by(|a, b| a.id.cmp(&b.id));
+        list
+    }
+
+    /// The supervised process holding `port`, including one between restarts
+    pub fn port_owner(&self, port: u16) -> Option<String> {
+        let processes = self.processes.lock().unwrap();
+        processes
+            .values()
+            .filter(|entry| entry.config.port == Some(port))
+            .find(|entry| {
+                matches!(
+                    entry.state,
+                    ProcessState::Starting | ProcessState::Running | ProcessState::Restarting
+                )
+            })
+            .map(|entry| entry.config.id.clone())
+    }
+
+    pub fn get(&self, id: &str) -> Option<SupervisedProcess> {
+        self.processes.lock().unwrap().get(id).map(Entry::snapshot)
+    }
+
+    /// The config a process was last started with, even once it has stopped
+    pub fn config(&self, id: &str) -> Option<ProcessConfig> {
+        self.processes.lock().unwrap().get(id).map(|entry| entry.config.clone())
+    }
+
+    /// Start a process and wait until it's ready, reusing a running one with
+    /// the same config
+    pub fn start(
+        &self,
+        config: ProcessConfig,
+        app_handle: &AppHandle,
+    ) -> Result<SupervisedProcess, String> {
+        if config.id.trim().is_empty() {
+            return Err("A supervised process needs an id".to_string());
+        }
+        let id = config.id.clone();
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+        let replaced = {
+            let mut processes = self.processes.lock().unwrap();
+            if let Some(entry) = processes.get(&id) {
+                let active = matches!(entry.state, ProcessState::Starting | ProcessState::Running);
+                if active && entry.config == config {
+                    return Ok(entry.snapshot());
+                }
+            }
+            let replaced = processes.insert(
+                id.clone(),
+                Entry {
+                    config: config.clone(),
+                    child: None,
+                    state: ProcessState::Starting,
+                    restarts: 0,
+                    message: None,
+                    generation,
+                },
+            );
+            replaced.and_then(|mut entry| entry.child.take().map(|child| (child, entry.config)))
+        };
+        if let Some((child, old_config)) = replaced {
+            println!("[Supervisor] Replacing {}", id);
+            stop_child(child, &old_config);
+        }
+        self.emit(app_handle, &id);
+
+        let launched =
+            ensure_port_free(app_handle, &config).and_then(|()| launch(app_handle, &config));
+        {
+            let mut processes = self.processes.lock().unwrap();
+            let Some(entry) = processes
+                .get_mut(&id)
+                .filter(|entry| entry.generation == generation)
+            else {
+                drop(processes);
+                if let Ok(child) = launched {
+                    stop_child(child, &config);
+                }
+                return Err(format!("Start of {} was superseded", id));
+            };
+            match launched {
+                Ok(child) => {
+                    entry.child = Some(child);
+                    entry.state = ProcessState::Running;
+                }
+                Err(e) => {
+                    entry.state = ProcessState::Failed;
+                    entry.message = Some(e.clone());
+                    drop(processes);
+                    self.emit(app_handle, &id);
+                    return Err(e);
+                }
+            }
+        }
+        self.emit(app_handle, &id);
+
+        let monitor_handle = app_handle.clone();
+        thread::spawn(move || monitor(monitor_handle, config, generation));
+        self.get(&id).ok_or_else(|| format!("{} was removed while starting", id))
+    }
+
+    /// Stop a process and its children, keeping it listed as stopped
+    pub fn stop(&self, id: &str, app_handle: &AppHandle) -> Result<(), String> {
+        let (child, config) = {
+            let mut processes = self.processes.lock().unwrap();
+            let entry = processes
+                .get_mut(id)
+                .ok_or_else(|| format!("No supervised process named {}", id))?;
+            entry.generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+            entry.state = ProcessState::Stopped;
+            entry.message = None;
+            (entry.child.take(), entry.config.clone())
+        };
+        if let Some(child) = child {
+            println!("[Supervisor] Stopping {}", id);
+            stop_child(child, &config);
+        }
+        self.emit(app_handle, id);
+        Ok(())
+    }
+
+    /// Stop every process, e.g. when the app quits
+    pub fn stop_all(&self) {
+        let children: Vec<(Child, ProcessConfig)> = {
+            let mut processes = self.processes.lock().unwrap();
+            processes
+                .values_mut()
+                .filter_map(|entry| {
+                    entry.generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+                    entry.state = ProcessState::Stopped;
+                    entry.child.take().map(|child| (child, entry.config.clone()))
+                })
+                .collect()
+        };
+        for (child, config) in children {
+            println!("[Supervisor] Stopping {}", config.id);
+            stop_child(child, &config);
+        }
+    }
+
+    fn emit(&self, app_handle: &AppHandle, id: &str) {
+        if let Some(process) = self.get(id) {
+            let _ = app_handle.emit(PROCESS_EVENT, process);
+        }
+    }
+}
+
+impl Default for Supervisor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fail when something else holds the configured port, whose health checks
+/// would otherwise pass for this process
+fn ensure_port_free(app_handle: &AppHandle, config: &ProcessConfig) -> Result<(), String> {
+    let in_use = config
+        .port
+        .and_then(|port| port_in_use(app_handle, port, Some(&config.id)));
+    match in_use {
+        Some(error) => Err(error.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Spawn the process in its own group and wait until it's ready
+fn launch(app_handle: &AppHandle, config: &ProcessConfig) -> Result<Child, String> {
+    let mut command = Command::new(&config.program);
+    command
+        .args(&config.args)
+        .envs(&config.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(cwd) = &config.cwd {
+        command.current_dir(cwd);
+    }
+
+    #[cfg(target_os = "windows")]
+    command.creation_flags(CREATE_NO_WINDOW | CREATE_NEW_PROCESS_GROUP);
+    own_process_group(&mut command);
+
+    println!("[Supervisor] Starting {} ({})", config.id, config.program);
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {}", config.program, e))?;
+    track_process_tree(&child);
+
+    if let Some(stdout) = child.stdout.take() {
+        capture_output(app_handle.clone(), config.id.clone(), LogStream::Stdout, stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        capture_output(app_handle.clone(), config.id.clone(), LogStream::Stderr, stderr);
+    }
+
+    if let Err(e) = wait_until_ready(&mut child, config) {
+        stop_child(child, config);
+        return Err(e);
+    }
+
+    println!("[Supervisor] {} is ready (PID {})", config.id, child.id());
+    Ok(child)
+}
+
+/// Copy the process's output into the app log and its log history
+///
+/// Lines are split on raw bytes, since servers don't always write UTF-8.
+fn capture_output(
+    app_handle: AppHandle,
+    id: String,
+    stream: LogStream,
+    output: impl Read + Send + 'static,
+) {
+    thread::spawn(move || {
+        let supervisor = app_handle.state::<Supervisor>();
+        let mut reader = BufReader::new(output);
+        let mut buffer = Vec::new();
+        while matches!(reader.read_until(b'\n', &mut buffer), Ok(read) if read > 0) {
+            let line = String::from_utf8_lossy(&buffer).trim_end().to_string();
+            println!("[{}] {}", id, line);
+            supervisor.logs.push(&id, stream, line);
+            buffer.clear();
+        }
+    });
+}
+
+fn wait_until_ready(child: &mut Child, config: &ProcessConfig) -> Result<(), String> {
+    let started = Instant::now();
+    loop {
+        if let Ok(Some(status)) = child.try_wait() {
+            return Err(format!("{} exited during startup ({})", config.id, status));
+        }
+        if is_healthy(config) {
+            return Ok(());
+        }
+        if started.elapsed() > config.startup_timeout() {
+            return Err(format!("Timed out waiting for {} to start", config.id));
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+}
+
+/// Whether the process answers on its port, or with a 2xx on its health
+/// path when it has one
+///
+/// Processes without a port are healthy for as long as they run.
+fn is_healthy(config: &ProcessConfig) -> bool {
+    let Some(port) = config.port else {
+        return true;
+    };
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let Ok(mut stream) = TcpStream::connect_timeout(&address, Duration::from_millis(500)) else {
+        return false;
+    };
+    let Some(path) = &config.health_path else {
+        return true;
+    };
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+
+    let request = format!(
+        "GET {} HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nConnection: close\r\n\r\n",
+        path, port
+    );
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+
+    let mut status_line = String::new();
+    if BufReader::new(stream).read_line(&mut status_line).is_err() {
+        return false;
+    }
+    status_line
+        .split_whitespace()
+        .nth(1)
+        .is_some_and(|status| status.starts_with('2'))
+}
+
+/// Stop the process tree, killing it if it doesn't exit in time
+fn stop_child(mut child: Child, config: &ProcessConfig) {
+    if let Err(e) = stop_process_tree(child.id(), config.shutdown_timeout()) {
+        eprintln!("[Supervisor] {}: {}", config.id, e);
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+}
+
+/// Watch a process and restart it when it exits or stops answering health
+/// checks, until its restart budget runs out
+fn monitor(app_handle: AppHandle, config: ProcessConfig, generation: u64) {
+    let supervisor = app_handle.state::<Supervisor>();
+    let id = config.id.clone();
+    let max_restarts = config.max_restarts.unwrap_or(DEFAULT_MAX_RESTARTS);
+    let mut health_failures = 0;
+    loop {
+        thread::sleep(HEALTH_INTERVAL);
+
+        let exit = {
+            let mut processes = supervisor.processes.lock().unwrap();
+            let Some(child) = processes
+                .get_mut(&id)
+                .filter(|entry| entry.generation == generation)
+                .and_then(|entry| entry.child.as_mut())
+            else {
+                return;
+            };
+            match child.try_wait() {
+                Ok(None) => None,
+                Ok(Some(status)) => Some(status.to_string()),
+                Err(e) => Some(e.to_string()),
+            }
+        };
+        let exited = exit.is_some();
+        let reason = match exit {
+            Some(status) => format!("Exited unexpectedly ({})", status),
+            None if is_healthy(&config) => {
+                health_failures = 0;
+                continue;
+            }
+            None => {
+                health_failures += 1;
+                if health_failures < MAX_HEALTH_FAILURES {
+                    continue;
+                }
+                "Stopped responding to health checks".to_string()
+            }
+        };
+        health_failures = 0;
+        eprintln!("[Supervisor] {}: {}", id, reason);
+
+        let (hung, restarts) = {
+            let mut processes = supervisor.processes.lock().unwrap();
+            let Some(entry) = processes
+                .get_mut(&id)
+                .filter(|entry| entry.generation == generation)
+            else {
+                return;
+            };
+            let child = entry.child.take();
+            entry.message = Some(reason);
+            entry.state = if config.restart && entry.restarts < max_restarts {
+                ProcessState::Restarting
+            } else {
+                ProcessState::Failed
+            };
+            // An exited child was already reaped by `try_wait`
+            (child.filter(|_| !exited), entry.restarts)
+        };
+        if let Some(hung) = hung {
+            stop_child(hung, &config);
+        }
+        supervisor.emit(&app_handle, &id);
+        if !config.restart || restarts >= max_restarts {
+            if config.restart {
+                eprintln!("[Supervisor] Giving up on {} after {} restarts", id, restarts);
+            }
+            return;
+        }
+
+        thread::sleep((RESTART_BACKOFF * 2u32.saturating_pow(restarts)).min(MAX_BACKOFF));
+        let current = supervisor
+            .processes
+            .lock()
+            .unwrap()
+            .get(&id)
+            .is_some_and(|entry| entry.generation == generation);
+        if !current {
+            return;
+        }
+
+        let launched =
+            ensure_port_free(&app_handle, &config).and_then(|()| launch(&app_handle, &config));
+        let failed = {
+            let mut processes = supervisor.processes.lock().unwrap();
+            let Some(entry) = processes
+                .get_mut(&id)
+                .filter(|entry| entry.generation == generation)
+            else {
+                drop(processes);
+                if let Ok(child) = launched {
+                    stop_child(child, &config);
+                }
+                return;
+            };
+            entry.restarts += 1;
+            match launched {
+                Ok(child) => {
+                    entry.child = Some(child);
+                    entry.state = ProcessState::Running;
+                    false
+                }
+                Err(e) => {
+                    eprintln!("[Supervisor] Restarting {} failed: {}", id, e);
+                    entry.state = ProcessState::Failed;
+                    entry.message = Some(e);
+                    true
+                }
+            }
+        };
+        supervisor.emit(&app_handle, &id);
+        if failed {
+            return;
+        }
+    }
+}
+
+/// Start a local server under supervision, resolving once it's ready
+///
+/// The process is restarted with backoff if it exits or fails
+/// `MAX_HEALTH_FAILURES` health checks in a row. Starting an id that's
+/// already running with the same config returns it as is; a different
+/// config replaces it.
+#[tauri::command]
+pub async fn start_supervised_process(
+    config: ProcessConfig,
+    app_handle: AppHandle,
+) -> Result<SupervisedProcess, String> {
+    tokio::task::spawn_blocking(move || {
+        let supervisor = app_handle.state::<Supervisor>();
+        supervisor.start(config, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Stop a supervised process and every process it started
+#[tauri::command]
+pub async fn stop_supervised_process(id: String, app_handle: AppHandle) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let supervisor = app_handle.state::<Supervisor>();
+        supervisor.stop(&id, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub fn list_supervised_processes(
+    supervisor: tauri::State<'_, Supervisor>,
+) -> Vec<SupervisedProcess> {
+    supervisor.list()
+}
+
+/// Recent output of a supervised process, or of all of them when `id` is
+/// omitted, oldest first
+#[tauri::command]
+pub fn get_process_logs(
+    id: Option<String>,
+    supervisor: tauri::State<'_, Supervisor>,
+) -> Vec<LogLine> {
+    supervisor.logs.history(id.as_deref())
+}
+
+/// Send each new line a supervised process writes to `on_line`, following
+/// every process when `id` is omitted
+///
+/// Pair with `get_process_logs` to fill in what came before.
+#[tauri::command]
+pub fn watch_process_logs(
+    id: Option<String>,
+    on_line: Channel<LogLine>,
+    supervisor: tauri::State<'_, Supervisor>,
+) {
+    supervisor.logs.watch(id, on_line);
+}
+
+/// Stop sending lines to the channel passed to `watch_process_logs`
+#[tauri::command]
+pub fn unwatch_process_logs(channel_id: u32, supervisor: tauri::State<'_, Supervisor>) {
+    supervisor.logs.unwatch(channel_id);
+}
@@ -0,0 +1,236 @@
+use accessibility_sys::{
+    kAXErrorFailure, kAXErrorSuccess, kAXValueTypeCFRange, AXError, AXIsProcessTrusted,
+    AXUIElementCopyAttributeValue, AXUIElementCreateApplication, AXUIElementCreateSystemWide,
+    AXUIElementIsAttributeSettable, AXUIElementPerformAction, AXUIElementSetAttributeValue,
+    AXValueGetValue, AXValueRef,
+};
+use core_foundation_sys::array::{CFArrayGetCount, CFArrayGetValueAtIndex, CFArrayRef};
+use core_foundation_sys::base::{
+    kCFAllocatorDefault, Boolean, CFGetTypeID, CFIndex, CFRange, CFRelease, CFTypeRef,
+};
+use core_foundation_sys::string::{
+    kCFStringEncodingUTF8, CFStringCreateWithBytes, CFStringGetCString, CFStringGetLength,
+    CFStringGetMaximumSizeForEncoding, CFStringGetTypeID, CFStringRef,
+};
+use std::ffi::CStr;
+use std::ptr;
+
+const FOCUSED_UI_ELEMENT: &str = "AXFocusedUIElement";
+const FOCUSED_WINDOW: &str = "AXFocusedWindow";
+const RAISE: &str = "AXRaise";
+const SELECTED_TEXT: &str = "AXSelectedText";
+const SELECTED_TEXT_RANGE: &str = "AXSelectedTextRange";
+const TITLE: &str = "AXTitle";
+const VALUE: &str = "AXValue";
+const WINDOWS: &str = "AXWindows";
+
+/// An owned Core Foundation object, released when dropped
+struct CfOwned(CFTypeRef);
+
+impl CfOwned {
+    fn new(object: CFTypeRef) -> Option<Self> {
+        (!object.is_null()).then_some(Self(object))
+    }
+
+    fn string(text: &str) -> Result<Self, String> {
+        let string = unsafe {
+            CFStringCreateWithBytes(
+                kCFAllocatorDefault,
+                text.as_ptr(),
+                text.len() as CFIndex,
+                kCFStringEncodingUTF8,
+                false as Boolean,
+            )
+        };
+        Self::new(string as CFTypeRef).ok_or_else(|| "Failed to create CFString".to_string())
+    }
+
+    fn as_string(&self) -> CFStringRef {
+        self.0 as CFStringRef
+    }
+
+    /// The object's text, if it is a CFString
+    fn to_text(&self) -> Option<String> {
+        unsafe {
+            if CFGetTypeID(self.0) != CFStringGetTypeID() {
+                return None;
+            }
+            let length = CFStringGetLength(self.as_string());
+            let capacity = CFStringGetMaximumSizeForEncoding(length, kCFStringEncodingUTF8) + 1;
+            let mut buffer = vec![0u8; capacity as usize];
+            let copied = CFStringGetCString(
+                self.as_string(),
+                buffer.as_mut_ptr() as *mut _,
+                capacity,
+                kCFStringEncodingUTF8,
+            );
+            if copied == 0 {
+                return None;
+            }
+            CStr::from_bytes_until_nul(&buffer)
+                .ok()
+                .map(|text| text.to_string_lossy().into_owned())
+        }
+    }
+}
+
+/// Copy an accessibility element's attribute value
+unsafe fn copy_attribute(element: CFTypeRef, attribute: &str) -> Result<CfOwned, AXError> {
+    let attribute = CfOwned::string(attribute).map_err(|_| kAXErrorFailure)?;
+    let mut value: CFTypeRef = ptr::null();
+    let error = AXUIElementCopyAttributeValue(element as _, attribute.as_string(), &mut value);
+    match CfOwned::new(value) {
+        Some(value) if error == kAXErrorSuccess => Ok(value),
+        _ => Err(error),
+    }
+}
+
+impl Drop for CfOwned {
+    fn drop(&mut self) {
+        unsafe { CFRelease(self.0) };
+    }
+}
+
+/// Insert text at the caret of the focused element through the Accessibility
+/// API, replacing the selection if there is one
+///
+/// Setting `AXSelectedText` never touches the clipboard or sends key events,
+/// so it works in apps that block the paste shortcut. Elements that don't
+/// expose a settable selection (games, some Electron and Java apps, secure
+/// fields) return an error so the caller can pick another strategy.
+pub fn insert_text(text: &str) -> Result<(), String> {
+    if !unsafe { AXIsProcessTrusted() } {
+        return Err("Accessibility permission is required to insert text directly".to_string());
+    }
+
+    let selected_text_attribute = CfOwned::string(SELECTED_TEXT)?;
+    let value = CfOwned::string(text)?;
+
+    unsafe {
+        let system = CfOwned::new(AXUIElementCreateSystemWide() as CFTypeRef)
+            .ok_or_else(|| "Failed to create system-wide accessibility element".to_string())?;
+
+        let focused = copy_attribute(system.0, FOCUSED_UI_ELEMENT)
+            .map_err(|error| ax_error("No focused element to insert into", error))?;
+
+        let mut settable: Boolean = 0;
+        let error = AXUIElementIsAttributeSettable(
+            focused.0 as _,
+            selected_text_attribute.as_string(),
+            &mut settable,
+        );
+        if error != kAXErrorSuccess || settable == 0 {
+            return Err(ax_error(
+                "The focused element doesn't accept direct text insertion",
+                error,
+            ));
+        }
+
+        let error = AXUIElementSetAttributeValue(
+            focused.0 as _,
+            selected_text_attribute.as_string(),
+            value.0,
+        );
+        if error != kAXErrorSuccess {
+            return Err(ax_error("Failed to insert text", error));
+        }
+    }
+    Ok(())
+}
+
+/// The character before the caret in the focused text element, None at the
+/// start of the text
+///
+/// Fails without Accessibility permission and for elements that don't expose
+/// their value and selection, which covers most non-native text views.
+pub fn char_before_caret() -> Result<Option<char>, String> {
+    if !unsafe { AXIsProcessTrusted() } {
+        return Err("Accessibility permission is required to read the text".to_string());
+    }
+    unsafe {
+        let system = CfOwned::new(AXUIElementCreateSystemWide() as CFTypeRef)
+            .ok_or_else(|| "Failed to create system-wide accessibility element".to_string())?;
+        let focused = copy_attribute(system.0, FOCUSED_UI_ELEMENT)
+            .map_err(|error| ax_error("No focused element", error))?;
+
+        let range_value = copy_attribute(focused.0, SELECTED_TEXT_RANGE)
+            .map_err(|error| ax_error("The focused element has no selection", error))?;
+        let mut range = CFRange {
+            location: 0,
+            length: 0,
+        };
+        let read = AXValueGetValue(
+            range_value.0 as AXValueRef,
+            kAXValueTypeCFRange,
+            &mut range as *mut CFRange as *mut _,
+        );
+        if !read {
+            return Err("The focused element's selection isn't a range".to_string());
+        }
+        if range.location <= 0 {
+            return Ok(None);
+        }
+
+        let value = copy_attribute(focused.0, VALUE)
+            .ok()
+            .and_then(|value| value.to_text())
+            .ok_or_else(|| "The focused element doesn't expose its text".to_string())?;
+        // The range counts UTF-16 code units
+        let before: Vec<u16> = value.encode_utf16().take(range.location as usize).collect();
+        Ok(String::from_utf16_lossy(&before).chars().last())
+    }
+}
+
+fn ax_error(message: &str, error: AXError) -> String {
+    if error == kAXErrorSuccess {
+        message.to_string()
+    } else {
+        format!("{} (AXError {})", message, error)
+    }
+}
+
+/// Title of an application's focused window
+///
+/// None without Accessibility permission or when the app has no window.
+pub fn focused_window_title(process_id: i32) -> Option<String> {
+    if !unsafe { AXIsProcessTrusted() } {
+        return None;
+    }
+    unsafe {
+        let application = CfOwned::new(AXUIElementCreateApplication(process_id) as CFTypeRef)?;
+        let window = copy_attribute(application.0, FOCUSED_WINDOW).ok()?;
+        copy_attribute(window.0, TITLE).ok()?.to_text()
+    }
+}
+
+/// Raise the first of an application's windows whose title contains
+/// `needle` (case-insensitive), returning whether one was found
+pub fn raise_window_titled(process_id: i32, needle: &str) -> bool {
+    if !unsafe { AXIsProcessTrusted() } {
+        return false;
+    }
+    let needle = needle.trim().to_lowercase();
+    let Ok(raise) = CfOwned::string(RAISE) else {
+        return false;
+    };
+    unsafe {
+        let Some(application) =
+            CfOwned::new(AXUIElementCreateApplication(process_id) as CFTypeRef)
+        else {
+            return false;
+        };
+        let Ok(windows) = copy_attribute(application.0, WINDOWS) else {
+            return false;
+        };
+        let windows = windows.0 as CFArrayRef;
+        for index in 0..CFArrayGetCount(windows) {
+            // Borrowed from the array, so not released here
+            let window = CFArrayGetValueAtIndex(windows, index) as CFTypeRef;
+            let title = copy_attribute(window, TITLE).ok().and_then(|title| title.to_text());
+            if title.is_some_and(|title| title.to_lowercase().contains(&needle)) {
+                return AXUIElementPerformAction(window as _, raise.as_string()) == kAXErrorSuccess;
+            }
+        }
+    }
+    false
+}
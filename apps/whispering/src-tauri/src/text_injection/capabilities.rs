@@ -0,0 +1,80 @@
+use serde::Serialize;
+
+#[cfg(not(target_os = "linux"))]
+use super::secure_input;
+#[cfg(target_os = "linux")]
+use super::wayland;
+
+/// How `write_text` can reach other applications in this session - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextInjectionCapabilities {
+    /// "macos", "windows", "x11", "wayland", or the OS name elsewhere
+    pub session_type: String,
+    /// What synthesizes keystrokes: "enigo", "wtype" or "ydotool"
+    pub input_backend: Option<String>,
+    /// Simulated paste and typing reach native windows; false on Wayland
+    /// without wtype or ydotool, where only XWayland windows receive them
+    pub native_input: bool,
+    /// macOS secure input is on, so simulated paste and typing are dropped
+    /// until the password field or Secure Keyboard Entry is left
+    pub secure_input: bool,
+    /// Why injection may not work here, and what to install
+    pub warning: Option<String>,
+}
+
+/// Report the session type and which input backend `write_text` will use,
+/// so the app can explain failures before the user dictates into the void
+#[tauri::command]
+pub fn get_text_injection_capabilities() -> TextInjectionCapabilities {
+    #[cfg(target_os = "linux")]
+    {
+        if !wayland::is_wayland_session() {
+            return TextInjectionCapabilities {
+                session_type: "x11".to_string(),
+                input_backend: Some("enigo".to_string()),
+                native_input: true,
+                secure_input: false,
+                warning: None,
+            };
+        }
+
+        match wayland::tool() {
+            Some(tool) => TextInjectionCapabilities {
+                session_type: "wayland".to_string(),
+                input_backend: Some(tool.name().to_string()),
+                native_input: true,
+                secure_input: false,
+                warning: None,
+            },
+            None => TextInjectionCapabilities {
+                session_type: "wayland".to_string(),
+                input_backend: Some("enigo".to_string()),
+                native_input: false,
+                secure_input: false,
+                warning: Some(
+                    "Wayland blocks simulated input from X11 tools. Install wtype (Sway, \
+                     Hyprland, KDE) or ydotool with ydotoold running (GNOME) so text can be \
+                     inserted into native Wayland apps."
+                        .to_string(),
+                ),
+            },
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let secure_input = secure_input::is_enabled();
+        TextInjectionCapabilities {
+            session_type: std::env::consts::OS.to_string(),
+            input_backend: Some("enigo".to_string()),
+            native_input: true,
+            secure_input,
+            warning: secure_input.then(|| {
+                "Secure input is on, so macOS blocks inserted text until the password field \
+                 or Secure Keyboard Entry is left"
+                    .to_string()
+            }),
+        }
+    }
+}
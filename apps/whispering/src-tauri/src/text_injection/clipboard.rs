@@ -0,0 +1,289 @@
+use tauri::image::Image;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+/// Everything that was on the clipboard before `write_text` replaced it
+///
+/// On macOS and Windows every format is captured byte for byte (rich text,
+/// HTML, images, file lists, app-private formats), so restoring it is
+/// lossless. Elsewhere, or if native capture fails, plain text and images are
+/// kept through the clipboard plugin.
+pub struct ClipboardSnapshot(Contents);
+
+enum Contents {
+    Native(platform::Formats),
+    Basic {
+        text: Option<String>,
+        image: Option<Image<'static>>,
+    },
+}
+
+impl ClipboardSnapshot {
+    pub fn capture(app: &tauri::AppHandle) -> Self {
+        let contents = match platform::capture() {
+            Ok(formats) => Contents::Native(formats),
+            Err(e) => {
+                if platform::NATIVE {
+                    eprintln!("[Write Text] Keeping only text and images on the clipboard: {}", e);
+                }
+                Contents::Basic {
+                    text: app.clipboard().read_text().ok(),
+                    image: app.clipboard().read_image().ok().map(|image| image.to_owned()),
+                }
+            }
+        };
+        Self(contents)
+    }
+
+    /// Put the captured content back; an empty snapshot leaves the clipboard
+    /// as it is
+    pub fn restore(self, app: &tauri::AppHandle) -> Result<(), String> {
+        match self.0 {
+            Contents::Native(formats) => platform::restore(formats),
+            Contents::Basic { text, image } => {
+                if let Some(image) = image {
+                    app.clipboard()
+                        .write_image(&image)
+                        .map_err(|e| format!("Failed to restore clipboard: {}", e))
+                } else if let Some(text) = text {
+                    app.clipboard()
+                        .write_text(text)
+                        .map_err(|e| format!("Failed to restore clipboard: {}", e))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use objc2::msg_send;
+    use objc2::rc::{Allocated, Retained};
+    use objc2::runtime::{AnyClass, AnyObject, Bool};
+    use objc2_foundation::{NSData, NSString};
+
+    pub const NATIVE: bool = true;
+
+    /// Each pasteboard item's (type, data) pairs, in order
+    pub struct Formats(Vec<Vec<(String, Vec<u8>)>>);
+
+    fn class(name: &std::ffi::CStr) -> Result<&'static AnyClass, String> {
+        AnyClass::get(name).ok_or_else(|| format!("{} is unavailable", name.to_string_lossy()))
+    }
+
+    fn general_pasteboard() -> Result<Retained<AnyObject>, String> {
+        let pasteboard: Option<Retained<AnyObject>> =
+            unsafe { msg_send![class(c"NSPasteboard")?, generalPasteboard] };
+        pasteboard.ok_or_else(|| "No general pasteboard".to_string())
+    }
+
+    pub fn capture() -> Result<Formats, String> {
+        let pasteboard = general_pasteboard()?;
+        let mut items = Vec::new();
+        unsafe {
+            let pasteboard_items: Option<Retained<AnyObject>> =
+                msg_send![&*pasteboard, pasteboardItems];
+            let Some(pasteboard_items) = pasteboard_items else {
+                return Ok(Formats(items));
+            };
+            let item_count: usize = msg_send![&*pasteboard_items, count];
+            for index in 0..item_count {
+                let item: Retained<AnyObject> = msg_send![&*pasteboard_items, objectAtIndex: index];
+                let types: Retained<AnyObject> = msg_send![&*item, types];
+                let type_count: usize = msg_send![&*types, count];
+
+                let mut formats = Vec::with_capacity(type_count);
+                for type_index in 0..type_count {
+                    let pasteboard_type: Retained<NSString> =
+                        msg_send![&*types, objectAtIndex: type_index];
+                    // Promised data that the source app can no longer provide
+                    let data: Option<Retained<NSData>> =
+                        msg_send![&*item, dataForType: &*pasteboard_type];
+                    if let Some(data) = data {
+                        formats.push((pasteboard_type.to_string(), data.to_vec()));
+                    }
+                }
+                items.push(formats);
+            }
+        }
+        Ok(Formats(items))
+    }
+
+    pub fn restore(formats: Formats) -> Result<(), String> {
+        if formats.0.is_empty() {
+            return Ok(());
+        }
+        let pasteboard = general_pasteboard()?;
+        let item_class = class(c"NSPasteboardItem")?;
+        let array_class = class(c"NSMutableArray")?;
+        unsafe {
+            let items: Retained<AnyObject> = msg_send![array_class, array];
+            for formats in &formats.0 {
+                let allocated: Allocated<AnyObject> = msg_send![item_class, alloc];
+                let item: Retained<AnyObject> = msg_send![allocated, init];
+                for (pasteboard_type, bytes) in formats {
+                    let pasteboard_type = NSString::from_str(pasteboard_type);
+                    let data = NSData::with_bytes(bytes);
+                    let _: Bool = msg_send![&*item, setData: &*data, forType: &*pasteboard_type];
+                }
+                let _: () = msg_send![&*items, addObject: &*item];
+            }
+
+            let _: isize = msg_send![&*pasteboard, clearContents];
+            let written: Bool = msg_send![&*pasteboard, writeObjects: &*items];
+            if !written.as_bool() {
+                return Err("Failed to restore clipboard".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use std::ptr;
+    use std::time::Duration;
+    use windows_sys::Win32::System::DataExchange::{
+        CloseClipboard, EmptyClipboard, EnumClipboardFormats, GetClipboardData, OpenClipboard,
+        SetClipboardData,
+    };
+    use windows_sys::Win32::System::Memory::{
+        GlobalAlloc, GlobalFree, GlobalLock, GlobalSize, GlobalUnlock, GMEM_MOVEABLE,
+    };
+
+    pub const NATIVE: bool = true;
+
+    /// Formats whose handle isn't global memory (bitmaps, metafiles,
+    /// palettes, owner-drawn and GDI object formats); Windows synthesizes
+    /// CF_BITMAP from the CF_DIB data that is kept
+    const CF_BITMAP: u32 = 2;
+    const CF_METAFILEPICT: u32 = 3;
+    const CF_PALETTE: u32 = 9;
+    const CF_ENHMETAFILE: u32 = 14;
+    const CF_OWNERDISPLAY: u32 = 0x80;
+    const CF_DSPBITMAP: u32 = 0x82;
+    const CF_DSPMETAFILEPICT: u32 = 0x83;
+    const CF_DSPENHMETAFILE: u32 = 0x8E;
+    const CF_PRIVATEFIRST: u32 = 0x200;
+    const CF_GDIOBJLAST: u32 = 0x3FF;
+
+    /// Another app may hold the clipboard open for a moment
+    const OPEN_ATTEMPTS: u32 = 10;
+    const OPEN_RETRY_DELAY: Duration = Duration::from_millis(10);
+
+    /// (format id, data) pairs in the order the owner offered them
+    pub struct Formats(Vec<(u32, Vec<u8>)>);
+
+    fn is_memory_format(format: u32) -> bool {
+        !matches!(
+            format,
+            CF_BITMAP
+                | CF_METAFILEPICT
+                | CF_PALETTE
+                | CF_ENHMETAFILE
+                | CF_OWNERDISPLAY
+                | CF_DSPBITMAP
+                | CF_DSPMETAFILEPICT
+                | CF_DSPENHMETAFILE
+                | CF_PRIVATEFIRST..=CF_GDIOBJLAST
+        )
+    }
+
+    /// Holds the clipboard open, closing it when dropped
+    struct OpenClipboardGuard;
+
+    impl OpenClipboardGuard {
+        fn open() -> Result<Self, String> {
+            for _ in 0..OPEN_ATTEMPTS {
+                if unsafe { OpenClipboard(ptr::null_mut()) } != 0 {
+                    return Ok(Self);
+                }
+                std::thread::sleep(OPEN_RETRY_DELAY);
+            }
+            Err("The clipboard is in use by another application".to_string())
+        }
+    }
+
+    impl Drop for OpenClipboardGuard {
+        fn drop(&mut self) {
+            unsafe { CloseClipboard() };
+        }
+    }
+
+    pub fn capture() -> Result<Formats, String> {
+        let _guard = OpenClipboardGuard::open()?;
+        let mut formats = Vec::new();
+        let mut format = 0;
+        loop {
+            format = unsafe { EnumClipboardFormats(format) };
+            if format == 0 {
+                break;
+            }
+            if !is_memory_format(format) {
+                continue;
+            }
+            unsafe {
+                let handle = GetClipboardData(format);
+                if handle.is_null() {
+                    continue;
+                }
+                let size = GlobalSize(handle);
+                let data = GlobalLock(handle) as *const u8;
+                if data.is_null() {
+                    continue;
+                }
+                formats.push((format, std::slice::from_raw_parts(data, size).to_vec()));
+                GlobalUnlock(handle);
+            }
+        }
+        Ok(Formats(formats))
+    }
+
+    pub fn restore(formats: Formats) -> Result<(), String> {
+        if formats.0.is_empty() {
+            return Ok(());
+        }
+        let _guard = OpenClipboardGuard::open()?;
+        unsafe {
+            if EmptyClipboard() == 0 {
+                return Err("Failed to clear the clipboard".to_string());
+            }
+            for (format, bytes) in &formats.0 {
+                let handle = GlobalAlloc(GMEM_MOVEABLE, bytes.len().max(1));
+                if handle.is_null() {
+                    return Err("Out of memory restoring the clipboard".to_string());
+                }
+                let data = GlobalLock(handle) as *mut u8;
+                if data.is_null() {
+                    GlobalFree(handle);
+                    continue;
+                }
+                ptr::copy_nonoverlapping(bytes.as_ptr(), data, bytes.len());
+                GlobalUnlock(handle);
+                // The clipboard owns the memory once this succeeds
+                if SetClipboardData(*format, handle).is_null() {
+                    GlobalFree(handle);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+mod platform {
+    /// X11 and Wayland clipboards are served live by the owning app, so there
+    /// is no byte-for-byte copy to take; text and images go through the plugin
+    pub const NATIVE: bool = false;
+
+    pub struct Formats;
+
+    pub fn capture() -> Result<Formats, String> {
+        Err("Native clipboard capture isn't available on this platform".to_string())
+    }
+
+    pub fn restore(_formats: Formats) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use super::focus::FocusedApplication;
+
+/// Event emitted when `write_text` copies instead of inserting
+pub const INSERTION_EXCLUDED_EVENT: &str = "insertion-excluded";
+
+/// Password managers, excluded until the user saves their own list
+const DEFAULT_EXCLUDED_APPS: &[&str] = &[
+    "com.1password.1password",
+    "com.agilebits.onepassword7",
+    "1Password",
+    "com.bitwarden.desktop",
+    "Bitwarden",
+    "org.keepassxc.keepassxc",
+    "KeePassXC",
+    "Dashlane",
+    "LastPass",
+];
+
+/// Payload for `INSERTION_EXCLUDED_EVENT` - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InsertionExcluded {
+    /// `None` when the focused application couldn't be determined
+    pub application: Option<FocusedApplication>,
+}
+
+/// Applications `write_text` never types or pastes into
+///
+/// Entries are bundle ids, executable names or app names, matched like paste
+/// shortcut rules. Persisted as a JSON array in the app config directory and
+/// enforced here rather than in the frontend, so no settings mix-up can paste
+/// a transcript into a password manager or banking app.
+pub struct ExcludedApps {
+    apps: Mutex<Option<Vec<String>>>,
+}
+
+impl ExcludedApps {
+    pub fn new() -> Self {
+        Self {
+            apps: Mutex::new(None),
+        }
+    }
+
+    /// Get the excluded apps, reading them from disk on first use
+    pub fn get(&self, app: &AppHandle) -> Result<Vec<String>, String> {
+        let mut apps = self.apps.lock().unwrap();
+        if let Some(apps) = apps.as_ref() {
+            return Ok(apps.clone());
+        }
+
+        let path = excluded_apps_path(app)?;
+        let loaded: Vec<String> = if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read excluded apps: {}", e))?;
+            serde_json::from_str(&contents)
+                .map_err(|e| format!("Failed to parse excluded apps: {}", e))?
+        } else {
+            DEFAULT_EXCLUDED_APPS.iter().map(|app| app.to_string()).collect()
+        };
+        Ok(apps.insert(loaded).clone())
+    }
+
+    /// Replace the excluded apps, dropping blanks and duplicates
+    pub fn set(&self, app: &AppHandle, apps: Vec<String>) -> Result<Vec<String>, String> {
+        let mut cleaned: Vec<String> = Vec::with_capacity(apps.len());
+        for entry in apps {
+            let entry = entry.trim();
+            if !entry.is_empty() && !cleaned.iter().any(|kept| kept.eq_ignore_ascii_case(entry)) {
+                cleaned.push(entry.to_string());
+            }
+        }
+
+        let path = excluded_apps_path(app)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let contents = serde_json::to_string_pretty(&cleaned)
+            .map_err(|e| format!("Failed to serialize excluded apps: {}", e))?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to save excluded apps: {}", e))?;
+
+        *self.apps.lock().unwrap() = Some(cleaned.clone());
+        Ok(cleaned)
+    }
+
+    /// Whether `focused` is excluded
+    ///
+    /// This guards password managers, so it fails closed: an unreadable list
+    /// or an unknown focused app counts as excluded unless the list is empty.
+    fn is_excluded(&self, app: &AppHandle, focused: Option<&FocusedApplication>) -> bool {
+        let apps = match self.get(app) {
+            Ok(apps) => apps,
+            Err(e) => {
+                eprintln!("[Write Text] Treating the focused app as excluded: {}", e);
+                return true;
+            }
+        };
+        if apps.is_empty() {
+            return false;
+        }
+        match focused {
+            Some(focused) => apps.iter().any(|entry| focused.matches(entry)),
+            None => {
+                eprintln!("[Write Text] Focused app unknown, treating it as excluded");
+                true
+            }
+        }
+    }
+}
+
+impl Default for ExcludedApps {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn excluded_apps_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join("excluded-apps.json"))
+        .map_err(|e| format!("Failed to resolve config directory: {}", e))
+}
+
+/// When the focused application is excluded, or can't be checked, copy the
+/// text to the clipboard instead of inserting it and report that it was
+/// handled
+pub fn copy_if_excluded(
+    app: &AppHandle,
+    focused: Option<&FocusedApplication>,
+    text: &str,
+) -> Result<bool, String> {
+    if !app.state::<ExcludedApps>().is_excluded(app, focused) {
+        return Ok(false);
+    }
+    println!(
+        "[Write Text] {} is excluded, copying the text to the clipboard instead",
+        focused.map_or("The focused app", |application| application.name.as_str())
+    );
+    app.clipboard()
+        .write_text(text)
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
+    let application = focused.cloned();
+    let _ = app.emit(INSERTION_EXCLUDED_EVENT, InsertionExcluded { application });
+    Ok(true)
+}
+
+/// Get the applications text is never inserted into
+#[tauri::command]
+pub fn get_excluded_apps(
+    app_handle: AppHandle,
+    excluded_apps: tauri::State<'_, ExcludedApps>,
+) -> Result<Vec<String>, String> {
+    excluded_apps.get(&app_handle)
+}
+
+/// Replace the excluded applications, returning the list that was stored
+#[tauri::command]
+pub fn set_excluded_apps(
+    apps: Vec<String>,
+    app_handle: AppHandle,
+    excluded_apps: tauri::State<'_, ExcludedApps>,
+) -> Result<Vec<String>, String> {
+    excluded_apps.set(&app_handle, apps)
+}
@@ -0,0 +1,347 @@
+use serde::Serialize;
+
+/// The application that will receive inserted text - sent to frontend
+///
+/// Fields a platform can't provide are None; the window title in particular
+/// needs Accessibility permission on macOS.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FocusedApplication {
+    /// Display name ("Terminal", "WindowsTerminal", "Alacritty")
+    pub name: String,
+    /// macOS bundle identifier ("com.apple.Terminal")
+    pub bundle_id: Option<String>,
+    /// Executable file name ("WindowsTerminal.exe", "alacritty")
+    pub executable: Option<String>,
+    pub process_id: Option<u32>,
+    /// Title of the focused window ("notes.md - Visual Studio Code")
+    pub window_title: Option<String>,
+}
+
+impl FocusedApplication {
+    /// Whether a rule's app pattern names this application
+    ///
+    /// Patterns compare case-insensitively against the bundle id, the
+    /// executable (with or without `.exe`) and the display name.
+    pub fn matches(&self, pattern: &str) -> bool {
+        let pattern = pattern.trim();
+        let executable_stem = self
+            .executable
+            .as_deref()
+            .map(|executable| executable.trim_end_matches(".exe").trim_end_matches(".EXE"));
+        [
+            self.bundle_id.as_deref(),
+            self.executable.as_deref(),
+            executable_stem,
+            Some(self.name.as_str()),
+        ]
+        .into_iter()
+        .flatten()
+        .any(|candidate| candidate.eq_ignore_ascii_case(pattern))
+    }
+
+    /// Whether an identifier names this application or appears in its
+    /// window title (both case-insensitive)
+    pub fn matches_window(&self, identifier: &str) -> bool {
+        let needle = identifier.trim().to_lowercase();
+        self.matches(identifier)
+            || self
+                .window_title
+                .as_ref()
+                .is_some_and(|title| title.to_lowercase().contains(&needle))
+    }
+}
+
+/// Look up the frontmost application
+pub fn focused_application() -> Result<FocusedApplication, String> {
+    platform::focused_application()
+}
+
+/// Report the frontmost application and its focused window, for app-specific
+/// paste shortcuts, transformations and auto-paste rules
+#[tauri::command]
+pub async fn get_focused_application() -> Result<FocusedApplication, String> {
+    tokio::task::spawn_blocking(focused_application)
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(target_os = "macos")]
+pub(super) mod platform {
+    use super::FocusedApplication;
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject};
+    use objc2_foundation::NSString;
+
+    pub fn focused_application() -> Result<FocusedApplication, String> {
+        let workspace_class =
+            AnyClass::get(c"NSWorkspace").ok_or_else(|| "NSWorkspace is unavailable".to_string())?;
+        unsafe {
+            let workspace: Retained<AnyObject> = msg_send![workspace_class, sharedWorkspace];
+            let application: Option<Retained<AnyObject>> =
+                msg_send![&*workspace, frontmostApplication];
+            let application =
+                application.ok_or_else(|| "No application is frontmost".to_string())?;
+
+            let mut focused = describe(&application);
+            focused.window_title = focused
+                .process_id
+                .and_then(|process_id| i32::try_from(process_id).ok())
+                .and_then(super::super::accessibility::focused_window_title);
+            Ok(focused)
+        }
+    }
+
+    /// Name, bundle id, executable and process of an `NSRunningApplication`,
+    /// without the window title
+    ///
+    /// # Safety
+    /// `application` must be an `NSRunningApplication`.
+    pub unsafe fn describe(application: &AnyObject) -> FocusedApplication {
+        let name: Option<Retained<NSString>> = msg_send![application, localizedName];
+        let bundle_id: Option<Retained<NSString>> = msg_send![application, bundleIdentifier];
+        let executable_url: Option<Retained<AnyObject>> = msg_send![application, executableURL];
+        let executable = match executable_url {
+            Some(url) => {
+                let component: Option<Retained<NSString>> = msg_send![&*url, lastPathComponent];
+                component.map(|component| component.to_string())
+            }
+            None => None,
+        };
+        let process_id: i32 = msg_send![application, processIdentifier];
+
+        FocusedApplication {
+            name: name
+                .map(|name| name.to_string())
+                .or_else(|| executable.clone())
+                .unwrap_or_default(),
+            bundle_id: bundle_id.map(|bundle_id| bundle_id.to_string()),
+            executable,
+            process_id: u32::try_from(process_id).ok(),
+            window_title: None,
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub(super) mod platform {
+    use super::FocusedApplication;
+    use std::path::Path;
+    use windows_sys::Win32::Foundation::{CloseHandle, HWND};
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        GetForegroundWindow, GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
+    };
+
+    fn window_title(window: HWND) -> Option<String> {
+        unsafe {
+            let length = GetWindowTextLengthW(window);
+            if length <= 0 {
+                return None;
+            }
+            let mut buffer = vec![0u16; length as usize + 1];
+            let copied = GetWindowTextW(window, buffer.as_mut_ptr(), buffer.len() as i32);
+            (copied > 0).then(|| String::from_utf16_lossy(&buffer[..copied as usize]))
+        }
+    }
+
+    fn process_image_path(process_id: u32) -> Option<String> {
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, process_id);
+            if process.is_null() {
+                return None;
+            }
+            let mut buffer = [0u16; 1024];
+            let mut length = buffer.len() as u32;
+            let ok = QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                buffer.as_mut_ptr(),
+                &mut length,
+            );
+            CloseHandle(process);
+            (ok != 0).then(|| String::from_utf16_lossy(&buffer[..length as usize]))
+        }
+    }
+
+    pub fn focused_application() -> Result<FocusedApplication, String> {
+        let window = unsafe { GetForegroundWindow() };
+        if window.is_null() {
+            return Err("No window is focused".to_string());
+        }
+        describe(window)
+    }
+
+    /// The application that owns a top-level window
+    pub fn describe(window: HWND) -> Result<FocusedApplication, String> {
+        let mut process_id = 0;
+        unsafe { GetWindowThreadProcessId(window, &mut process_id) };
+        if process_id == 0 {
+            return Err("Failed to find the window's process".to_string());
+        }
+
+        // Elevated processes can't be opened from an unelevated app
+        let image_path = process_image_path(process_id);
+        let path = image_path.as_deref().map(Path::new);
+        let executable = path
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned());
+        let name = path
+            .and_then(|path| path.file_stem())
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| format!("Process {}", process_id));
+
+        Ok(FocusedApplication {
+            name,
+            bundle_id: None,
+            executable,
+            process_id: Some(process_id),
+            window_title: window_title(window),
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(super) mod platform {
+    use super::FocusedApplication;
+    use serde_json::Value;
+    use std::process::Command;
+
+    pub fn run(program: &str, args: &[&str]) -> Result<String, String> {
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "{} failed: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn executable_of(process_id: Option<u32>) -> Option<String> {
+        let path = std::fs::read_link(format!("/proc/{}/exe", process_id?)).ok()?;
+        path.file_name().map(|name| name.to_string_lossy().into_owned())
+    }
+
+    pub fn from_window(
+        class: Option<String>,
+        process_id: Option<u32>,
+        window_title: Option<String>,
+    ) -> Result<FocusedApplication, String> {
+        let executable = executable_of(process_id);
+        let name = class
+            .clone()
+            .or_else(|| executable.clone())
+            .ok_or_else(|| "The window has no class or process".to_string())?;
+        Ok(FocusedApplication {
+            name,
+            bundle_id: None,
+            executable: executable.or(class),
+            process_id,
+            window_title: window_title.filter(|title| !title.is_empty()),
+        })
+    }
+
+    pub fn json_string(value: &Value, key: &str) -> Option<String> {
+        value[key].as_str().filter(|s| !s.is_empty()).map(str::to_string)
+    }
+
+    pub fn json_pid(value: &Value) -> Option<u32> {
+        value["pid"].as_u64().and_then(|pid| u32::try_from(pid).ok())
+    }
+
+    /// Hyprland's IPC reports the active window directly
+    fn hyprland() -> Result<FocusedApplication, String> {
+        let output = run("hyprctl", &["activewindow", "-j"])?;
+        let window: Value = serde_json::from_str(&output)
+            .map_err(|e| format!("Unexpected hyprctl output: {}", e))?;
+        from_window(json_string(&window, "class"), json_pid(&window), json_string(&window, "title"))
+    }
+
+    fn focused_node(node: &Value) -> Option<&Value> {
+        if node["focused"].as_bool() == Some(true) {
+            return Some(node);
+        }
+        ["nodes", "floating_nodes"]
+            .iter()
+            .filter_map(|key| node[key].as_array())
+            .flatten()
+            .find_map(focused_node)
+    }
+
+    /// Sway's tree marks the focused container; native windows carry an
+    /// app_id, XWayland ones an X11 class
+    fn sway() -> Result<FocusedApplication, String> {
+        let output = run("swaymsg", &["-t", "get_tree", "-r"])?;
+        let tree: Value = serde_json::from_str(&output)
+            .map_err(|e| format!("Unexpected swaymsg output: {}", e))?;
+        let node = focused_node(&tree).ok_or_else(|| "No window is focused".to_string())?;
+        let class = json_string(node, "app_id")
+            .or_else(|| json_string(&node["window_properties"], "class"));
+        from_window(class, json_pid(node), json_string(node, "name"))
+    }
+
+    /// The value after `=` (or `#` for window ids) on an xprop output line
+    fn property_value<'a>(output: &'a str, property: &str) -> Option<&'a str> {
+        output
+            .lines()
+            .find(|line| line.starts_with(property))
+            .and_then(|line| line.split_once(['=', '#']))
+            .map(|(_, value)| value.trim())
+    }
+
+    /// Read the active X11 window through xprop, which also covers XWayland
+    /// windows under compositors without an IPC
+    fn x11() -> Result<FocusedApplication, String> {
+        let root = run("xprop", &["-root", "_NET_ACTIVE_WINDOW"])?;
+        let window = property_value(&root, "_NET_ACTIVE_WINDOW")
+            .and_then(|value| value.split([',', ' ']).next())
+            .filter(|window| *window != "0x0")
+            .ok_or_else(|| "No window is focused".to_string())?;
+
+        let properties =
+            run("xprop", &["-id", window, "WM_CLASS", "_NET_WM_PID", "_NET_WM_NAME"])?;
+        // WM_CLASS(STRING) = "instance", "Class"
+        let class = property_value(&properties, "WM_CLASS")
+            .and_then(|value| value.rsplit(',').next())
+            .map(|class| class.trim().trim_matches('"').to_string());
+        let process_id = property_value(&properties, "_NET_WM_PID")
+            .and_then(|value| value.parse::<u32>().ok());
+        let window_title = property_value(&properties, "_NET_WM_NAME")
+            .map(|title| title.trim_matches('"').to_string());
+        from_window(class, process_id, window_title)
+    }
+
+    /// Ask the compositor when it has an IPC for it, otherwise X11
+    ///
+    /// Wayland has no common protocol for the focused window:
+    /// wlr-foreign-toplevel-management needs a Wayland connection of our own
+    /// and GNOME doesn't implement it, so Hyprland and Sway are queried
+    /// through their own tools, which report the same toplevel data.
+    pub fn focused_application() -> Result<FocusedApplication, String> {
+        if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            return hyprland();
+        }
+        if std::env::var_os("SWAYSOCK").is_some() {
+            return sway();
+        }
+        x11()
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    use super::FocusedApplication;
+
+    pub fn focused_application() -> Result<FocusedApplication, String> {
+        Err("Focused application detection isn't available on this platform".to_string())
+    }
+}
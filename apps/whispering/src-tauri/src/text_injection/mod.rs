@@ -0,0 +1,361 @@
+#[cfg(target_os = "macos")]
+mod accessibility;
+mod capabilities;
+mod clipboard;
+mod exclusions;
+mod focus;
+#[cfg(target_os = "linux")]
+mod primary_selection;
+mod secure_input;
+mod shortcut;
+mod spacing;
+mod typing;
+#[cfg(target_os = "windows")]
+mod unicode_input;
+mod window;
+#[cfg(target_os = "linux")]
+mod wayland;
+
+use serde::Deserialize;
+use std::time::Duration;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use clipboard::ClipboardSnapshot;
+use focus::FocusedApplication;
+use shortcut::PasteShortcut;
+use spacing::LeadingSpace;
+
+pub use capabilities::get_text_injection_capabilities;
+pub use exclusions::{get_excluded_apps, set_excluded_apps, ExcludedApps};
+pub use focus::get_focused_application;
+pub use shortcut::PasteShortcutRule;
+pub use spacing::{InsertionHistory, SpacingOptions};
+pub use typing::TypingOptions;
+
+/// Wait after writing the clipboard before pasting
+const DEFAULT_CLIPBOARD_DELAY_MS: u64 = 50;
+
+/// Wait after pasting before restoring the clipboard
+const DEFAULT_PASTE_DELAY_MS: u64 = 100;
+
+/// Wait for the window manager to move focus before inserting into a window
+const FOCUS_SETTLE_DELAY: Duration = Duration::from_millis(150);
+
+const VERIFY_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How `write_text` gets text into the focused application
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InsertionStrategy {
+    /// Clipboard sandwich: copy, simulate the paste shortcut, restore
+    #[default]
+    Paste,
+    /// Type the text character by character, for targets that ignore
+    /// synthetic paste (VMs, some terminals, RDP sessions)
+    Type,
+    /// Set the focused element's selected text through the macOS
+    /// Accessibility API: no clipboard, no key events
+    Accessibility,
+    /// Type through Windows `SendInput` with `KEYEVENTF_UNICODE`, independent
+    /// of the keyboard layout
+    Unicode,
+}
+
+/// Timing and checks for the `"paste"` strategy - from frontend
+///
+/// Slow machines can paste before the clipboard has updated, or restore the
+/// clipboard before the target has read it, which shows up as missing or
+/// stale pastes; longer delays and verification fix both.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteOptions {
+    /// Wait after writing the clipboard before pasting (default 50)
+    pub clipboard_delay_ms: Option<u64>,
+    /// Wait after pasting before restoring the clipboard (default 100)
+    pub paste_delay_ms: Option<u64>,
+    /// Read the clipboard back before pasting and before restoring, retrying
+    /// once when it hasn't updated
+    pub verify: Option<bool>,
+    /// Shortcuts for applications that don't paste with Cmd/Ctrl+V, such as
+    /// `{ app: "gnome-terminal-server", shortcut: "ctrl+shift+v" }`
+    pub shortcut_rules: Option<Vec<PasteShortcutRule>>,
+    /// Linux only: also leave the text in the primary selection so
+    /// middle-click pastes it again
+    pub primary_selection: Option<bool>,
+}
+
+/// Writes text at the cursor position
+///
+/// With the default `"paste"` strategy this uses the clipboard sandwich
+/// technique, which preserves the user's existing clipboard content by:
+/// 1. Saving the current clipboard content, in every format it is offered in
+/// 2. Writing the new text to clipboard
+/// 3. Simulating a paste operation (Cmd+V on macOS, Ctrl+V elsewhere)
+/// 4. Restoring the original clipboard content
+///
+/// This approach is faster than typing character-by-character and preserves
+/// the user's clipboard, making it ideal for inserting transcribed text.
+///
+/// `paste` tunes the delays around the paste and can verify the clipboard
+/// with one retry. Its shortcut rules swap the paste shortcut when the
+/// focused application matches one, e.g. Ctrl+Shift+V for terminals.
+///
+/// `strategy: "type"` types the text instead, never touching the clipboard;
+/// `typing.keystrokeDelayMs` or `typing.maxCharsPerSecond` slows it down for
+/// targets that drop keys, such as remote desktop sessions.
+///
+/// `strategy: "accessibility"` (macOS only) inserts through the focused
+/// element's `AXSelectedText` attribute and fails for elements that don't
+/// support it, leaving the fallback to the caller.
+///
+/// `strategy: "unicode"` (Windows only) types through `SendInput` so emoji and
+/// CJK text survive any keyboard layout. On Windows the paste strategy also
+/// falls back to it when the paste can't be performed.
+///
+/// Nothing is inserted into applications on the excluded list (password
+/// managers by default); the text is copied to the clipboard instead and
+/// `insertion-excluded` is emitted. The same happens when the focused app or
+/// the list can't be determined, unless the list is empty.
+///
+/// On macOS, key-event strategies fail up front while secure input is on,
+/// emitting `secure-input-blocked` so the UI can explain why nothing appeared.
+///
+/// `spacing` adds a leading space, trailing space or trailing newline so
+/// consecutive dictations join cleanly; `leadingSpace: "auto"` only adds the
+/// space after a non-whitespace character.
+#[tauri::command]
+pub async fn write_text(
+    app: tauri::AppHandle,
+    history: tauri::State<'_, InsertionHistory>,
+    text: String,
+    strategy: Option<InsertionStrategy>,
+    paste: Option<PasteOptions>,
+    typing: Option<TypingOptions>,
+    spacing: Option<SpacingOptions>,
+) -> Result<(), String> {
+    let focused = look_up_focused_application().await;
+    let spacing = spacing.unwrap_or_default();
+    let text = spacing::apply(text, &spacing, &history, focused.as_ref()).await;
+    let last_char = text.chars().last();
+
+    let result = insert(&app, focused.as_ref(), text, strategy, paste, typing).await;
+
+    // Only "auto" reads the history
+    if let (Ok(()), Some(last_char), Some(LeadingSpace::Auto)) =
+        (&result, last_char, spacing.leading_space)
+    {
+        history.record(focused.as_ref(), last_char);
+    }
+    result
+}
+
+/// Writes text into a specific window, then gives focus back
+///
+/// `window_identifier` names the window's application the way paste shortcut
+/// rules do (bundle id, executable or name) or is matched against window
+/// titles. The window is brought to the front, the text inserted with the
+/// same options as `write_text`, and the previously focused window is
+/// refocused even if insertion failed, so users can dictate into a notes app
+/// while looking at something else.
+#[tauri::command]
+pub async fn write_text_to_window(
+    app: tauri::AppHandle,
+    window_identifier: String,
+    text: String,
+    strategy: Option<InsertionStrategy>,
+    paste: Option<PasteOptions>,
+    typing: Option<TypingOptions>,
+) -> Result<(), String> {
+    let previous = tokio::task::spawn_blocking(move || window::activate(&window_identifier))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))??;
+    tokio::time::sleep(FOCUS_SETTLE_DELAY).await;
+
+    let focused = look_up_focused_application().await;
+    let result = insert(&app, focused.as_ref(), text, strategy, paste, typing).await;
+
+    let restored = tokio::task::spawn_blocking(move || window::restore(previous))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?;
+    if let Err(e) = restored {
+        eprintln!("[Write Text] Could not refocus the previous window: {}", e);
+    }
+    result
+}
+
+/// Look up the application receiving the text once per insertion, off the
+/// async runtime since X11, Wayland and Accessibility lookups can block
+///
+/// A failed lookup is logged, and the checks that need it are skipped.
+async fn look_up_focused_application() -> Option<FocusedApplication> {
+    match focus::get_focused_application().await {
+        Ok(application) => Some(application),
+        Err(e) => {
+            eprintln!("[Write Text] Couldn't find the focused application: {}", e);
+            None
+        }
+    }
+}
+
+async fn insert(
+    app: &tauri::AppHandle,
+    focused: Option<&FocusedApplication>,
+    text: String,
+    strategy: Option<InsertionStrategy>,
+    paste: Option<PasteOptions>,
+    typing: Option<TypingOptions>,
+) -> Result<(), String> {
+    if exclusions::copy_if_excluded(app, focused, &text)? {
+        return Ok(());
+    }
+
+    let strategy = strategy.unwrap_or_default();
+    if matches!(strategy, InsertionStrategy::Paste | InsertionStrategy::Type) {
+        secure_input::check(app, focused)?;
+    }
+    match strategy {
+        InsertionStrategy::Paste => {
+            paste_text(app, focused, &text, &paste.unwrap_or_default()).await
+        }
+        InsertionStrategy::Type => typing::type_text(text, typing.unwrap_or_default()).await,
+        InsertionStrategy::Accessibility => insert_with_accessibility(text).await,
+        InsertionStrategy::Unicode => send_unicode(text, typing.unwrap_or_default()).await,
+    }
+}
+
+#[cfg(target_os = "macos")]
+async fn insert_with_accessibility(text: String) -> Result<(), String> {
+    // A hung target app can hold an AX call for seconds
+    tokio::task::spawn_blocking(move || accessibility::insert_text(&text))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(not(target_os = "macos"))]
+async fn insert_with_accessibility(_text: String) -> Result<(), String> {
+    Err("Accessibility insertion is only available on macOS".to_string())
+}
+
+#[cfg(target_os = "windows")]
+async fn send_unicode(text: String, options: TypingOptions) -> Result<(), String> {
+    let delay_ms = options.delay_ms();
+    tokio::task::spawn_blocking(move || unicode_input::send_text(&text, delay_ms))
+        .await
+        .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn send_unicode(_text: String, _options: TypingOptions) -> Result<(), String> {
+    Err("Unicode input is only available on Windows".to_string())
+}
+
+/// Called once the clipboard has been restored after a paste that couldn't
+/// happen: Windows types the text with `SendInput` instead, elsewhere the
+/// error is returned
+#[cfg(target_os = "windows")]
+async fn paste_fallback(text: &str, error: String) -> Result<(), String> {
+    eprintln!("[Write Text] {}; typing with SendInput instead", error);
+    send_unicode(text.to_string(), TypingOptions::default()).await
+}
+
+#[cfg(not(target_os = "windows"))]
+async fn paste_fallback(_text: &str, error: String) -> Result<(), String> {
+    Err(error)
+}
+
+async fn paste_text(
+    app: &tauri::AppHandle,
+    focused: Option<&FocusedApplication>,
+    text: &str,
+    options: &PasteOptions,
+) -> Result<(), String> {
+    let clipboard_delay =
+        Duration::from_millis(options.clipboard_delay_ms.unwrap_or(DEFAULT_CLIPBOARD_DELAY_MS));
+    let paste_delay =
+        Duration::from_millis(options.paste_delay_ms.unwrap_or(DEFAULT_PASTE_DELAY_MS));
+    let verify = options.verify.unwrap_or(false);
+    let rules = options.shortcut_rules.as_deref().unwrap_or_default();
+    let shortcut = shortcut::for_application(rules, focused);
+
+    // 1. Save current clipboard content, including images and rich text
+    let original_clipboard = ClipboardSnapshot::capture(app);
+
+    // 2. Write new text to clipboard, giving a slow clipboard one more chance
+    // when verifying
+    let mut attempts = if verify { 2 } else { 1 };
+    loop {
+        attempts -= 1;
+        app.clipboard()
+            .write_text(text)
+            .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
+
+        // Small delay to ensure clipboard is updated
+        tokio::time::sleep(clipboard_delay).await;
+
+        if !verify || clipboard_holds(app, text, clipboard_delay).await {
+            break;
+        }
+        if attempts == 0 {
+            original_clipboard.restore(app)?;
+            let error = "Clipboard did not update in time, so nothing was pasted".to_string();
+            return paste_fallback(text, error).await;
+        }
+        eprintln!("[Write Text] Clipboard not updated yet, retrying");
+    }
+
+    // 3. Simulate paste operation, retrying once if the key events fail
+    if let Err(e) = press_paste_shortcut(&shortcut) {
+        eprintln!("[Write Text] Paste shortcut failed ({}), retrying", e);
+        tokio::time::sleep(clipboard_delay).await;
+        if let Err(e) = press_paste_shortcut(&shortcut) {
+            original_clipboard.restore(app)?;
+            return paste_fallback(text, e).await;
+        }
+    }
+
+    // Small delay to ensure paste completes
+    tokio::time::sleep(paste_delay).await;
+
+    // Restoring while the target is still reading would paste the old content
+    if verify && !clipboard_holds(app, text, paste_delay).await {
+        eprintln!("[Write Text] Clipboard changed during paste; the paste may be incomplete");
+    }
+
+    // 4. Restore original clipboard content
+    original_clipboard.restore(app)?;
+
+    #[cfg(target_os = "linux")]
+    if options.primary_selection.unwrap_or(false) {
+        // The text is already in place, so a missing tool isn't worth failing over
+        if let Err(e) = primary_selection::set(text) {
+            eprintln!("[Write Text] Primary selection not updated: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Simulate the paste shortcut
+///
+/// Wayland sessions go through wtype or ydotool when installed, since
+/// enigo's X11 events only reach XWayland windows there.
+fn press_paste_shortcut(shortcut: &PasteShortcut) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    if let Some(tool) = wayland::tool() {
+        return wayland::press_paste(tool, shortcut);
+    }
+    shortcut::press(shortcut)
+}
+
+/// Poll the clipboard until it reads back `text`, for up to `timeout`
+async fn clipboard_holds(app: &tauri::AppHandle, text: &str, timeout: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        if app.clipboard().read_text().is_ok_and(|content| content == text) {
+            return true;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(VERIFY_POLL_INTERVAL).await;
+    }
+}
@@ -0,0 +1,50 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use super::wayland;
+
+/// Put text in the primary selection, which middle-click pastes on Linux
+///
+/// Selections are served live by their owner, so this hands the text to
+/// wl-copy on Wayland or xclip/xsel on X11, which fork to keep serving it
+/// after `write_text` returns.
+pub fn set(text: &str) -> Result<(), String> {
+    let candidates: &[(&str, &[&str])] = if wayland::is_wayland_session() {
+        &[("wl-copy", &["--primary"])]
+    } else {
+        &[
+            ("xclip", &["-selection", "primary"]),
+            ("xsel", &["--primary", "--input"]),
+        ]
+    };
+    let (program, args) = candidates
+        .iter()
+        .find(|(program, _)| wayland::is_installed(program))
+        .ok_or_else(|| {
+            let names: Vec<&str> = candidates.iter().map(|(program, _)| *program).collect();
+            format!("Install {} to fill the primary selection", names.join(" or "))
+        })?;
+
+    let mut child = Command::new(program)
+        .args(*args)
+        .stdin(Stdio::piped())
+        // The forked server would hold captured pipes open indefinitely
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("Failed to send text to {}: {}", program, e))?;
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} failed ({})", program, status))
+    }
+}
@@ -0,0 +1,61 @@
+use serde::Serialize;
+use tauri::Emitter;
+
+use super::focus::FocusedApplication;
+
+/// Event emitted when `write_text` refuses to insert because of secure input
+pub const SECURE_INPUT_EVENT: &str = "secure-input-blocked";
+
+/// Payload for `SECURE_INPUT_EVENT` - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecureInputBlocked {
+    /// The frontmost app, which is usually the one holding secure input
+    /// (a focused password field, or Terminal's Secure Keyboard Entry)
+    pub application: Option<FocusedApplication>,
+}
+
+/// Whether some app has turned on macOS secure event input, which silently
+/// drops synthetic key events, the paste shortcut included
+pub fn is_enabled() -> bool {
+    platform::is_enabled()
+}
+
+/// Fail before injecting keystrokes that secure input would swallow, and tell
+/// the frontend why so it can explain instead of showing nothing
+///
+/// `focused` is the application looked up for this insertion.
+pub fn check(app: &tauri::AppHandle, focused: Option<&FocusedApplication>) -> Result<(), String> {
+    if !is_enabled() {
+        return Ok(());
+    }
+    let application = focused.cloned();
+    let holder = application
+        .as_ref()
+        .map_or("another app".to_string(), |application| application.name.clone());
+    let _ = app.emit(SECURE_INPUT_EVENT, SecureInputBlocked { application });
+    Err(format!(
+        "Secure input is on ({} has a password field focused or Secure Keyboard Entry \
+         enabled), so macOS blocks inserted text",
+        holder
+    ))
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        fn IsSecureEventInputEnabled() -> u8;
+    }
+
+    pub fn is_enabled() -> bool {
+        unsafe { IsSecureEventInputEnabled() != 0 }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    pub fn is_enabled() -> bool {
+        false
+    }
+}
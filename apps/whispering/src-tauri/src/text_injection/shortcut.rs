@@ -0,0 +1,158 @@
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use serde::Deserialize;
+
+use super::focus::FocusedApplication;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Modifier {
+    Control,
+    Shift,
+    Alt,
+    /// Cmd on macOS, the Windows/Super key elsewhere
+    Meta,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutKey {
+    /// A lowercase ASCII letter
+    Letter(char),
+    Insert,
+}
+
+/// A key combination that pastes, parsed from strings like `"ctrl+shift+v"`
+/// or `"shift+insert"`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct PasteShortcut {
+    pub modifiers: Vec<Modifier>,
+    pub key: ShortcutKey,
+}
+
+impl Default for PasteShortcut {
+    /// Cmd+V on macOS, Ctrl+V elsewhere
+    fn default() -> Self {
+        #[cfg(target_os = "macos")]
+        let modifier = Modifier::Meta;
+        #[cfg(not(target_os = "macos"))]
+        let modifier = Modifier::Control;
+        Self {
+            modifiers: vec![modifier],
+            key: ShortcutKey::Letter('v'),
+        }
+    }
+}
+
+impl TryFrom<String> for PasteShortcut {
+    type Error = String;
+
+    fn try_from(combo: String) -> Result<Self, Self::Error> {
+        let mut parts: Vec<String> =
+            combo.split('+').map(|part| part.trim().to_lowercase()).collect();
+        let key = parts.pop().unwrap_or_default();
+        let key = match key.as_str() {
+            "insert" | "ins" => ShortcutKey::Insert,
+            _ => match key.chars().collect::<Vec<_>>()[..] {
+                [letter] if letter.is_ascii_lowercase() => ShortcutKey::Letter(letter),
+                _ => return Err(format!("Unsupported paste key {:?} in {:?}", key, combo)),
+            },
+        };
+
+        let mut modifiers = Vec::with_capacity(parts.len());
+        for part in &parts {
+            let modifier = match part.as_str() {
+                "ctrl" | "control" => Modifier::Control,
+                "shift" => Modifier::Shift,
+                "alt" | "option" | "opt" => Modifier::Alt,
+                "cmd" | "command" | "meta" | "super" | "win" => Modifier::Meta,
+                _ => return Err(format!("Unknown modifier {:?} in {:?}", part, combo)),
+            };
+            if !modifiers.contains(&modifier) {
+                modifiers.push(modifier);
+            }
+        }
+        Ok(Self { modifiers, key })
+    }
+}
+
+/// Paste with a different shortcut in one application - from frontend
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteShortcutRule {
+    /// Bundle id, executable name or display name, as reported by
+    /// `get_focused_application` (case-insensitive)
+    pub app: String,
+    pub shortcut: PasteShortcut,
+}
+
+/// The paste shortcut for the focused application: the first matching
+/// rule's, or the platform default when none matches or the application
+/// isn't known
+pub fn for_application(
+    rules: &[PasteShortcutRule],
+    application: Option<&FocusedApplication>,
+) -> PasteShortcut {
+    let Some(application) = application else {
+        return PasteShortcut::default();
+    };
+    rules
+        .iter()
+        .find(|rule| application.matches(&rule.app))
+        .map(|rule| {
+            println!("[Write Text] Pasting into {} with a custom shortcut", application.name);
+            rule.shortcut.clone()
+        })
+        .unwrap_or_default()
+}
+
+fn enigo_modifier(modifier: Modifier) -> Key {
+    match modifier {
+        Modifier::Control => Key::Control,
+        Modifier::Shift => Key::Shift,
+        Modifier::Alt => Key::Alt,
+        Modifier::Meta => Key::Meta,
+    }
+}
+
+/// Virtual key codes where the platform has them, so letters work with any
+/// keyboard layout
+fn enigo_key(key: ShortcutKey) -> Result<Key, String> {
+    match key {
+        #[cfg(target_os = "macos")]
+        ShortcutKey::Letter('v') => Ok(Key::Other(9)), // Virtual key code for V on macOS
+        // VK_A..VK_Z match the uppercase ASCII codes
+        #[cfg(target_os = "windows")]
+        ShortcutKey::Letter(letter) => Ok(Key::Other(letter.to_ascii_uppercase() as u32)),
+        #[cfg(not(target_os = "windows"))]
+        ShortcutKey::Letter(letter) => Ok(Key::Unicode(letter)),
+        #[cfg(not(target_os = "macos"))]
+        ShortcutKey::Insert => Ok(Key::Insert),
+        #[cfg(target_os = "macos")]
+        ShortcutKey::Insert => Err("Mac keyboards have no Insert key".to_string()),
+    }
+}
+
+/// Press and release a shortcut with enigo
+pub fn press(shortcut: &PasteShortcut) -> Result<(), String> {
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+    let key = enigo_key(shortcut.key)?;
+
+    for &modifier in &shortcut.modifiers {
+        enigo
+            .key(enigo_modifier(modifier), Direction::Press)
+            .map_err(|e| format!("Failed to press modifier key: {}", e))?;
+    }
+    enigo
+        .key(key, Direction::Press)
+        .map_err(|e| format!("Failed to press paste key: {}", e))?;
+
+    // Release in reverse order for proper cleanup
+    enigo
+        .key(key, Direction::Release)
+        .map_err(|e| format!("Failed to release paste key: {}", e))?;
+    for &modifier in shortcut.modifiers.iter().rev() {
+        enigo
+            .key(enigo_modifier(modifier), Direction::Release)
+            .map_err(|e| format!("Failed to release modifier key: {}", e))?;
+    }
+    Ok(())
+}
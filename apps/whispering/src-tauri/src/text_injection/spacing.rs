@@ -0,0 +1,128 @@
+use serde::Deserialize;
+use std::sync::Mutex;
+
+use super::focus::FocusedApplication;
+
+/// Text that already joins onto what comes before it
+const NO_SPACE_BEFORE: &[char] = &[',', '.', '!', '?', ';', ':', ')', ']', '}', '\'', '"'];
+
+/// When to put a space before inserted text
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LeadingSpace {
+    #[default]
+    Never,
+    Always,
+    /// Only when the character before the cursor isn't whitespace
+    Auto,
+}
+
+/// Whitespace `write_text` adds around the text - from frontend
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpacingOptions {
+    pub leading_space: Option<LeadingSpace>,
+    /// Append a space so the next dictation joins on
+    pub trailing_space: Option<bool>,
+    /// End with a newline, e.g. to submit a chat message or start a list item
+    pub trailing_newline: Option<bool>,
+}
+
+/// Where the last insertion went and what it ended with
+struct LastInsertion {
+    application: String,
+    window_title: Option<String>,
+    last_char: char,
+}
+
+/// The end of the last text `write_text` inserted, so `leading_space: "auto"`
+/// can tell whether the cursor sits right after a word when the character
+/// before it can't be read from the target
+pub struct InsertionHistory {
+    last: Mutex<Option<LastInsertion>>,
+}
+
+impl InsertionHistory {
+    pub fn new() -> Self {
+        Self {
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Remember the window text went into and the last character inserted
+    pub fn record(&self, application: Option<&FocusedApplication>, last_char: char) {
+        let insertion = application.map(|application| LastInsertion {
+            application: application.name.clone(),
+            window_title: application.window_title.clone(),
+            last_char,
+        });
+        *self.last.lock().unwrap() = insertion;
+    }
+
+    /// The last inserted character, if it went into the same window
+    fn last_char_in(&self, application: Option<&FocusedApplication>) -> Option<char> {
+        let last = self.last.lock().unwrap();
+        let last = last.as_ref()?;
+        let application = application?;
+        (application.name == last.application && application.window_title == last.window_title)
+            .then_some(last.last_char)
+    }
+}
+
+impl Default for InsertionHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The character before the cursor: read through the Accessibility API on
+/// macOS, otherwise the end of our own last insertion into this window
+///
+/// Outer None means unknown; `Some(None)` means the cursor is at the start.
+async fn char_before_cursor(
+    history: &InsertionHistory,
+    focused: Option<&FocusedApplication>,
+) -> Option<Option<char>> {
+    // A hung target app can hold an AX call for seconds
+    #[cfg(target_os = "macos")]
+    if let Ok(Ok(before)) =
+        tokio::task::spawn_blocking(super::accessibility::char_before_caret).await
+    {
+        return Some(before);
+    }
+    history.last_char_in(focused).map(Some)
+}
+
+/// Add the requested whitespace around `text`, going into `focused`
+pub async fn apply(
+    text: String,
+    options: &SpacingOptions,
+    history: &InsertionHistory,
+    focused: Option<&FocusedApplication>,
+) -> String {
+    let leading_space = match options.leading_space.unwrap_or_default() {
+        LeadingSpace::Never => false,
+        LeadingSpace::Always => true,
+        LeadingSpace::Auto => {
+            !text.starts_with(char::is_whitespace)
+                && !text.starts_with(NO_SPACE_BEFORE)
+                && matches!(
+                    char_before_cursor(history, focused).await,
+                    Some(Some(c)) if !c.is_whitespace()
+                )
+        }
+    };
+
+    let mut spaced = String::with_capacity(text.len() + 2);
+    if leading_space {
+        spaced.push(' ');
+    }
+    spaced.push_str(&text);
+    if options.trailing_space.unwrap_or(false) && !spaced.ends_with(char::is_whitespace) {
+        spaced.push(' ');
+    }
+    if options.trailing_newline.unwrap_or(false) && !spaced.ends_with('\n') {
+        spaced.push('\n');
+    }
+    spaced
+}
@@ -0,0 +1,64 @@
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Pacing for the `"type"` strategy - from frontend
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypingOptions {
+    /// Pause after each character; 0 (default) types as fast as the OS accepts
+    pub keystroke_delay_ms: Option<u64>,
+    /// Upper bound on typing speed, for RDP, Citrix and SSH sessions whose
+    /// input queue drops keys that arrive faster than it forwards them
+    pub max_chars_per_second: Option<f64>,
+}
+
+impl TypingOptions {
+    /// Pause after each character: the keystroke delay, lengthened to stay
+    /// under `max_chars_per_second`
+    pub fn delay_ms(&self) -> u64 {
+        let throttle_ms = self
+            .max_chars_per_second
+            .filter(|rate| *rate > 0.0)
+            .map_or(0, |rate| (1000.0 / rate).ceil() as u64);
+        self.keystroke_delay_ms.unwrap_or(0).max(throttle_ms)
+    }
+}
+
+/// Type text into the focused application one character at a time
+///
+/// Runs on a blocking thread since a long transcript with a keystroke delay
+/// can take several seconds. Newlines and tabs are sent as Return and Tab key
+/// presses, which editors and terminals handle more reliably than typed
+/// control characters. Wayland sessions type through wtype or ydotool when
+/// one is installed.
+pub async fn type_text(text: String, options: TypingOptions) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let delay_ms = options.delay_ms();
+
+        #[cfg(target_os = "linux")]
+        if let Some(tool) = super::wayland::tool() {
+            return super::wayland::type_text(tool, &text, delay_ms);
+        }
+
+        let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+        let delay = Duration::from_millis(delay_ms);
+
+        for c in text.chars() {
+            match c {
+                '\r' => continue,
+                '\n' => enigo.key(Key::Return, Direction::Click),
+                '\t' => enigo.key(Key::Tab, Direction::Click),
+                _ => enigo.text(c.encode_utf8(&mut [0; 4])),
+            }
+            .map_err(|e| format!("Failed to type {:?}: {}", c, e))?;
+
+            if !delay.is_zero() {
+                std::thread::sleep(delay);
+            }
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
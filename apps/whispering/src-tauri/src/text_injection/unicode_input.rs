@@ -0,0 +1,93 @@
+use std::time::Duration;
+use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_KEYUP, KEYEVENTF_UNICODE,
+    VIRTUAL_KEY, VK_RETURN, VK_TAB,
+};
+
+fn key_input(virtual_key: VIRTUAL_KEY, scan: u16, unicode: bool, key_up: bool) -> INPUT {
+    let mut flags = 0;
+    if unicode {
+        flags |= KEYEVENTF_UNICODE;
+    }
+    if key_up {
+        flags |= KEYEVENTF_KEYUP;
+    }
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: virtual_key,
+                wScan: scan,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+/// Key down and up events for one character
+///
+/// Characters outside the BMP (emoji, rare CJK) are sent as their UTF-16
+/// surrogate pair, which Windows recombines into a single WM_CHAR.
+fn char_inputs(c: char) -> Vec<INPUT> {
+    let virtual_key = match c {
+        '\n' => Some(VK_RETURN),
+        '\t' => Some(VK_TAB),
+        _ => None,
+    };
+    if let Some(virtual_key) = virtual_key {
+        return vec![
+            key_input(virtual_key, 0, false, false),
+            key_input(virtual_key, 0, false, true),
+        ];
+    }
+
+    let mut units = [0; 2];
+    c.encode_utf16(&mut units)
+        .iter()
+        .flat_map(|&unit| [key_input(0, unit, true, false), key_input(0, unit, true, true)])
+        .collect()
+}
+
+fn send(inputs: &[INPUT]) -> Result<(), String> {
+    if inputs.is_empty() {
+        return Ok(());
+    }
+    let sent = unsafe {
+        SendInput(inputs.len() as u32, inputs.as_ptr(), std::mem::size_of::<INPUT>() as i32)
+    };
+    if sent as usize == inputs.len() {
+        Ok(())
+    } else {
+        // Input was blocked, usually by UIPI when the target runs elevated
+        Err(format!(
+            "SendInput delivered {} of {} key events: {}",
+            sent,
+            inputs.len(),
+            std::io::Error::last_os_error()
+        ))
+    }
+}
+
+/// Type text with `SendInput` and `KEYEVENTF_UNICODE`
+///
+/// Each UTF-16 code unit is delivered as a character rather than a virtual
+/// key, so the result doesn't depend on the active keyboard layout and any
+/// Unicode text (emoji, CJK, accented letters) arrives intact. Without a
+/// keystroke delay the whole text goes out in one `SendInput` call, which
+/// keeps other input from interleaving with it.
+pub fn send_text(text: &str, keystroke_delay_ms: u64) -> Result<(), String> {
+    let chars = text.chars().filter(|&c| c != '\r');
+    if keystroke_delay_ms == 0 {
+        let inputs: Vec<INPUT> = chars.flat_map(char_inputs).collect();
+        return send(&inputs);
+    }
+
+    let delay = Duration::from_millis(keystroke_delay_ms);
+    for c in chars {
+        send(&char_inputs(c))?;
+        std::thread::sleep(delay);
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use std::path::Path;
+use std::process::Command;
+
+use super::shortcut::{Modifier, PasteShortcut, ShortcutKey};
+
+/// Linux input event codes (`linux/input-event-codes.h`) for ydotool
+const KEY_LEFTCTRL: u16 = 29;
+const KEY_LEFTSHIFT: u16 = 42;
+const KEY_LEFTALT: u16 = 56;
+const KEY_LEFTMETA: u16 = 125;
+const KEY_INSERT: u16 = 110;
+/// Event codes for a..z, which follow the QWERTY rows rather than the alphabet
+const LETTER_KEYS: [u16; 26] = [
+    30, 48, 46, 32, 18, 33, 34, 35, 23, 36, 37, 38, 50, 49, 24, 25, 16, 19, 31, 20, 22, 47, 17,
+    45, 21, 44,
+];
+
+/// A command-line tool that can inject input into Wayland sessions, where
+/// enigo's X11 path only reaches XWayland windows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaylandTool {
+    /// Speaks the virtual-keyboard protocol; works on wlroots compositors
+    /// (Sway, Hyprland, river) and KDE, not GNOME
+    Wtype,
+    /// Writes to /dev/uinput through the ydotoold daemon; works under any
+    /// compositor, GNOME included
+    Ydotool,
+}
+
+impl WaylandTool {
+    pub fn name(self) -> &'static str {
+        match self {
+            WaylandTool::Wtype => "wtype",
+            WaylandTool::Ydotool => "ydotool",
+        }
+    }
+}
+
+/// Whether the app is running in a Wayland session
+pub fn is_wayland_session() -> bool {
+    std::env::var("XDG_SESSION_TYPE").is_ok_and(|session| session.eq_ignore_ascii_case("wayland"))
+        || std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+/// Whether an executable with this name is on PATH
+pub fn is_installed(name: &str) -> bool {
+    std::env::var_os("PATH").is_some_and(|path| {
+        std::env::split_paths(&path).any(|dir| Path::new(&dir).join(name).is_file())
+    })
+}
+
+/// Whether the desktop is GNOME, whose compositor (Mutter) doesn't implement
+/// the virtual-keyboard protocol wtype needs
+fn is_gnome() -> bool {
+    std::env::var("XDG_CURRENT_DESKTOP").is_ok_and(|desktops| {
+        desktops
+            .split(':')
+            .any(|desktop| desktop.eq_ignore_ascii_case("gnome"))
+    })
+}
+
+/// The Wayland injection tool to use, preferring the protocol-native wtype
+/// except on GNOME, where only ydotool works
+///
+/// Returns None outside Wayland sessions and when neither tool is installed.
+pub fn tool() -> Option<WaylandTool> {
+    if !is_wayland_session() {
+        return None;
+    }
+    let preference = if is_gnome() {
+        [WaylandTool::Ydotool, WaylandTool::Wtype]
+    } else {
+        [WaylandTool::Wtype, WaylandTool::Ydotool]
+    };
+    preference
+        .into_iter()
+        .find(|tool| is_installed(tool.name()))
+}
+
+/// Press a paste shortcut in the focused Wayland window
+pub fn press_paste(tool: WaylandTool, shortcut: &PasteShortcut) -> Result<(), String> {
+    let mut command = Command::new(tool.name());
+    match tool {
+        WaylandTool::Wtype => {
+            let modifier_name = |modifier| match modifier {
+                Modifier::Control => "ctrl",
+                Modifier::Shift => "shift",
+                Modifier::Alt => "alt",
+                Modifier::Meta => "logo",
+            };
+            let key = match shortcut.key {
+                ShortcutKey::Letter(letter) => letter.to_string(),
+                ShortcutKey::Insert => "Insert".to_string(),
+            };
+            for &modifier in &shortcut.modifiers {
+                command.args(["-M", modifier_name(modifier)]);
+            }
+            command.args(["-k", &key]);
+            for &modifier in shortcut.modifiers.iter().rev() {
+                command.args(["-m", modifier_name(modifier)]);
+            }
+        }
+        WaylandTool::Ydotool => {
+            let modifier_code = |modifier| match modifier {
+                Modifier::Control => KEY_LEFTCTRL,
+                Modifier::Shift => KEY_LEFTSHIFT,
+                Modifier::Alt => KEY_LEFTALT,
+                Modifier::Meta => KEY_LEFTMETA,
+            };
+            let key = match shortcut.key {
+                ShortcutKey::Letter(letter) => LETTER_KEYS[(letter as u8 - b'a') as usize],
+                ShortcutKey::Insert => KEY_INSERT,
+            };
+            command.arg("key");
+            for &modifier in &shortcut.modifiers {
+                command.arg(format!("{}:1", modifier_code(modifier)));
+            }
+            command.args([format!("{}:1", key), format!("{}:0", key)]);
+            for &modifier in shortcut.modifiers.iter().rev() {
+                command.arg(format!("{}:0", modifier_code(modifier)));
+            }
+        }
+    }
+    run(tool, command)
+}
+
+/// Type text into the focused Wayland window with a pause after each key
+pub fn type_text(tool: WaylandTool, text: &str, keystroke_delay_ms: u64) -> Result<(), String> {
+    let mut command = Command::new(tool.name());
+    let delay = keystroke_delay_ms.to_string();
+    match tool {
+        WaylandTool::Wtype => command.args(["-d", &delay, "--", text]),
+        WaylandTool::Ydotool => command.args(["type", "--key-delay", &delay, "--", text]),
+    };
+    run(tool, command)
+}
+
+fn run(tool: WaylandTool, mut command: Command) -> Result<(), String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", tool.name(), e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            tool.name(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
@@ -0,0 +1,316 @@
+pub use platform::PreviousFocus;
+
+/// Bring the first window matching `identifier` to the front
+///
+/// The identifier is compared like a paste shortcut rule's app (bundle id,
+/// executable or name) and otherwise searched for in window titles. Returns
+/// what was focused before, for `restore`.
+pub fn activate(identifier: &str) -> Result<PreviousFocus, String> {
+    platform::activate(identifier)
+}
+
+/// Give focus back to the window that had it before `activate`
+pub fn restore(previous: PreviousFocus) -> Result<(), String> {
+    platform::restore(previous)
+}
+
+fn no_match(identifier: &str) -> String {
+    format!("No open window matches {:?}", identifier)
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::super::{accessibility, focus};
+    use objc2::msg_send;
+    use objc2::rc::Retained;
+    use objc2::runtime::{AnyClass, AnyObject, Bool};
+
+    /// `NSApplicationActivationPolicyRegular`: apps with a Dock icon and windows
+    const ACTIVATION_POLICY_REGULAR: isize = 0;
+    /// `NSApplicationActivateIgnoringOtherApps`
+    const ACTIVATE_IGNORING_OTHER_APPS: usize = 1 << 1;
+
+    /// Process id of the previously frontmost application
+    pub struct PreviousFocus(Option<i32>);
+
+    fn process_id(application: &AnyObject) -> i32 {
+        unsafe { msg_send![application, processIdentifier] }
+    }
+
+    fn bring_to_front(application: &AnyObject) -> Result<(), String> {
+        let activated: Bool =
+            unsafe { msg_send![application, activateWithOptions: ACTIVATE_IGNORING_OTHER_APPS] };
+        if activated.as_bool() {
+            Ok(())
+        } else {
+            Err("macOS refused to activate the application".to_string())
+        }
+    }
+
+    pub fn activate(identifier: &str) -> Result<PreviousFocus, String> {
+        let workspace_class =
+            AnyClass::get(c"NSWorkspace").ok_or_else(|| "NSWorkspace is unavailable".to_string())?;
+        unsafe {
+            let workspace: Retained<AnyObject> = msg_send![workspace_class, sharedWorkspace];
+            let previous: Option<Retained<AnyObject>> =
+                msg_send![&*workspace, frontmostApplication];
+            let previous = PreviousFocus(previous.map(|application| process_id(&application)));
+
+            let applications: Retained<AnyObject> = msg_send![&*workspace, runningApplications];
+            let count: usize = msg_send![&*applications, count];
+            let mut regular = Vec::new();
+            for index in 0..count {
+                let application: Retained<AnyObject> =
+                    msg_send![&*applications, objectAtIndex: index];
+                let policy: isize = msg_send![&*application, activationPolicy];
+                if policy == ACTIVATION_POLICY_REGULAR {
+                    regular.push(application);
+                }
+            }
+
+            // App names first, since a title search raises windows as it goes
+            let target = regular
+                .iter()
+                .find(|application| focus::platform::describe(application).matches(identifier))
+                .or_else(|| {
+                    regular.iter().find(|application| {
+                        accessibility::raise_window_titled(process_id(application), identifier)
+                    })
+                })
+                .ok_or_else(|| super::no_match(identifier))?;
+            bring_to_front(target)?;
+            Ok(previous)
+        }
+    }
+
+    pub fn restore(previous: PreviousFocus) -> Result<(), String> {
+        let Some(process_id) = previous.0 else {
+            return Ok(());
+        };
+        let class = AnyClass::get(c"NSRunningApplication")
+            .ok_or_else(|| "NSRunningApplication is unavailable".to_string())?;
+        let application: Option<Retained<AnyObject>> =
+            unsafe { msg_send![class, runningApplicationWithProcessIdentifier: process_id] };
+        match application {
+            Some(application) => bring_to_front(&application),
+            // The app quit in the meantime
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use super::super::focus;
+    use std::ptr;
+    use windows_sys::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows_sys::Win32::System::Threading::{AttachThreadInput, GetCurrentThreadId};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetForegroundWindow, GetWindowTextLengthW, GetWindowThreadProcessId,
+        IsIconic, IsWindowVisible, SetForegroundWindow, ShowWindow, SW_RESTORE,
+    };
+
+    /// The previously focused window, as an address so it can cross threads
+    pub struct PreviousFocus(isize);
+
+    unsafe extern "system" fn collect_window(window: HWND, windows: LPARAM) -> BOOL {
+        let windows = &mut *(windows as *mut Vec<isize>);
+        if IsWindowVisible(window) != 0 && GetWindowTextLengthW(window) > 0 {
+            windows.push(window as isize);
+        }
+        1
+    }
+
+    fn bring_to_front(window: HWND) -> Result<(), String> {
+        unsafe {
+            if IsIconic(window) != 0 {
+                ShowWindow(window, SW_RESTORE);
+            }
+            // Only the foreground thread may move focus, so borrow its input
+            // state for the switch
+            let foreground_thread =
+                GetWindowThreadProcessId(GetForegroundWindow(), ptr::null_mut());
+            let current_thread = GetCurrentThreadId();
+            let attached = foreground_thread != 0
+                && foreground_thread != current_thread
+                && AttachThreadInput(current_thread, foreground_thread, 1) != 0;
+            let focused = SetForegroundWindow(window) != 0;
+            if attached {
+                AttachThreadInput(current_thread, foreground_thread, 0);
+            }
+            if focused {
+                Ok(())
+            } else {
+                Err("Windows refused to focus the window".to_string())
+            }
+        }
+    }
+
+    pub fn activate(identifier: &str) -> Result<PreviousFocus, String> {
+        let previous = PreviousFocus(unsafe { GetForegroundWindow() } as isize);
+
+        // Top to bottom in z-order, so the most recently used match wins
+        let mut windows: Vec<isize> = Vec::new();
+        unsafe { EnumWindows(Some(collect_window), &mut windows as *mut Vec<isize> as LPARAM) };
+        let target = windows
+            .into_iter()
+            .map(|window| window as HWND)
+            .find(|&window| {
+                focus::platform::describe(window)
+                    .is_ok_and(|application| application.matches_window(identifier))
+            })
+            .ok_or_else(|| super::no_match(identifier))?;
+        bring_to_front(target)?;
+        Ok(previous)
+    }
+
+    pub fn restore(previous: PreviousFocus) -> Result<(), String> {
+        if previous.0 == 0 {
+            return Ok(());
+        }
+        bring_to_front(previous.0 as HWND)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::super::focus::platform::{from_window, json_pid, json_string, run};
+    use serde_json::Value;
+
+    pub enum PreviousFocus {
+        Hyprland(String),
+        Sway(u64),
+        X11(String),
+        Unknown,
+    }
+
+    fn parse_json(output: &str, program: &str) -> Result<Value, String> {
+        serde_json::from_str(output).map_err(|e| format!("Unexpected {} output: {}", program, e))
+    }
+
+    fn hyprland(identifier: &str) -> Result<PreviousFocus, String> {
+        let active = parse_json(&run("hyprctl", &["activewindow", "-j"])?, "hyprctl")?;
+        let previous = json_string(&active, "address")
+            .map_or(PreviousFocus::Unknown, PreviousFocus::Hyprland);
+
+        let clients = parse_json(&run("hyprctl", &["clients", "-j"])?, "hyprctl")?;
+        let address = clients
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|client| {
+                from_window(
+                    json_string(client, "class"),
+                    json_pid(client),
+                    json_string(client, "title"),
+                )
+                .is_ok_and(|application| application.matches_window(identifier))
+            })
+            .and_then(|client| json_string(client, "address"))
+            .ok_or_else(|| super::no_match(identifier))?;
+        focus_hyprland(&address)?;
+        Ok(previous)
+    }
+
+    fn focus_hyprland(address: &str) -> Result<(), String> {
+        run("hyprctl", &["dispatch", "focuswindow", &format!("address:{}", address)]).map(|_| ())
+    }
+
+    /// Every window in Sway's tree; containers without a pid are layout nodes
+    fn sway_windows<'a>(node: &'a Value, windows: &mut Vec<&'a Value>) {
+        if node["pid"].is_u64() {
+            windows.push(node);
+        }
+        for key in ["nodes", "floating_nodes"] {
+            for child in node[key].as_array().into_iter().flatten() {
+                sway_windows(child, windows);
+            }
+        }
+    }
+
+    fn sway(identifier: &str) -> Result<PreviousFocus, String> {
+        let tree = parse_json(&run("swaymsg", &["-t", "get_tree", "-r"])?, "swaymsg")?;
+        let mut windows = Vec::new();
+        sway_windows(&tree, &mut windows);
+
+        let previous = windows
+            .iter()
+            .find(|window| window["focused"].as_bool() == Some(true))
+            .and_then(|window| window["id"].as_u64())
+            .map_or(PreviousFocus::Unknown, PreviousFocus::Sway);
+        let id = windows
+            .iter()
+            .find(|window| {
+                let class = json_string(window, "app_id")
+                    .or_else(|| json_string(&window["window_properties"], "class"));
+                from_window(class, json_pid(window), json_string(window, "name"))
+                    .is_ok_and(|application| application.matches_window(identifier))
+            })
+            .and_then(|window| window["id"].as_u64())
+            .ok_or_else(|| super::no_match(identifier))?;
+        focus_sway(id)?;
+        Ok(previous)
+    }
+
+    fn focus_sway(id: u64) -> Result<(), String> {
+        run("swaymsg", &[&format!("[con_id={}]", id), "focus"]).map(|_| ())
+    }
+
+    /// Search titles, then classes, with xdotool
+    fn x11(identifier: &str) -> Result<PreviousFocus, String> {
+        let previous = run("xdotool", &["getactivewindow"])
+            .ok()
+            .map(|output| output.trim().to_string())
+            .filter(|window| !window.is_empty())
+            .map_or(PreviousFocus::Unknown, PreviousFocus::X11);
+
+        // xdotool matches case-insensitively
+        let pattern = regex::escape(identifier.trim());
+        let window = ["--name", "--class"]
+            .into_iter()
+            .find_map(|field| {
+                // xdotool exits non-zero when nothing matches
+                let output = run("xdotool", &["search", "--onlyvisible", field, &pattern]).ok()?;
+                output.lines().next().map(|window| window.trim().to_string())
+            })
+            .ok_or_else(|| super::no_match(identifier))?;
+        focus_x11(&window)?;
+        Ok(previous)
+    }
+
+    fn focus_x11(window: &str) -> Result<(), String> {
+        run("xdotool", &["windowactivate", "--sync", window]).map(|_| ())
+    }
+
+    pub fn activate(identifier: &str) -> Result<PreviousFocus, String> {
+        if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            return hyprland(identifier);
+        }
+        if std::env::var_os("SWAYSOCK").is_some() {
+            return sway(identifier);
+        }
+        x11(identifier)
+    }
+
+    pub fn restore(previous: PreviousFocus) -> Result<(), String> {
+        match previous {
+            PreviousFocus::Hyprland(address) => focus_hyprland(&address),
+            PreviousFocus::Sway(id) => focus_sway(id),
+            PreviousFocus::X11(window) => focus_x11(&window),
+            PreviousFocus::Unknown => Ok(()),
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+mod platform {
+    pub struct PreviousFocus;
+
+    pub fn activate(_identifier: &str) -> Result<PreviousFocus, String> {
+        Err("Focusing other windows isn't available on this platform".to_string())
+    }
+
+    pub fn restore(_previous: PreviousFocus) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,236 @@
+use super::convert_audio_for_whisper;
+use super::error::TranscriptionError;
+use super::postprocess::{self, PostProcessingOptions};
+
+/// Transcribe audio with Apple's Speech framework (`SFSpeechRecognizer`) in
+/// on-device mode
+///
+/// Nothing needs downloading and the Neural Engine does the work, which makes
+/// this a battery-friendly option for quick dictations on macOS. `language` is
+/// a locale identifier such as "en-US" (defaults to the system locale); not
+/// every locale supports on-device recognition. The first call prompts for
+/// speech recognition permission. Returns an error on other platforms.
+#[tauri::command]
+pub async fn transcribe_audio_apple(
+    audio_data: Vec<u8>,
+    language: Option<String>,
+    post_processing: Option<PostProcessingOptions>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, TranscriptionError> {
+    let wav_data = convert_audio_for_whisper(audio_data)?;
+
+    let locale = language.clone();
+    let text =
+        tokio::task::spawn_blocking(move || platform::transcribe(wav_data, locale.as_deref()))
+            .await
+            .map_err(|e| TranscriptionError::TranscriptionError {
+                message: format!("Task join error: {}", e),
+            })??;
+    Ok(postprocess::process_text(
+        &app_handle,
+        &text,
+        &post_processing.unwrap_or_default(),
+        language.as_deref(),
+    ))
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use block2::RcBlock;
+    use objc2::msg_send;
+    use objc2::rc::{Allocated, Retained};
+    use objc2::runtime::{AnyClass, AnyObject};
+    use objc2_foundation::NSString;
+    use std::io::Write;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use super::super::error::TranscriptionError;
+
+    #[link(name = "Speech", kind = "framework")]
+    extern "C" {}
+
+    /// `SFSpeechRecognizerAuthorizationStatus` values
+    const NOT_DETERMINED: isize = 0;
+    const AUTHORIZED: isize = 3;
+
+    /// Upper bound on waiting for a result, in case the framework never calls back
+    const RESULT_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+    fn error(message: impl Into<String>) -> TranscriptionError {
+        TranscriptionError::TranscriptionError {
+            message: message.into(),
+        }
+    }
+
+    fn class(name: &std::ffi::CStr) -> Result<&'static AnyClass, TranscriptionError> {
+        AnyClass::get(name).ok_or_else(|| {
+            error(format!(
+                "{} is unavailable on this version of macOS",
+                name.to_string_lossy()
+            ))
+        })
+    }
+
+    pub fn transcribe(
+        wav_data: Vec<u8>,
+        language: Option<&str>,
+    ) -> Result<String, TranscriptionError> {
+        // SFSpeechURLRecognitionRequest reads from disk
+        let mut file = tempfile::Builder::new()
+            .suffix(".wav")
+            .tempfile()
+            .map_err(|e| TranscriptionError::AudioReadError {
+                message: format!("Failed to create temp file: {}", e),
+            })?;
+        file.write_all(&wav_data)
+            .map_err(|e| TranscriptionError::AudioReadError {
+                message: format!("Failed to write audio data: {}", e),
+            })?;
+
+        authorize()?;
+
+        let recognizer_class = class(c"SFSpeechRecognizer")?;
+        let locale_class = class(c"NSLocale")?;
+        let recognizer: Option<Retained<AnyObject>> = unsafe {
+            match language {
+                Some(identifier) => {
+                    let identifier = NSString::from_str(identifier);
+                    let locale: Option<Retained<AnyObject>> =
+                        msg_send![locale_class, localeWithLocaleIdentifier: &*identifier];
+                    let locale = locale.ok_or_else(|| error("Invalid locale"))?;
+                    let allocated: Allocated<AnyObject> = msg_send![recognizer_class, alloc];
+                    msg_send![allocated, initWithLocale: &*locale]
+                }
+                None => msg_send![recognizer_class, new],
+            }
+        };
+        let recognizer = recognizer.ok_or_else(|| {
+            error(format!(
+                "Speech recognition doesn't support locale {}",
+                language.unwrap_or("(system default)")
+            ))
+        })?;
+
+        let (available, on_device): (bool, bool) = unsafe {
+            (
+                msg_send![&*recognizer, isAvailable],
+                msg_send![&*recognizer, supportsOnDeviceRecognition],
+            )
+        };
+        if !available {
+            return Err(error("Speech recognition is currently unavailable"));
+        }
+        if !on_device {
+            return Err(error("On-device speech recognition isn't supported for this language"));
+        }
+
+        let url_class = class(c"NSURL")?;
+        let request_class = class(c"SFSpeechURLRecognitionRequest")?;
+        let request: Option<Retained<AnyObject>> = unsafe {
+            let path = NSString::from_str(&file.path().to_string_lossy());
+            let url: Option<Retained<AnyObject>> = msg_send![url_class, fileURLWithPath: &*path];
+            let url = url.ok_or_else(|| error("Invalid audio file path"))?;
+            let allocated: Allocated<AnyObject> = msg_send![request_class, alloc];
+            msg_send![allocated, initWithURL: &*url]
+        };
+        let request = request.ok_or_else(|| error("Failed to create recognition request"))?;
+
+        let (result_tx, result_rx) = mpsc::channel::<Result<String, String>>();
+        let handler = RcBlock::new(move |result: *mut AnyObject, failure: *mut AnyObject| {
+            let (result, failure) = unsafe { (result.as_ref(), failure.as_ref()) };
+            if let Some(failure) = failure {
+                let description: Option<Retained<NSString>> =
+                    unsafe { msg_send![failure, localizedDescription] };
+                let _ = result_tx.send(Err(description.map_or_else(
+                    || "Speech recognition failed".to_string(),
+                    |d| d.to_string(),
+                )));
+                return;
+            }
+            let Some(result) = result else {
+                return;
+            };
+            let is_final: bool = unsafe { msg_send![result, isFinal] };
+            if is_final {
+                let transcription: Option<Retained<AnyObject>> =
+                    unsafe { msg_send![result, bestTranscription] };
+                let text: Option<Retained<NSString>> = match &transcription {
+                    Some(transcription) => unsafe { msg_send![&**transcription, formattedString] },
+                    None => None,
+                };
+                let text = text.map(|text| text.to_string()).unwrap_or_default();
+                let _ = result_tx.send(Ok(text));
+            }
+        });
+
+        let queue_class = class(c"NSOperationQueue")?;
+        let task: Option<Retained<AnyObject>> = unsafe {
+            let () = msg_send![&*request, setRequiresOnDeviceRecognition: true];
+            let () = msg_send![&*request, setShouldReportPartialResults: false];
+
+            // Deliver results on a background queue; the default is the main
+            // queue, which the app's event loop owns
+            let queue: Option<Retained<AnyObject>> = msg_send![queue_class, new];
+            if let Some(queue) = &queue {
+                let () = msg_send![&*recognizer, setQueue: &**queue];
+            }
+
+            msg_send![
+                &*recognizer,
+                recognitionTaskWithRequest: &*request,
+                resultHandler: &*handler
+            ]
+        };
+        let task = task.ok_or_else(|| error("Failed to start speech recognition"))?;
+
+        let outcome = result_rx.recv_timeout(RESULT_TIMEOUT);
+        if outcome.is_err() {
+            let () = unsafe { msg_send![&*task, cancel] };
+        }
+
+        match outcome {
+            Ok(Ok(text)) => Ok(text.trim().to_string()),
+            Ok(Err(message)) => Err(error(message)),
+            Err(_) => Err(error("Timed out waiting for speech recognition")),
+        }
+    }
+
+    /// Make sure the user has allowed speech recognition, asking if needed
+    fn authorize() -> Result<(), TranscriptionError> {
+        let recognizer_class = class(c"SFSpeechRecognizer")?;
+        let mut status: isize = unsafe { msg_send![recognizer_class, authorizationStatus] };
+
+        if status == NOT_DETERMINED {
+            let (status_tx, status_rx) = mpsc::channel();
+            let handler = RcBlock::new(move |status: isize| {
+                let _ = status_tx.send(status);
+            });
+            let () = unsafe { msg_send![recognizer_class, requestAuthorization: &*handler] };
+            status = status_rx.recv().unwrap_or(NOT_DETERMINED);
+        }
+
+        if status == AUTHORIZED {
+            Ok(())
+        } else {
+            Err(error(
+                "Speech recognition permission was not granted. Enable Whispering in \
+                 System Settings > Privacy & Security > Speech Recognition.",
+            ))
+        }
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+mod platform {
+    use super::super::error::TranscriptionError;
+
+    pub fn transcribe(
+        _wav_data: Vec<u8>,
+        _language: Option<&str>,
+    ) -> Result<String, TranscriptionError> {
+        Err(TranscriptionError::TranscriptionError {
+            message: "Apple Speech transcription is only available on macOS".to_string(),
+        })
+    }
+}
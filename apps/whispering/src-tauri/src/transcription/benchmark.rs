@@ -0,0 +1,155 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use transcribe_rs::engines::parakeet::ParakeetInferenceParams;
+use transcribe_rs::TranscriptionEngine;
+
+use super::error::TranscriptionError;
+use super::model_manager::{self, EngineKind, ModelManager};
+use super::output_format::{self, OutputFormat};
+use super::stats::{MemoryMonitor, TranscriptionStats};
+use super::whisper_cpp::{ComputeDevice, WhisperOptions};
+use super::{convert_audio_for_whisper, extract_samples_from_wav, run_whisper};
+
+/// How one model performed on the reference clip - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelBenchmark {
+    pub model_path: String,
+    pub engine: EngineKind,
+    /// Why the model couldn't be benchmarked; the other fields are then empty
+    pub error: Option<String>,
+    pub stats: Option<TranscriptionStats>,
+    pub text: Option<String>,
+}
+
+/// Transcribe the same clip with each model to compare speed, memory and
+/// output on this machine
+///
+/// `sample_audio` is the reference clip the frontend bundles, in any format
+/// the transcription commands accept. Folders are run as Parakeet models and
+/// files as whisper.cpp models, with default options and no vocabulary or
+/// post-processing so the results are comparable. Each model is loaded from
+/// scratch, so `modelLoadMs` is always reported; whatever model was resident
+/// beforehand is unloaded.
+///
+/// A model that fails to load or transcribe gets an `error` entry rather than
+/// failing the whole benchmark.
+#[tauri::command]
+pub async fn benchmark_models(
+    paths: Vec<String>,
+    sample_audio: Vec<u8>,
+    device: Option<ComputeDevice>,
+    model_manager: tauri::State<'_, ModelManager>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<ModelBenchmark>, TranscriptionError> {
+    let wav_data = convert_audio_for_whisper(sample_audio)?;
+    let samples = extract_samples_from_wav(wav_data)?;
+    if samples.is_empty() {
+        return Err(TranscriptionError::AudioReadError {
+            message: "Benchmark clip contains no samples".to_string(),
+        });
+    }
+
+    let device = device.unwrap_or_default();
+    let benchmarks = paths
+        .into_iter()
+        .map(|model_path| {
+            let engine = if Path::new(&model_path).is_dir() {
+                EngineKind::Parakeet
+            } else {
+                EngineKind::Whisper
+            };
+
+            model_manager.unload_model();
+            let result = match engine {
+                EngineKind::Whisper => {
+                    run_whisper(
+                        &samples,
+                        &model_path,
+                        device,
+                        &WhisperOptions::default(),
+                        None,
+                        &model_manager,
+                        &app_handle,
+                    )
+                    .map(|(segments, stats)| {
+                        let text = output_format::render(&segments, &stats, OutputFormat::Plain);
+                        (text, stats)
+                    })
+                }
+                EngineKind::Parakeet => run_parakeet(&samples, &model_path, &model_manager),
+            };
+
+            match result {
+                Ok((text, stats)) => {
+                    println!(
+                        "[Benchmark] {} ran at RTF {:.2}, peak memory {:?} bytes",
+                        model_path, stats.real_time_factor, stats.peak_memory_bytes
+                    );
+                    ModelBenchmark {
+                        model_path,
+                        engine,
+                        error: None,
+                        stats: Some(stats),
+                        text: Some(text),
+                    }
+                }
+                Err(e) => {
+                    eprintln!("[Benchmark] {} failed: {}", model_path, e);
+                    ModelBenchmark {
+                        model_path,
+                        engine,
+                        error: Some(e.to_string()),
+                        stats: None,
+                        text: None,
+                    }
+                }
+            }
+        })
+        .collect();
+
+    Ok(benchmarks)
+}
+
+/// Time a Parakeet model the same way `run_whisper` times whisper.cpp
+fn run_parakeet(
+    samples: &[f32],
+    model_path: &str,
+    model_manager: &ModelManager,
+) -> Result<(String, TranscriptionStats), TranscriptionError> {
+    let memory = MemoryMonitor::start();
+
+    let load_started = Instant::now();
+    let engine_arc = model_manager
+        .get_or_load_parakeet(PathBuf::from(model_path))
+        .map_err(|e| TranscriptionError::ModelLoadError { message: e })?;
+    let model_load = load_started.elapsed();
+
+    let mut engine_guard = engine_arc.lock().unwrap();
+    let parakeet_engine = match engine_guard.as_mut() {
+        Some(model_manager::Engine::Parakeet(e)) => e,
+        _ => {
+            return Err(TranscriptionError::ModelLoadError {
+                message: "Expected Parakeet engine but got different type".to_string(),
+            })
+        }
+    };
+
+    let processing_started = Instant::now();
+    let result = parakeet_engine
+        .transcribe_samples(samples.to_vec(), Some(ParakeetInferenceParams::default()))
+        .map_err(|e| TranscriptionError::TranscriptionError {
+            message: e.to_string(),
+        })?;
+
+    let stats = TranscriptionStats::new(
+        // 16 samples per millisecond at 16 kHz
+        samples.len() as u64 / 16,
+        processing_started.elapsed(),
+        Some(model_load),
+        &Default::default(),
+        memory.finish(),
+    );
+    Ok((result.text.trim().to_string(), stats))
+}
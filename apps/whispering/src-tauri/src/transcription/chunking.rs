@@ -0,0 +1,119 @@
+use std::ops::Range;
+
+use super::whisper_cpp::Segment;
+
+const SAMPLE_RATE: usize = 16000;
+
+/// 30 ms analysis frames for energy-based speech detection
+pub const FRAME_SAMPLES: usize = 480;
+
+/// Chunks are cut roughly this often; long enough to amortize whisper.cpp's
+/// per-run setup, short enough to spread an hour of audio across many cores
+const TARGET_CHUNK_SAMPLES: usize = 60 * SAMPLE_RATE;
+
+/// How far either side of the target cut to look for a pause
+const SEARCH_SAMPLES: usize = 10 * SAMPLE_RATE;
+
+/// Extra audio decoded on both sides of a cut so words straddling it aren't lost
+const OVERLAP_SAMPLES: usize = SAMPLE_RATE;
+
+/// Frames quieter than the noise floor times this are treated as silence
+const NOISE_FLOOR_FACTOR: f32 = 2.0;
+
+/// Never treat frames louder than this RMS as silence, even in noisy recordings
+const MIN_SPEECH_RMS: f32 = 0.005;
+
+/// A piece of a long recording transcribed on its own
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    /// Samples handed to whisper.cpp, including overlap with neighbours
+    pub decode: Range<usize>,
+    /// Samples this chunk is responsible for; segments outside it belong to a neighbour
+    pub keep: Range<usize>,
+}
+
+/// RMS energy of each 30 ms frame
+pub fn frame_energies(samples: &[f32]) -> Vec<f32> {
+    samples
+        .chunks(FRAME_SAMPLES)
+        .map(|frame| (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt())
+        .collect()
+}
+
+/// Energy below which a frame counts as silence, adapted to the recording's
+/// noise floor (its 10th percentile frame energy)
+pub fn silence_threshold(energies: &[f32]) -> f32 {
+    if energies.is_empty() {
+        return MIN_SPEECH_RMS;
+    }
+
+    let mut sorted = energies.to_vec();
+    sorted.sort_by(f32::total_cmp);
+    let noise_floor = sorted[sorted.len() / 10];
+    (noise_floor * NOISE_FLOOR_FACTOR).max(MIN_SPEECH_RMS)
+}
+
+/// Split a recording into chunks cut at the quietest moment near every
+/// `TARGET_CHUNK_SAMPLES`, so cuts land in pauses between words rather than
+/// mid-sentence. Short recordings come back as a single chunk.
+pub fn split_on_pauses(samples: &[f32]) -> Vec<Chunk> {
+    let energies = frame_energies(samples);
+    let mut cuts = vec![0];
+
+    let mut target = TARGET_CHUNK_SAMPLES;
+    while target + SEARCH_SAMPLES < samples.len() {
+        let first_frame = (target - SEARCH_SAMPLES) / FRAME_SAMPLES;
+        let last_frame = ((target + SEARCH_SAMPLES) / FRAME_SAMPLES).min(energies.len());
+
+        let quietest = (first_frame..last_frame)
+            .min_by(|&a, &b| energies[a].total_cmp(&energies[b]))
+            .unwrap_or(target / FRAME_SAMPLES);
+        let cut = quietest * FRAME_SAMPLES + FRAME_SAMPLES / 2;
+
+        cuts.push(cut);
+        target = cut + TARGET_CHUNK_SAMPLES;
+    }
+    cuts.push(samples.len());
+
+    cuts.windows(2)
+        .map(|pair| Chunk {
+            decode: pair[0].saturating_sub(OVERLAP_SAMPLES)
+                ..(pair[1] + OVERLAP_SAMPLES).min(samples.len()),
+            keep: pair[0]..pair[1],
+        })
+        .collect()
+}
+
+/// Merge per-chunk segments (already offset to absolute times) into one transcript
+///
+/// Each segment is kept only by the chunk whose `keep` range contains its
+/// midpoint, which removes the copies decoded in the overlaps. A segment whose
+/// text repeats the one before it across a cut is dropped as well, since
+/// whisper.cpp sometimes places the same words slightly differently in each chunk.
+pub fn stitch(chunks: &[Chunk], results: Vec<Vec<Segment>>) -> Vec<Segment> {
+    let mut stitched: Vec<Segment> = Vec::new();
+
+    for (chunk, segments) in chunks.iter().zip(results) {
+        let keep_ms = samples_to_ms(chunk.keep.start)..samples_to_ms(chunk.keep.end);
+
+        for segment in segments {
+            let midpoint = (segment.start_ms + segment.end_ms) / 2;
+            if !keep_ms.contains(&midpoint) {
+                continue;
+            }
+
+            let repeats_previous = stitched.last().map_or(false, |previous| {
+                previous.text.trim() == segment.text.trim() && segment.start_ms <= previous.end_ms
+            });
+            if !repeats_previous {
+                stitched.push(segment);
+            }
+        }
+    }
+
+    stitched
+}
+
+pub fn samples_to_ms(samples: usize) -> i64 {
+    (samples * 1000 / SAMPLE_RATE) as i64
+}
@@ -0,0 +1,84 @@
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use std::io::Write;
+use whisper_rs::{WhisperState, WhisperToken};
+
+use super::chunking::{self, FRAME_SAMPLES};
+
+/// Mean log probability of a segment's text tokens
+///
+/// Timestamp and other special tokens (IDs at or above end-of-text) are
+/// skipped, matching how Whisper's reference implementation scores segments.
+pub fn average_logprob(
+    state: &WhisperState,
+    segment: i32,
+    eot: WhisperToken,
+) -> Result<f32, whisper_rs::WhisperError> {
+    let mut total = 0.0;
+    let mut count = 0;
+    for token in 0..state.full_n_tokens(segment)? {
+        let data = state.full_get_token_data(segment, token)?;
+        if data.id < eot {
+            total += data.plog;
+            count += 1;
+        }
+    }
+
+    Ok(if count > 0 { total / count as f32 } else { 0.0 })
+}
+
+/// Ratio of raw to zlib-compressed text length
+///
+/// Repetitive output ("thank you thank you thank you") compresses far better
+/// than real speech; Whisper treats ratios above 2.4 as a decoding failure.
+pub fn compression_ratio(text: &str) -> f32 {
+    let bytes = text.trim().as_bytes();
+    if bytes.is_empty() {
+        return 0.0;
+    }
+
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    let compressed = encoder
+        .write_all(bytes)
+        .and_then(|_| encoder.finish())
+        .map(|compressed| compressed.len())
+        .unwrap_or(bytes.len());
+
+    bytes.len() as f32 / compressed.max(1) as f32
+}
+
+/// Measures how much of a segment's audio is silent, from its energy
+///
+/// This is not whisper's no-speech probability, which the bundled whisper.cpp
+/// doesn't expose: it is the fraction of 30 ms frames in a segment that fall
+/// below the recording's silence threshold.
+pub struct SilenceEstimator {
+    energies: Vec<f32>,
+    threshold: f32,
+}
+
+impl SilenceEstimator {
+    pub fn new(samples: &[f32]) -> Self {
+        let energies = chunking::frame_energies(samples);
+        let threshold = chunking::silence_threshold(&energies);
+        Self {
+            energies,
+            threshold,
+        }
+    }
+
+    pub fn silence_ratio(&self, start_ms: i64, end_ms: i64) -> f32 {
+        let frame_ms = (FRAME_SAMPLES * 1000 / 16000) as i64;
+        let first = (start_ms.max(0) / frame_ms) as usize;
+        let last = ((end_ms.max(0) / frame_ms) as usize).min(self.energies.len());
+        if first >= last {
+            return 0.0;
+        }
+
+        let silent = self.energies[first..last]
+            .iter()
+            .filter(|&&energy| energy < self.threshold)
+            .count();
+        silent as f32 / (last - first) as f32
+    }
+}
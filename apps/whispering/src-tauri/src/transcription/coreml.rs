@@ -0,0 +1,135 @@
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Where the converted CoreML encoders for the official GGML models are published
+const COREML_ENCODER_BASE_URL: &str = "https://huggingface.co/ggerganov/whisper.cpp/resolve/main";
+
+/// CoreML encoder state for a GGML model - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoreMlEncoderStatus {
+    /// Whether this build can use a CoreML encoder at all (Apple Silicon only)
+    pub supported: bool,
+    /// Path whisper.cpp looks for the compiled encoder at
+    pub encoder_path: String,
+    /// Whether the compiled encoder is already on disk
+    pub installed: bool,
+}
+
+/// Path whisper.cpp probes for the CoreML encoder of a GGML model
+///
+/// Mirrors `whisper_get_coreml_path_encoder`: the extension and any
+/// quantization suffix ("-q5_0") are stripped, since quantization only applies
+/// to the GGML decoder, and "-encoder.mlmodelc" is appended.
+pub fn encoder_path(model_path: &Path) -> PathBuf {
+    let stem = model_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let stem = match stem.rfind('-') {
+        Some(pos) if is_quantization_suffix(&stem[pos..]) => stem[..pos].to_string(),
+        _ => stem,
+    };
+
+    model_path.with_file_name(format!("{}-encoder.mlmodelc", stem))
+}
+
+fn is_quantization_suffix(suffix: &str) -> bool {
+    let bytes = suffix.as_bytes();
+    bytes.len() == 5 && bytes[1] == b'q' && bytes[3] == b'_'
+}
+
+pub fn is_supported() -> bool {
+    cfg!(all(target_os = "macos", target_arch = "aarch64"))
+}
+
+pub fn status(model_path: &Path) -> CoreMlEncoderStatus {
+    let encoder = encoder_path(model_path);
+    CoreMlEncoderStatus {
+        supported: is_supported(),
+        installed: encoder.is_dir(),
+        encoder_path: encoder.to_string_lossy().to_string(),
+    }
+}
+
+/// Download the prebuilt CoreML encoder for an official GGML model and unpack
+/// it next to the model file, where whisper.cpp picks it up on the next load.
+pub async fn download_encoder(model_path: &Path) -> Result<CoreMlEncoderStatus, String> {
+    if !is_supported() {
+        return Err("CoreML encoders are only supported on Apple Silicon Macs".to_string());
+    }
+
+    let encoder = encoder_path(model_path);
+    let encoder_name = encoder
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .ok_or_else(|| format!("Invalid model path: {:?}", model_path))?;
+    let url = format!("{}/{}.zip", COREML_ENCODER_BASE_URL, encoder_name);
+
+    println!("[CoreML] Downloading encoder from {}", url);
+
+    let mut response = tauri_plugin_http::reqwest::get(&url)
+        .await
+        .map_err(|e| format!("Failed to download CoreML encoder: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "No CoreML encoder available for this model ({}): {}",
+            encoder_name,
+            response.status()
+        ));
+    }
+
+    let mut archive = tempfile::Builder::new()
+        .suffix(".zip")
+        .tempfile()
+        .map_err(|e| format!("Failed to create temp file: {}", e))?;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to download CoreML encoder: {}", e))?
+    {
+        std::io::Write::write_all(&mut archive, &chunk)
+            .map_err(|e| format!("Failed to write CoreML encoder: {}", e))?;
+    }
+
+    let parent = encoder
+        .parent()
+        .ok_or_else(|| format!("Invalid model path: {:?}", model_path))?;
+    extract_archive(archive.path(), parent)?;
+
+    if !encoder.is_dir() {
+        return Err(format!(
+            "CoreML archive did not contain {}",
+            encoder.to_string_lossy()
+        ));
+    }
+
+    println!("[CoreML] Installed encoder at {:?}", encoder);
+    Ok(status(model_path))
+}
+
+/// Unpack a zip archive with macOS's built-in `ditto`, which preserves the
+/// bundle structure of `.mlmodelc` directories
+#[cfg(target_os = "macos")]
+fn extract_archive(archive: &Path, destination: &Path) -> Result<(), String> {
+    let output = std::process::Command::new("ditto")
+        .args(["-x", "-k"])
+        .arg(archive)
+        .arg(destination)
+        .output()
+        .map_err(|e| format!("Failed to run ditto: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to extract CoreML encoder: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn extract_archive(_archive: &Path, _destination: &Path) -> Result<(), String> {
+    Err("CoreML encoders are only supported on macOS".to_string())
+}
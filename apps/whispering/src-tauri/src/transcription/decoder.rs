@@ -0,0 +1,103 @@
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Decode compressed audio (MP3, M4A/AAC, ALAC, OGG/Vorbis, FLAC, ...) to mono f32
+///
+/// The container is detected from the data itself, so files dropped on the app
+/// don't need a correct extension.
+///
+/// # Returns
+/// * `Ok((Vec<f32>, u32))` - Mono samples in [-1.0, 1.0] and their sample rate
+/// * `Err(String)` - Error message if the format or codec isn't supported
+pub fn decode_to_mono(audio_data: Vec<u8>) -> Result<(Vec<f32>, u32), String> {
+    let source = MediaSourceStream::new(
+        Box::new(std::io::Cursor::new(audio_data)),
+        Default::default(),
+    );
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &Hint::new(),
+            source,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("Unrecognized audio format: {}", e))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| "No audio track found".to_string())?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("Unsupported audio codec: {}", e))?;
+
+    let mut mono_samples = Vec::new();
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // End of stream is reported as an unexpected EOF
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(e) => return Err(format!("Failed to read audio packet: {}", e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt frame shouldn't fail the whole file
+            Err(SymphoniaError::DecodeError(e)) => {
+                eprintln!("[Audio Decoder] Skipping undecodable packet: {}", e);
+                continue;
+            }
+            Err(e) => return Err(format!("Failed to decode audio: {}", e)),
+        };
+
+        let spec = *decoded.spec();
+        let channels = spec.channels.count().max(1);
+        sample_rate = spec.rate;
+
+        // Reuse the interleaving buffer unless this packet is larger than any before
+        let too_small = buffer
+            .as_ref()
+            .map_or(true, |b| b.capacity() < decoded.capacity() * channels);
+        if too_small {
+            buffer = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+        }
+        let sample_buffer = buffer.as_mut().unwrap();
+        sample_buffer.copy_interleaved_ref(decoded);
+
+        mono_samples.extend(
+            sample_buffer
+                .samples()
+                .chunks_exact(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+    }
+
+    if sample_rate == 0 {
+        return Err("Audio stream has no sample rate".to_string());
+    }
+
+    println!(
+        "[Audio Decoder] Decoded {} mono samples at {} Hz",
+        mono_samples.len(),
+        sample_rate
+    );
+    Ok((mono_samples, sample_rate))
+}
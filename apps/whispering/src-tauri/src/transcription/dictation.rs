@@ -0,0 +1,296 @@
+/// What a spoken command does to the text written so far
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Punctuate(char),
+    NewLine,
+    NewParagraph,
+    /// Delete the most recent sentence
+    ScratchThat,
+}
+
+const ENGLISH: &[(&str, Action)] = &[
+    ("period", Action::Punctuate('.')),
+    ("full stop", Action::Punctuate('.')),
+    ("comma", Action::Punctuate(',')),
+    ("question mark", Action::Punctuate('?')),
+    ("exclamation mark", Action::Punctuate('!')),
+    ("exclamation point", Action::Punctuate('!')),
+    ("colon", Action::Punctuate(':')),
+    ("semicolon", Action::Punctuate(';')),
+    ("new line", Action::NewLine),
+    ("new paragraph", Action::NewParagraph),
+    ("scratch that", Action::ScratchThat),
+    ("delete that", Action::ScratchThat),
+];
+
+const SPANISH: &[(&str, Action)] = &[
+    ("punto", Action::Punctuate('.')),
+    ("coma", Action::Punctuate(',')),
+    ("signo de interrogación", Action::Punctuate('?')),
+    ("signo de exclamación", Action::Punctuate('!')),
+    ("dos puntos", Action::Punctuate(':')),
+    ("punto y coma", Action::Punctuate(';')),
+    ("nueva línea", Action::NewLine),
+    ("nuevo párrafo", Action::NewParagraph),
+    ("borra eso", Action::ScratchThat),
+];
+
+const FRENCH: &[(&str, Action)] = &[
+    ("point", Action::Punctuate('.')),
+    ("virgule", Action::Punctuate(',')),
+    ("point d'interrogation", Action::Punctuate('?')),
+    ("point d'exclamation", Action::Punctuate('!')),
+    ("deux points", Action::Punctuate(':')),
+    ("deux-points", Action::Punctuate(':')),
+    ("point virgule", Action::Punctuate(';')),
+    ("point-virgule", Action::Punctuate(';')),
+    ("à la ligne", Action::NewLine),
+    ("nouvelle ligne", Action::NewLine),
+    ("nouveau paragraphe", Action::NewParagraph),
+    ("efface ça", Action::ScratchThat),
+];
+
+const GERMAN: &[(&str, Action)] = &[
+    ("punkt", Action::Punctuate('.')),
+    ("komma", Action::Punctuate(',')),
+    ("fragezeichen", Action::Punctuate('?')),
+    ("ausrufezeichen", Action::Punctuate('!')),
+    ("doppelpunkt", Action::Punctuate(':')),
+    ("semikolon", Action::Punctuate(';')),
+    ("neue zeile", Action::NewLine),
+    ("neuer absatz", Action::NewParagraph),
+    ("streich das", Action::ScratchThat),
+];
+
+/// Commands that are also everyday words ("the period of", "point de vue"),
+/// only acted on at the end of a phrase
+const ORDINARY_WORDS: &[&str] = &["period", "punto", "point", "punkt"];
+
+/// Punctuation whisper may already have put where a command was spoken
+const TRAILING_MARKS: &[char] = &['.', ',', '?', '!', ':', ';'];
+
+/// The commands for a whisper language code such as "de" or "fr-CA", falling
+/// back to English for unknown or auto-detected languages
+fn command_set(language: Option<&str>) -> &'static [(&'static str, Action)] {
+    let code = language
+        .and_then(|language| language.split(['-', '_']).next())
+        .map(str::to_lowercase);
+    match code.as_deref() {
+        Some("es") => SPANISH,
+        Some("fr") => FRENCH,
+        Some("de") => GERMAN,
+        _ => ENGLISH,
+    }
+}
+
+/// Lowercase a word and drop punctuation, so "Comma," matches "comma"
+fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Builds the output text, tracking where each input segment starts so the
+/// result can be split back up
+#[derive(Default)]
+struct Writer {
+    output: String,
+    starts: Vec<usize>,
+    capitalize_next: bool,
+}
+
+impl Writer {
+    fn push_word(&mut self, word: &str) {
+        if !self.output.is_empty() && !self.output.ends_with(char::is_whitespace) {
+            self.output.push(' ');
+        }
+        if std::mem::take(&mut self.capitalize_next) {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                self.output.extend(first.to_uppercase());
+                self.output.push_str(chars.as_str());
+                return;
+            }
+        }
+        self.output.push_str(word);
+    }
+
+    fn apply(&mut self, action: Action) {
+        match action {
+            Action::Punctuate(mark) => {
+                let kept = self.output.trim_end().trim_end_matches(TRAILING_MARKS).len();
+                self.truncate(kept);
+                self.output.push(mark);
+                self.capitalize_next = matches!(mark, '.' | '?' | '!');
+            }
+            Action::NewLine | Action::NewParagraph => {
+                let kept = self.output.trim_end_matches(' ').len();
+                self.truncate(kept);
+                self.output.push_str(if action == Action::NewLine { "\n" } else { "\n\n" });
+                self.capitalize_next = true;
+            }
+            Action::ScratchThat => {
+                // Skip the end of the last sentence, then cut back to the one before
+                let sentence = self.output.trim_end().trim_end_matches(['.', '?', '!']);
+                let kept = sentence
+                    .rfind(['.', '?', '!', '\n'])
+                    .map_or(0, |boundary| boundary + 1);
+                self.truncate(kept);
+                self.capitalize_next = true;
+            }
+        }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.output.truncate(len);
+        for start in &mut self.starts {
+            *start = (*start).min(len);
+        }
+    }
+}
+
+/// Replace spoken dictation commands with their effects
+///
+/// "new line" and "new paragraph" insert line breaks, punctuation names
+/// ("comma", "question mark") become the mark itself, and "scratch that"
+/// deletes the sentence before it. `texts` are consecutive pieces of one
+/// transcript, such as whisper.cpp segments, so a command can act on text
+/// from an earlier piece; one output is returned per input. Commands are
+/// recognised in the given language, defaulting to English. Commands that
+/// are also ordinary words, like "period" or "point", only count at the end
+/// of a piece or where whisper marked a pause after them.
+pub fn apply(texts: &[&str], language: Option<&str>) -> Vec<String> {
+    let mut commands: Vec<(Vec<String>, Action)> = command_set(language)
+        .iter()
+        .map(|(phrase, action)| (phrase.split_whitespace().map(normalize_word).collect(), *action))
+        .collect();
+    // "punto y coma" must win over "punto"
+    commands.sort_by_key(|(words, _)| std::cmp::Reverse(words.len()));
+
+    let mut writer = Writer::default();
+    for text in texts {
+        writer.starts.push(writer.output.len());
+
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let normalized: Vec<String> = words.iter().map(|word| normalize_word(word)).collect();
+        let mut index = 0;
+        while index < words.len() {
+            let phrase_end = index + 1 == words.len() || words[index].ends_with(TRAILING_MARKS);
+            let command = commands.iter().find(|(phrase, _)| {
+                normalized[index..].starts_with(phrase)
+                    && (phrase_end
+                        || phrase.len() > 1
+                        || !ORDINARY_WORDS.contains(&phrase[0].as_str()))
+            });
+            match command {
+                Some((phrase, action)) => {
+                    writer.apply(*action);
+                    index += phrase.len();
+                }
+                None => {
+                    writer.push_word(words[index]);
+                    index += 1;
+                }
+            }
+        }
+    }
+
+    let mut ends = writer.starts.iter().skip(1).copied().collect::<Vec<_>>();
+    ends.push(writer.output.len());
+    writer
+        .starts
+        .iter()
+        .zip(ends)
+        .map(|(&start, end)| writer.output[start..end].to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dictate(text: &str, language: Option<&str>) -> String {
+        apply(&[text], language).concat()
+    }
+
+    #[test]
+    fn picks_command_set_by_language_code() {
+        assert_eq!(command_set(None), ENGLISH);
+        assert_eq!(command_set(Some("auto")), ENGLISH);
+        assert_eq!(command_set(Some("es")), SPANISH);
+        assert_eq!(command_set(Some("fr-CA")), FRENCH);
+        assert_eq!(command_set(Some("de_AT")), GERMAN);
+    }
+
+    #[test]
+    fn english_commands() {
+        assert_eq!(dictate("hello comma world period", None), "hello, world.");
+        assert_eq!(
+            dictate("really question mark yes exclamation mark", Some("en")),
+            "really? Yes!"
+        );
+        assert_eq!(dictate("note colon buy milk semicolon eggs", None), "note: buy milk; eggs");
+        assert_eq!(dictate("first line new line second line", None), "first line\nSecond line");
+        assert_eq!(dictate("one new paragraph two", None), "one\n\nTwo");
+    }
+
+    #[test]
+    fn spanish_commands() {
+        assert_eq!(dictate("hola coma qué tal punto", Some("es")), "hola, qué tal.");
+        assert_eq!(dictate("uno nueva línea dos", Some("es")), "uno\nDos");
+        assert_eq!(dictate("hola. Borra eso. Adiós", Some("es")), "Adiós");
+    }
+
+    #[test]
+    fn french_commands() {
+        assert_eq!(
+            dictate("bonjour virgule ça va point d'interrogation", Some("fr")),
+            "bonjour, ça va?"
+        );
+        assert_eq!(dictate("a point-virgule b", Some("fr")), "a; b");
+        assert_eq!(dictate("note deux-points lait", Some("fr")), "note: lait");
+        assert_eq!(dictate("un à la ligne deux", Some("fr")), "un\nDeux");
+    }
+
+    #[test]
+    fn german_commands() {
+        assert_eq!(
+            dictate("hallo komma welt neue zeile tschüss", Some("de")),
+            "hallo, welt\nTschüss"
+        );
+        assert_eq!(dictate("wirklich fragezeichen", Some("de")), "wirklich?");
+    }
+
+    #[test]
+    fn scratch_that_deletes_the_last_sentence() {
+        assert_eq!(dictate("I like cats. Scratch that. I like dogs.", None), "I like dogs.");
+        assert_eq!(
+            dictate("First one. Second one scratch that third", None),
+            "First one. Third"
+        );
+        assert_eq!(dictate("one new line two delete that", None), "one\n");
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        assert_eq!(dictate("hola punto y coma adiós punto", Some("es")), "hola; adiós.");
+        assert_eq!(dictate("a point virgule b", Some("fr")), "a; b");
+        assert_eq!(dictate("quoi point d'exclamation", Some("fr")), "quoi!");
+    }
+
+    #[test]
+    fn ordinary_words_only_count_at_a_phrase_end() {
+        assert_eq!(dictate("the period of time", None), "the period of time");
+        assert_eq!(dictate("that was it period. next", None), "that was it. Next");
+        assert_eq!(dictate("point de vue", Some("fr")), "point de vue");
+        assert_eq!(dictate("c'est fini point", Some("fr")), "c'est fini.");
+        assert_eq!(dictate("punto de vista", Some("es")), "punto de vista");
+        assert_eq!(dictate("auf den Punkt gebracht", Some("de")), "auf den Punkt gebracht");
+    }
+
+    #[test]
+    fn commands_act_across_pieces() {
+        assert_eq!(apply(&["hello", "comma world"], None), ["hello", ", world"]);
+    }
+}
@@ -4,6 +4,9 @@ use thiserror::Error;
 #[derive(Error, Debug, Serialize, Deserialize)]
 #[serde(tag = "name")]
 pub enum TranscriptionError {
+    #[error("Transcription cancelled: {message}")]
+    CancelledError { message: String },
+
     #[error("Audio read error: {message}")]
     AudioReadError { message: String },
 
@@ -0,0 +1,106 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// "ggml" magic at the start of every whisper.cpp model file
+const GGML_MAGIC: u32 = 0x6767_6d6c;
+
+/// "GGUF" magic used by llama.cpp-era models
+///
+/// whisper.cpp only reads GGML files, so GGUF conversions can't be supported
+/// here; quantized GGML models (q5_0, q8_0, ...) are the equivalent.
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+
+/// Error for GGUF files, explaining what to use instead
+pub const GGUF_UNSUPPORTED: &str = "GGUF models are not supported by whisper.cpp. \
+    Use a GGML .bin model (quantized variants such as q5_0 or q8_0 work).";
+
+/// Quantized models store `quantization version * 1000 + ftype`
+const QNT_VERSION_FACTOR: i32 = 1000;
+
+/// Hyperparameters from a whisper GGML model header
+#[derive(Debug, Clone)]
+pub struct GgmlHeader {
+    pub n_vocab: i32,
+    pub n_audio_ctx: i32,
+    pub n_audio_state: i32,
+    pub n_audio_head: i32,
+    pub n_audio_layer: i32,
+    pub n_text_ctx: i32,
+    pub n_text_state: i32,
+    pub n_text_head: i32,
+    pub n_text_layer: i32,
+    pub n_mels: i32,
+    pub ftype: i32,
+}
+
+impl GgmlHeader {
+    /// Weight type name as used in model file names ("f16", "q5_0", ...)
+    pub fn quantization(&self) -> &'static str {
+        match self.ftype % QNT_VERSION_FACTOR {
+            0 => "f32",
+            1 => "f16",
+            2 => "q4_0",
+            3 => "q4_1",
+            7 => "q8_0",
+            8 => "q5_0",
+            9 => "q5_1",
+            10 => "q2_k",
+            11 => "q3_k",
+            12 => "q4_k",
+            13 => "q5_k",
+            14 => "q6_k",
+            _ => "unknown",
+        }
+    }
+}
+
+/// Whether the file at `path` is a GGUF model
+pub fn is_gguf(path: &Path) -> bool {
+    let mut magic = [0u8; 4];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut magic))
+        .is_ok_and(|_| &magic == GGUF_MAGIC)
+}
+
+/// Read and validate the header of a whisper model file
+///
+/// Gives a clear error for files whisper.cpp would otherwise reject with a
+/// generic load failure, such as GGUF conversions or truncated downloads.
+pub fn read_header(path: &Path) -> Result<GgmlHeader, String> {
+    let mut file =
+        File::open(path).map_err(|e| format!("Failed to open model {:?}: {}", path, e))?;
+
+    let mut magic = [0u8; 4];
+    file.read_exact(&mut magic)
+        .map_err(|_| format!("Model file {:?} is empty or truncated", path))?;
+
+    if &magic == GGUF_MAGIC {
+        return Err(GGUF_UNSUPPORTED.to_string());
+    }
+    if u32::from_le_bytes(magic) != GGML_MAGIC {
+        return Err(format!("{:?} is not a whisper GGML model", path));
+    }
+
+    let mut fields = [0i32; 11];
+    for field in fields.iter_mut() {
+        let mut bytes = [0u8; 4];
+        file.read_exact(&mut bytes)
+            .map_err(|_| format!("Model file {:?} has a truncated header", path))?;
+        *field = i32::from_le_bytes(bytes);
+    }
+
+    Ok(GgmlHeader {
+        n_vocab: fields[0],
+        n_audio_ctx: fields[1],
+        n_audio_state: fields[2],
+        n_audio_head: fields[3],
+        n_audio_layer: fields[4],
+        n_text_ctx: fields[5],
+        n_text_state: fields[6],
+        n_text_head: fields[7],
+        n_text_layer: fields[8],
+        n_mels: fields[9],
+        ftype: fields[10],
+    })
+}
@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use std::ffi::{c_int, c_void};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperToken};
+use whisper_rs_sys::{whisper_context, whisper_state, whisper_token_data};
+
+use super::confidence;
+use super::error::TranscriptionError;
+use super::whisper_cpp::normalize_phrase;
+
+/// whisper.cpp's default `grammar_penalty`
+const DEFAULT_PENALTY: f32 = 100.0;
+
+/// Longest phrase, in tokens, a grammar can contain
+const MAX_PHRASE_TOKENS: usize = 64;
+
+/// Beam width for constrained decoding; a wider search recovers when the
+/// greedy choice would leave the grammar early
+const BEAM_SIZE: i32 = 5;
+
+/// A vocabulary of phrases to constrain decoding to - from frontend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Grammar {
+    /// Allowed phrases, e.g. voice-control commands like "open settings"
+    pub phrases: Vec<String>,
+    /// Logit penalty for tokens that leave the grammar; higher is stricter
+    pub penalty: Option<f32>,
+}
+
+/// What constrained decoding settled on - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrammarMatch {
+    /// The phrase as written in the grammar, or `None` if the decoder left it
+    pub phrase: Option<String>,
+    /// Text decoded under the constraint
+    pub text: String,
+    /// Mean log probability of the constrained text (0.0 is certain)
+    pub avg_logprob: f32,
+}
+
+/// A transcript and its grammar-constrained reading - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConstrainedTranscript {
+    /// Unconstrained transcript of the same audio
+    pub transcript: String,
+    pub constrained: GrammarMatch,
+}
+
+/// Token sequences the decoder may produce, checked before each token is sampled
+///
+/// whisper-rs hands `set_grammar` elements to whisper.cpp as a flat list where
+/// whisper.cpp expects one pointer per rule, so the same rule is enforced here
+/// through the logits filter: tokens that don't continue any phrase (or end
+/// one) have `penalty` subtracted from their logits, as whisper.cpp does.
+struct Constraint {
+    sequences: Vec<Vec<WhisperToken>>,
+    eot: WhisperToken,
+    n_vocab: usize,
+    penalty: f32,
+}
+
+impl Constraint {
+    fn allowed_next(&self, generated: &[WhisperToken]) -> Vec<WhisperToken> {
+        let mut allowed: Vec<WhisperToken> = self
+            .sequences
+            .iter()
+            .filter(|sequence| sequence.starts_with(generated))
+            .map(|sequence| sequence.get(generated.len()).copied().unwrap_or(self.eot))
+            .collect();
+        if allowed.is_empty() {
+            // Off the grammar already; let the decoder finish
+            allowed.push(self.eot);
+        }
+        allowed.sort_unstable();
+        allowed.dedup();
+        allowed
+    }
+}
+
+unsafe extern "C" fn constrain_logits(
+    _ctx: *mut whisper_context,
+    _state: *mut whisper_state,
+    tokens: *const whisper_token_data,
+    n_tokens: c_int,
+    logits: *mut f32,
+    user_data: *mut c_void,
+) {
+    let constraint = &*(user_data as *const Constraint);
+    let generated: Vec<WhisperToken> = if tokens.is_null() || n_tokens <= 0 {
+        Vec::new()
+    } else {
+        std::slice::from_raw_parts(tokens, n_tokens as usize)
+            .iter()
+            .map(|token| token.id)
+            .filter(|&id| id < constraint.eot)
+            .collect()
+    };
+
+    let allowed = constraint.allowed_next(&generated);
+    let logits = std::slice::from_raw_parts_mut(logits, constraint.n_vocab);
+    for (token, logit) in logits.iter_mut().enumerate() {
+        if allowed.binary_search(&(token as WhisperToken)).is_err() {
+            *logit -= constraint.penalty;
+        }
+    }
+}
+
+/// The ways whisper is likely to spell a phrase at the start of a transcript
+///
+/// Text tokens carry their leading space, and whisper usually capitalizes the
+/// first word, so both casings are allowed.
+pub(super) fn spellings(phrase: &str) -> Vec<String> {
+    let phrase = phrase.trim();
+    let mut chars = phrase.chars();
+    let capitalized = match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    };
+
+    let mut spellings = vec![format!(" {}", phrase), format!(" {}", capitalized)];
+    spellings.dedup();
+    spellings
+}
+
+/// Decode samples constrained to the grammar's phrases
+///
+/// Meant for short utterances such as voice commands: the audio is decoded as
+/// a single segment without timestamps, and the result is matched back to the
+/// phrase it spells, ignoring case and punctuation.
+pub fn decode(
+    context: &WhisperContext,
+    samples: &[f32],
+    language: Option<&str>,
+    grammar: &Grammar,
+) -> Result<GrammarMatch, TranscriptionError> {
+    let to_error = |e: whisper_rs::WhisperError| TranscriptionError::TranscriptionError {
+        message: format!("Constrained decoding failed: {}", e),
+    };
+
+    let mut sequences = Vec::new();
+    for phrase in grammar.phrases.iter().filter(|phrase| !phrase.trim().is_empty()) {
+        for spelling in spellings(phrase) {
+            sequences.push(context.tokenize(&spelling, MAX_PHRASE_TOKENS).map_err(to_error)?);
+        }
+    }
+    if sequences.is_empty() {
+        return Err(TranscriptionError::TranscriptionError {
+            message: "Grammar has no phrases".to_string(),
+        });
+    }
+
+    let constraint = Constraint {
+        sequences,
+        eot: context.token_eot(),
+        n_vocab: context.n_vocab().max(0) as usize,
+        penalty: grammar.penalty.unwrap_or(DEFAULT_PENALTY),
+    };
+
+    let mut params = FullParams::new(SamplingStrategy::BeamSearch {
+        beam_size: BEAM_SIZE,
+        patience: -1.0,
+    });
+    params.set_language(language);
+    params.set_no_timestamps(true);
+    params.set_single_segment(true);
+    params.set_max_tokens(MAX_PHRASE_TOKENS as i32);
+    // Penalized tokens drag the log probability down, which would otherwise
+    // trigger temperature fallback and undo the constraint
+    params.set_temperature_inc(0.0);
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_suppress_blank(true);
+    unsafe {
+        params.set_filter_logits_callback(Some(constrain_logits));
+        params.set_filter_logits_callback_user_data(
+            &constraint as *const Constraint as *mut c_void,
+        );
+    }
+
+    let mut state = context.create_state().map_err(to_error)?;
+    state.full(params, samples).map_err(to_error)?;
+
+    let mut text = String::new();
+    let mut logprob_total = 0.0;
+    let segment_count = state.full_n_segments().map_err(to_error)?;
+    for segment in 0..segment_count {
+        text.push_str(&state.full_get_segment_text_lossy(segment).map_err(to_error)?);
+        logprob_total +=
+            confidence::average_logprob(&state, segment, constraint.eot).map_err(to_error)?;
+    }
+    let text = text.trim().to_string();
+
+    let normalized = normalize_phrase(&text);
+    let phrase = grammar
+        .phrases
+        .iter()
+        .find(|phrase| normalize_phrase(phrase) == normalized)
+        .cloned();
+
+    Ok(GrammarMatch {
+        phrase,
+        text,
+        avg_logprob: if segment_count > 0 {
+            logprob_total / segment_count as f32
+        } else {
+            0.0
+        },
+    })
+}
@@ -0,0 +1,130 @@
+use whisper_rs::{WhisperContext, WhisperToken};
+use whisper_rs_sys::whisper_token_data;
+
+use super::error::TranscriptionError;
+use super::grammar::spellings;
+use super::whisper_cpp::DecodingOptions;
+
+/// Logit bonus for hotword tokens when `hotwordBoost` isn't set; roughly
+/// seven times the odds, enough to tip close calls without forcing the phrase
+const DEFAULT_BOOST: f32 = 2.0;
+
+/// Longest hotword, in tokens
+const MAX_HOTWORD_TOKENS: usize = 32;
+
+/// Phrases whose tokens are made more likely while decoding
+///
+/// Applied from the logits filter before each token is sampled: at the start
+/// of a word, the first token of every phrase gets `boost` added to its
+/// logit, and once the decoder has produced the start of a phrase, its next
+/// token is boosted too, so a phrase that gets going tends to be completed. Unlike the initial
+/// prompt, this keeps working past the first 30 second window.
+pub struct HotwordBias {
+    sequences: Vec<Vec<WhisperToken>>,
+    eot: WhisperToken,
+    /// Whether each text token ends in whitespace or punctuation, so that the
+    /// token after it starts a new word
+    ends_word: Vec<bool>,
+    n_vocab: usize,
+    boost: f32,
+}
+
+impl HotwordBias {
+    /// Tokenize the hotwords in `decoding`, or return `None` when there are none
+    pub fn new(
+        context: &WhisperContext,
+        decoding: &DecodingOptions,
+    ) -> Result<Option<Self>, TranscriptionError> {
+        let to_error = |e: whisper_rs::WhisperError| TranscriptionError::TranscriptionError {
+            message: format!("Failed to tokenize hotwords: {}", e),
+        };
+
+        let mut sequences = Vec::new();
+        for phrase in decoding.hotwords.iter().flatten() {
+            if phrase.trim().is_empty() {
+                continue;
+            }
+            for spelling in spellings(phrase) {
+                let sequence = context.tokenize(&spelling, MAX_HOTWORD_TOKENS).map_err(to_error)?;
+                if !sequence.is_empty() {
+                    sequences.push(sequence);
+                }
+            }
+        }
+        if sequences.is_empty() {
+            return Ok(None);
+        }
+
+        let eot = context.token_eot();
+        let ends_word = (0..eot)
+            .map(|token| {
+                context.token_to_str(token).is_ok_and(|text| {
+                    text.chars()
+                        .last()
+                        .is_some_and(|c| c.is_whitespace() || c.is_ascii_punctuation())
+                })
+            })
+            .collect();
+
+        Ok(Some(Self {
+            sequences,
+            eot,
+            ends_word,
+            n_vocab: context.n_vocab().max(0) as usize,
+            boost: decoding.hotword_boost.unwrap_or(DEFAULT_BOOST),
+        }))
+    }
+
+    /// Boost the logits of tokens that start a hotword at a word boundary, or
+    /// continue one the decoder has begun
+    ///
+    /// # Safety
+    /// `tokens` must point to `n_tokens` decoded tokens (or be null) and
+    /// `logits` to a full row of vocabulary logits, as whisper.cpp passes them
+    /// to its logits filter.
+    pub unsafe fn apply(
+        &self,
+        tokens: *const whisper_token_data,
+        n_tokens: i32,
+        logits: *mut f32,
+    ) {
+        let generated: Vec<WhisperToken> = if tokens.is_null() || n_tokens <= 0 {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(tokens, n_tokens as usize)
+                .iter()
+                .map(|token| token.id)
+                .filter(|&id| id < self.eot)
+                .collect()
+        };
+        let logits = std::slice::from_raw_parts_mut(logits, self.n_vocab);
+
+        // Starting a phrase mid-word would skew every word towards it
+        let at_word_start = match generated.last() {
+            Some(&token) => self.ends_word.get(token as usize).copied().unwrap_or(false),
+            None => true,
+        };
+
+        let mut boosted: Vec<WhisperToken> = Vec::new();
+        for sequence in &self.sequences {
+            if at_word_start {
+                boosted.push(sequence[0]);
+            }
+            // The longest part of the phrase the decoder has just produced
+            if let Some(matched) = (1..sequence.len())
+                .rev()
+                .find(|&length| generated.ends_with(&sequence[..length]))
+            {
+                boosted.push(sequence[matched]);
+            }
+        }
+        boosted.sort_unstable();
+        boosted.dedup();
+
+        for token in boosted {
+            if let Some(logit) = logits.get_mut(token as usize) {
+                *logit += self.boost;
+            }
+        }
+    }
+}
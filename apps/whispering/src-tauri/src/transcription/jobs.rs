@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Tracks in-flight transcription jobs so they can be cancelled by ID
+pub struct JobRegistry {
+    jobs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register a job and return the flag whisper.cpp polls to abort it
+    pub fn register(&self, job_id: &str) -> Arc<AtomicBool> {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.jobs
+            .lock()
+            .unwrap()
+            .insert(job_id.to_string(), cancelled.clone());
+        cancelled
+    }
+
+    /// Forget a job once it has completed, failed, or been cancelled
+    ///
+    /// `cancelled` is the flag `register` returned. If a later job reused the
+    /// same ID its entry is left alone, so it can still be cancelled.
+    pub fn finish(&self, job_id: &str, cancelled: &Arc<AtomicBool>) {
+        let mut jobs = self.jobs.lock().unwrap();
+        if jobs.get(job_id).map_or(false, |current| Arc::ptr_eq(current, cancelled)) {
+            jobs.remove(job_id);
+        }
+    }
+
+    /// Request cancellation of a job. Returns false if no such job is running.
+    pub fn cancel(&self, job_id: &str) -> bool {
+        match self.jobs.lock().unwrap().get(job_id) {
+            Some(cancelled) => {
+                cancelled.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,437 @@
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{SampleFormat, Stream};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::chunking;
+use super::model_manager::{self, ModelManager};
+use super::stats::InferenceTimings;
+use super::whisper_cpp::{self, ComputeDevice, Segment, WhisperOptions};
+use super::Vocabulary;
+#[cfg(desktop)]
+use crate::overlay::LiveCaptions;
+use crate::recorder::recorder::{find_device, get_optimal_config};
+
+/// Event emitted with interim and finalized live captions
+pub const LIVE_EVENT: &str = "live-transcription";
+
+const SAMPLE_RATE: usize = 16000;
+
+/// How often the sliding window is re-transcribed
+const STEP: Duration = Duration::from_millis(1000);
+
+/// Text is finalized once the window grows this long, keeping each pass fast
+const MAX_WINDOW_SAMPLES: usize = 10 * SAMPLE_RATE;
+
+/// A pause this long at the end of the window finalizes everything before it
+const TRAILING_SILENCE_SAMPLES: usize = 8 * SAMPLE_RATE / 10;
+
+/// Audio kept from the previous window so words on the cut aren't lost
+const KEEP_SAMPLES: usize = SAMPLE_RATE / 5;
+
+/// Live caption text - sent to frontend
+///
+/// Interim results for the current window are replaced by each update until a
+/// final result for the same audio arrives; offsets are in milliseconds since
+/// the session started.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveTranscriptionUpdate {
+    pub text: String,
+    pub is_final: bool,
+    pub start_ms: i64,
+    pub end_ms: i64,
+}
+
+struct LiveSession {
+    stop: Arc<AtomicBool>,
+    capture: JoinHandle<()>,
+    worker: JoinHandle<String>,
+}
+
+/// The running live transcription session, if any
+pub struct LiveTranscription {
+    session: Mutex<Option<LiveSession>>,
+}
+
+impl LiveTranscription {
+    pub fn new() -> Self {
+        Self {
+            session: Mutex::new(None),
+        }
+    }
+}
+
+/// Caption microphone audio in real time with a local whisper.cpp model
+///
+/// Audio from `device_name` is transcribed in a sliding window every second.
+/// Each pass emits an interim `live-transcription` event; once the window
+/// reaches ten seconds or the speaker pauses, the completed text is emitted as
+/// final and dropped from the window. Use a small model (tiny/base) to keep up
+/// with real time on CPUs.
+#[tauri::command]
+pub async fn start_live_transcription(
+    device_name: String,
+    model_path: String,
+    language: Option<String>,
+    device: Option<ComputeDevice>,
+    model_manager: tauri::State<'_, ModelManager>,
+    live: tauri::State<'_, LiveTranscription>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    if live.session.lock().unwrap().is_some() {
+        return Err("Live transcription is already running".to_string());
+    }
+
+    // Load up front so a bad model fails the start rather than the first pass,
+    // without holding the session lock through a load that can take seconds
+    let model_path = PathBuf::from(model_path);
+    let device = device.unwrap_or_default();
+    {
+        let model_manager = model_manager.inner().clone();
+        let model_path = model_path.clone();
+        tokio::task::spawn_blocking(move || model_manager.get_or_load_whisper(model_path, device))
+            .await
+            .map_err(|e| format!("Task join error: {}", e))??;
+    }
+
+    // Another start may have won the race while the model loaded
+    let mut session = live.session.lock().unwrap();
+    if session.is_some() {
+        return Err("Live transcription is already running".to_string());
+    }
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let pending = Arc::new(Mutex::new(Vec::new()));
+    let capture = spawn_capture(device_name, pending.clone(), stop.clone())?;
+
+    let options = super::whisper_options(
+        language,
+        None,
+        None,
+        &app_handle.state::<Vocabulary>(),
+        &app_handle,
+    );
+    #[cfg(desktop)]
+    let captions_handle = app_handle.clone();
+    let worker = {
+        let model_manager = model_manager.inner().clone();
+        let stop = stop.clone();
+        std::thread::spawn(move || {
+            run_live_loop(
+                &model_manager,
+                model_path,
+                device,
+                &options,
+                &pending,
+                &stop,
+                &app_handle,
+            )
+        })
+    };
+
+    *session = Some(LiveSession {
+        stop,
+        capture,
+        worker,
+    });
+    println!("[Live] Live transcription started");
+
+    #[cfg(desktop)]
+    if let Err(e) = captions_handle.state::<LiveCaptions>().show(&captions_handle) {
+        eprintln!("[Live] {}", e);
+    }
+    Ok(())
+}
+
+/// Stop live transcription, finalize the remaining audio, and return the full
+/// finalized transcript
+#[tauri::command]
+pub async fn stop_live_transcription(
+    live: tauri::State<'_, LiveTranscription>,
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let session = live
+        .session
+        .lock()
+        .unwrap()
+        .take()
+        .ok_or_else(|| "Live transcription is not running".to_string())?;
+    session.stop.store(true, Ordering::Relaxed);
+
+    let transcript = tokio::task::spawn_blocking(move || {
+        let _ = session.capture.join();
+        session
+            .worker
+            .join()
+            .map_err(|_| "Live transcription worker panicked".to_string())
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?;
+
+    #[cfg(desktop)]
+    app_handle.state::<LiveCaptions>().hide(&app_handle);
+    #[cfg(not(desktop))]
+    let _ = app_handle;
+    transcript
+}
+
+/// Open the input device on its own thread (cpal streams must stay on the
+/// thread that built them on macOS) and append mono samples to `pending`
+/// until `stop` is set
+///
+/// Returns once the stream is running, or with the error that prevented it.
+fn spawn_capture(
+    device_name: String,
+    pending: Arc<Mutex<Vec<f32>>>,
+    stop: Arc<AtomicBool>,
+) -> Result<JoinHandle<()>, String> {
+    let (ready_tx, ready_rx) = mpsc::channel();
+
+    let handle = std::thread::spawn(move || {
+        let stream = match build_capture_stream(&device_name, pending) {
+            Ok(stream) => stream,
+            Err(e) => {
+                let _ = ready_tx.send(Err(e));
+                return;
+            }
+        };
+        if let Err(e) = stream.play() {
+            let _ = ready_tx.send(Err(format!("Failed to start stream: {}", e)));
+            return;
+        }
+        let _ = ready_tx.send(Ok(()));
+
+        while !stop.load(Ordering::Relaxed) {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        // Stream drops here, releasing the device
+    });
+
+    ready_rx
+        .recv()
+        .map_err(|_| "Audio capture thread exited unexpectedly".to_string())??;
+    Ok(handle)
+}
+
+fn build_capture_stream(
+    device_name: &str,
+    pending: Arc<Mutex<Vec<f32>>>,
+) -> Result<Stream, String> {
+    let host = cpal::default_host();
+    let device = find_device(&host, device_name)?;
+    let config = get_optimal_config(&device, Some(SAMPLE_RATE as u32))?;
+    let sample_format = config.sample_format();
+    let sample_rate = config.sample_rate().0;
+    let channels = config.channels() as usize;
+    let stream_config = cpal::StreamConfig {
+        channels: config.channels(),
+        sample_rate: cpal::SampleRate(sample_rate),
+        buffer_size: cpal::BufferSize::Default,
+    };
+
+    let push = move |samples: &mut dyn Iterator<Item = f32>| {
+        let interleaved: Vec<f32> = samples.collect();
+        let mono: Vec<f32> = interleaved
+            .chunks(channels.max(1))
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect();
+        let resampled = resample_linear(&mono, sample_rate, SAMPLE_RATE as u32);
+        pending.lock().unwrap().extend(resampled);
+    };
+    let err_fn = |err| eprintln!("[Live] Audio stream error: {}", err);
+
+    let stream = match sample_format {
+        SampleFormat::F32 => device.build_input_stream(
+            &stream_config,
+            move |data: &[f32], _: &_| push(&mut data.iter().copied()),
+            err_fn,
+            None,
+        ),
+        SampleFormat::I16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[i16], _: &_| push(&mut data.iter().map(|&s| s as f32 / 32768.0)),
+            err_fn,
+            None,
+        ),
+        SampleFormat::U16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[u16], _: &_| {
+                push(&mut data.iter().map(|&s| (s as f32 - 32768.0) / 32768.0))
+            },
+            err_fn,
+            None,
+        ),
+        _ => return Err(format!("Unsupported sample format: {:?}", sample_format)),
+    };
+
+    stream.map_err(|e| format!("Failed to build input stream: {}", e))
+}
+
+/// Cheap linear resampling for the live path, where latency matters more than
+/// the last bit of fidelity
+fn resample_linear(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let ratio = from as f64 / to as f64;
+    let output_len = (samples.len() as f64 / ratio) as usize;
+    (0..output_len)
+        .map(|i| {
+            let position = i as f64 * ratio;
+            let index = position as usize;
+            let next = samples[(index + 1).min(samples.len() - 1)];
+            let fraction = (position - index as f64) as f32;
+            samples[index] * (1.0 - fraction) + next * fraction
+        })
+        .collect()
+}
+
+/// Re-transcribe the sliding window every `STEP` until stopped, then finalize
+/// what's left and return the whole finalized transcript
+fn run_live_loop(
+    model_manager: &ModelManager,
+    model_path: PathBuf,
+    device: ComputeDevice,
+    options: &WhisperOptions,
+    pending: &Mutex<Vec<f32>>,
+    stop: &AtomicBool,
+    app: &AppHandle,
+) -> String {
+    let mut window: Vec<f32> = Vec::new();
+    // Session offset of the window's first sample
+    let mut window_start = 0usize;
+    let mut transcript = String::new();
+
+    loop {
+        let stopping = stop.load(Ordering::Relaxed);
+        if !stopping {
+            std::thread::sleep(STEP);
+        }
+        window.append(&mut pending.lock().unwrap());
+
+        let segments = if has_speech(&window) {
+            match transcribe_window(model_manager, &model_path, device, options, &window) {
+                Ok(segments) => segments,
+                Err(e) => {
+                    eprintln!("[Live] Transcription pass failed: {}", e);
+                    Vec::new()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+        let offset_ms = chunking::samples_to_ms(window_start);
+
+        // Decide how much of the window is settled enough to finalize
+        let cut = if stopping || ends_in_silence(&window) {
+            window.len()
+        } else if window.len() >= MAX_WINDOW_SAMPLES {
+            // Keep the last segment open; it may still be mid-sentence
+            match segments.last() {
+                Some(last) if segments.len() > 1 => ms_to_samples(last.start_ms),
+                _ => window.len().saturating_sub(KEEP_SAMPLES),
+            }
+        } else {
+            0
+        };
+
+        let cut_ms = chunking::samples_to_ms(cut);
+        let (settled, open): (Vec<&Segment>, Vec<&Segment>) = segments
+            .iter()
+            .partition(|segment| cut > 0 && segment.start_ms < cut_ms);
+
+        if !settled.is_empty() {
+            let text = join_text(&settled);
+            emit(app, &text, true, &settled, offset_ms);
+            if !transcript.is_empty() {
+                transcript.push(' ');
+            }
+            transcript.push_str(&text);
+        }
+        if !open.is_empty() {
+            emit(app, &join_text(&open), false, &open, offset_ms);
+        }
+
+        if cut > 0 {
+            window.drain(..cut.min(window.len()));
+            window_start += cut;
+        }
+        if stopping {
+            break;
+        }
+    }
+
+    println!("[Live] Live transcription stopped");
+    transcript
+}
+
+fn transcribe_window(
+    model_manager: &ModelManager,
+    model_path: &Path,
+    device: ComputeDevice,
+    options: &WhisperOptions,
+    window: &[f32],
+) -> Result<Vec<Segment>, String> {
+    let engine_arc = model_manager.get_or_load_whisper(model_path.to_path_buf(), device)?;
+    let mut engine_guard = engine_arc.lock().unwrap();
+    let Some(model_manager::Engine::Whisper(context)) = engine_guard.as_mut() else {
+        return Err("Whisper model is not loaded".to_string());
+    };
+
+    whisper_cpp::transcribe(context, window, options, None, &InferenceTimings::default())
+        .map_err(|e| e.to_string())
+}
+
+fn emit(app: &AppHandle, text: &str, is_final: bool, segments: &[&Segment], offset_ms: i64) {
+    let (Some(first), Some(last)) = (segments.first(), segments.last()) else {
+        return;
+    };
+    let _ = app.emit(
+        LIVE_EVENT,
+        LiveTranscriptionUpdate {
+            text: text.to_string(),
+            is_final,
+            start_ms: first.start_ms + offset_ms,
+            end_ms: last.end_ms + offset_ms,
+        },
+    );
+}
+
+fn join_text(segments: &[&Segment]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn has_speech(window: &[f32]) -> bool {
+    let energies = chunking::frame_energies(window);
+    let threshold = chunking::silence_threshold(&energies);
+    energies.iter().any(|&energy| energy >= threshold)
+}
+
+fn ends_in_silence(window: &[f32]) -> bool {
+    if window.len() < TRAILING_SILENCE_SAMPLES * 2 {
+        return false;
+    }
+
+    let energies = chunking::frame_energies(window);
+    let threshold = chunking::silence_threshold(&energies);
+    let trailing_frames = TRAILING_SILENCE_SAMPLES / chunking::FRAME_SAMPLES;
+    let (speech, tail) = energies.split_at(energies.len() - trailing_frames);
+    speech.iter().any(|&energy| energy >= threshold)
+        && tail.iter().all(|&energy| energy < threshold)
+}
+
+fn ms_to_samples(ms: i64) -> usize {
+    ms.max(0) as usize * SAMPLE_RATE / 1000
+}
@@ -1,18 +1,77 @@
+mod apple_speech;
+mod benchmark;
+mod chunking;
+mod confidence;
+mod coreml;
+mod decoder;
+mod dictation;
 mod error;
+pub(crate) mod ggml;
+mod grammar;
+mod hotwords;
+mod jobs;
+mod live;
 mod model_manager;
-
+mod ner;
+mod output_format;
+mod postprocess;
+mod preprocess;
+mod priority;
+mod profanity;
+mod punctuation;
+mod queue;
+mod redaction;
+mod replacements;
+mod stats;
+mod vocabulary;
+mod vosk;
+mod whisper_cpp;
+mod whisper_server;
+mod windows_speech;
+
+pub use apple_speech::transcribe_audio_apple;
+pub use benchmark::benchmark_models;
+use coreml::CoreMlEncoderStatus;
+use crate::models::cache::record_model_use;
 use error::TranscriptionError;
+use grammar::{ConstrainedTranscript, Grammar};
+pub use jobs::JobRegistry;
+pub use live::{start_live_transcription, stop_live_transcription, LiveTranscription};
+pub use model_manager::{EngineKind, LoadedModel};
 pub use model_manager::ModelManager;
+pub use ner::NerModels;
+use output_format::OutputFormat;
+use postprocess::PostProcessingOptions;
+use preprocess::PreprocessingOptions;
+use stats::{InferenceTimings, MemoryMonitor, TranscriptionStats};
+pub use queue::{
+    cancel_job, enqueue_transcription, list_jobs, retry_failed_jobs, TranscriptionQueue,
+};
+use replacements::ReplacementRule;
+pub use punctuation::PunctuationModels;
+pub use replacements::Replacements;
+pub use vocabulary::Vocabulary;
+pub use vosk::{is_vosk_model, transcribe_audio_vosk, VoskModels};
+pub use whisper_cpp::ComputeBackends;
+pub use whisper_server::{
+    get_whisper_server_status, start_whisper_server, stop_whisper_server,
+    transcribe_audio_whisper_server, whisper_server_port, whisper_server_status,
+    WhisperServerStatus, WHISPER_SERVER_ID,
+};
+pub use windows_speech::transcribe_audio_windows;
+use whisper_cpp::{
+    ComputeDevice, DecodingOptions, HallucinationFilter, LanguageProbability, PerformanceOptions,
+    Segment, Task, TranscriptionJob, WhisperOptions,
+};
 use std::path::PathBuf;
+use std::time::Instant;
+use tauri::Manager;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 use std::io::Write;
 use transcribe_rs::{
     TranscriptionEngine,
-    engines::{
-        whisper::{WhisperEngine, WhisperInferenceParams},
-        parakeet::{ParakeetInferenceParams, TimestampGranularity},
-    },
+    engines::parakeet::{ParakeetInferenceParams, TimestampGranularity},
 };
 
 #[cfg(target_os = "windows")]
@@ -131,7 +190,15 @@ fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError
 
     println!("[Rust Audio Conversion] Mono samples: {}", mono_samples.len());
 
-    // Step 3: Resample to 16kHz (if needed)
+    encode_whisper_wav(mono_samples, sample_rate)
+}
+
+/// Resample mono f32 audio to 16kHz and encode it as 16-bit PCM WAV
+///
+/// Shared by the WAV and compressed-format decoders so every pure Rust path
+/// produces the same output as the FFmpeg fallback.
+fn encode_whisper_wav(mono_samples: Vec<f32>, sample_rate: u32) -> Result<Vec<u8>, TranscriptionError> {
+    // Step 1: Resample to 16kHz (if needed)
     let resampled: Vec<f32> = if sample_rate != 16000 {
         println!("[Rust Audio Conversion] Resampling from {} Hz to 16000 Hz", sample_rate);
 
@@ -218,7 +285,7 @@ fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError
         mono_samples
     };
 
-    // Step 4: Convert f32 samples to 16-bit PCM
+    // Step 2: Convert f32 samples to 16-bit PCM
     println!("[Rust Audio Conversion] Converting {} f32 samples to 16-bit PCM", resampled.len());
     let pcm_samples: Vec<i16> = resampled
         .iter()
@@ -231,7 +298,7 @@ fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError
 
     println!("[Rust Audio Conversion] Converted to {} PCM samples", pcm_samples.len());
 
-    // Step 5: Write output WAV to memory buffer
+    // Step 3: Write output WAV to memory buffer
     let mut cursor = std::io::Cursor::new(Vec::new());
     {
         let spec = hound::WavSpec {
@@ -275,7 +342,7 @@ fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError
 /// - Channels: Mono (1 channel)
 /// - Format: 16-bit PCM WAV
 ///
-/// This function uses a three-tier conversion strategy:
+/// This function uses a four-tier conversion strategy:
 ///
 /// **Tier 1: Format Check (Fast Path)**
 /// - Checks if audio is already in the correct format
@@ -288,15 +355,19 @@ fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError
 /// - Uses high-quality resampling (SincFixedIn) for sample rate conversion
 /// - Works without FFmpeg installed, making it portable and reliable
 ///
-/// **Tier 3: FFmpeg Conversion (Last Resort)**
-/// - Falls back to FFmpeg for complex formats (MP3, M4A, OGG, etc.)
+/// **Tier 3: Native Decoding of Compressed Formats**
+/// - Decodes MP3, M4A/AAC, ALAC, OGG/Vorbis, and FLAC in Rust with symphonia
+/// - Lets users drop arbitrary audio files on the app without installing FFmpeg
+///
+/// **Tier 4: FFmpeg Conversion (Last Resort)**
+/// - Falls back to FFmpeg for anything symphonia can't decode (Opus, WMA, video containers, etc.)
 /// - Provides comprehensive format support but requires FFmpeg installation
 /// - Returns `FfmpegNotFoundError` if FFmpeg is not available
 ///
 /// This approach ensures maximum compatibility: users without FFmpeg can still
-/// transcribe most recordings, while complex formats are handled when FFmpeg is available.
+/// transcribe common audio files, while exotic formats are handled when FFmpeg is available.
 fn convert_audio_for_whisper(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError> {
-    println!("[Audio Conversion] Starting 3-tier conversion strategy for {} bytes", audio_data.len());
+    println!("[Audio Conversion] Starting 4-tier conversion strategy for {} bytes", audio_data.len());
 
     // Tier 1: Skip conversion if already in correct format (fast path)
     if is_valid_wav_format(&audio_data) {
@@ -315,11 +386,22 @@ fn convert_audio_for_whisper(audio_data: Vec<u8>) -> Result<Vec<u8>, Transcripti
         }
         Err(e) => {
             // Log the error but continue to FFmpeg fallback
-            eprintln!("[Audio Conversion] Tier 2: Pure Rust audio conversion failed: {}, falling back to Tier 3 (FFmpeg)", e);
+            eprintln!("[Audio Conversion] Tier 2: Pure Rust audio conversion failed: {}, trying Tier 3 (native decoding)", e);
         }
     }
 
-    // Tier 3: Fall back to FFmpeg for complex formats (MP3, M4A, OGG, etc.)
+    // Tier 3: Decode compressed formats (MP3, M4A, OGG, FLAC) natively
+    match decoder::decode_to_mono(audio_data.clone()) {
+        Ok((samples, sample_rate)) => {
+            println!("[Audio Conversion] Tier 3: Native decoding succeeded");
+            return encode_whisper_wav(samples, sample_rate);
+        }
+        Err(e) => {
+            eprintln!("[Audio Conversion] Tier 3: Native decoding failed: {}, falling back to Tier 4 (FFmpeg)", e);
+        }
+    }
+
+    // Tier 4: Fall back to FFmpeg for formats symphonia can't decode
     // Create temp files for conversion
     let mut input_file = tempfile::Builder::new()
         .suffix(".audio")
@@ -420,72 +502,594 @@ fn extract_samples_from_wav(wav_data: Vec<u8>) -> Result<Vec<f32>, Transcription
     Ok(samples)
 }
 
+/// Transcribe audio with a local whisper.cpp model
+///
+/// When `job_id` is provided, progress is reported through
+/// `transcription-progress` events carrying that ID so the frontend can show
+/// percent complete and an ETA for long files, and the job can be stopped
+/// early with `cancel_transcription`.
+///
+/// `output_format` selects plain text (default), a JSON segment list, or SRT/VTT
+/// subtitles rendered from the segment timestamps. `"verbose_json"` returns the
+/// text and segments together with performance stats (real-time factor, model
+/// load, encode and decode times, peak memory) for comparing models and hardware.
+///
+/// `task: "translate"` makes whisper.cpp output English regardless of the
+/// spoken language, avoiding a second translation pass.
+///
+/// `initial_prompt` is appended to the saved vocabulary (see `set_vocabulary`)
+/// and passed to whisper.cpp so names and jargon are spelled correctly.
+///
+/// `decoding` exposes the temperature schedule, beam size, best_of, entropy
+/// threshold, and no-context flag for users tuning accuracy against speed.
+/// On long noisy recordings that get stuck repeating a phrase, set
+/// `decoding.noContext` to true and raise `decoding.noSpeechThreshold`; the
+/// token suppression flags can be turned off to keep "[music]"-style
+/// annotations. `decoding.hotwords` biases the decoder towards recurring
+/// names and terms for the whole recording.
+///
+/// `performance.parallelChunks` splits long recordings at pauses and decodes
+/// that many chunks concurrently, trading memory for wall-clock time.
+/// `performance.threads` caps the CPU threads whisper.cpp uses, and
+/// `performance.priority` ("low" or "idle") lowers its scheduling priority so
+/// the machine stays responsive during background jobs.
+///
+/// `preprocessing.normalizeLoudness` applies EBU R128 normalization first,
+/// which helps with very quiet recordings.
+///
+/// `preprocessing.removeSilence` shortens long pauses before decoding, which
+/// speeds up silence-heavy recordings, and `preprocessing.speed` time-compresses
+/// the audio by up to 2x at a small accuracy cost. Segment timestamps still
+/// refer to the original audio.
+///
+/// `hallucination_filter` controls the removal of segments whisper.cpp invents
+/// over silence ("Thank you for watching", looping phrases); it is on by
+/// default and accepts extra blocklisted phrases.
+///
+/// `device` pins the model to the CPU or a specific GPU (see
+/// `get_compute_backends`); changing it reloads the model.
 #[tauri::command]
 pub async fn transcribe_audio_whisper(
     audio_data: Vec<u8>,
     model_path: String,
     language: Option<String>,
-    model_manager: tauri::State<'_, ModelManager>,
+    task: Option<Task>,
+    initial_prompt: Option<String>,
+    decoding: Option<DecodingOptions>,
+    performance: Option<PerformanceOptions>,
+    preprocessing: Option<PreprocessingOptions>,
+    hallucination_filter: Option<HallucinationFilter>,
+    post_processing: Option<PostProcessingOptions>,
+    device: Option<ComputeDevice>,
+    job_id: Option<String>,
+    output_format: Option<OutputFormat>,
+    app_handle: tauri::AppHandle,
 ) -> Result<String, TranscriptionError> {
-    // Convert audio to 16kHz mono format that whisper requires
-    let wav_data = convert_audio_for_whisper(audio_data)?;
+    let options = TranscribeOptions {
+        language,
+        task,
+        initial_prompt,
+        decoding,
+        performance,
+        preprocessing,
+        hallucination_filter,
+        post_processing,
+        device,
+        job_id,
+        output_format,
+    };
+    transcribe_audio(audio_data, model_path, options, app_handle).await
+}
 
-    // Extract samples from WAV
-    let samples = extract_samples_from_wav(wav_data)?;
+/// Transcribe raw PCM with a local whisper.cpp model, skipping the file
+/// decode and conversion step
+///
+/// `samples` must already be 16 kHz mono `f32` in the range [-1.0, 1.0], which
+/// is what the browser recorder and VAD produce, so audio can go straight from
+/// the webview to whisper.cpp without being encoded to WAV and written to disk.
+/// All other options behave as in `transcribe_audio_whisper`.
+#[tauri::command]
+pub async fn transcribe_pcm_whisper(
+    samples: Vec<f32>,
+    model_path: String,
+    language: Option<String>,
+    task: Option<Task>,
+    initial_prompt: Option<String>,
+    decoding: Option<DecodingOptions>,
+    performance: Option<PerformanceOptions>,
+    preprocessing: Option<PreprocessingOptions>,
+    hallucination_filter: Option<HallucinationFilter>,
+    post_processing: Option<PostProcessingOptions>,
+    device: Option<ComputeDevice>,
+    job_id: Option<String>,
+    output_format: Option<OutputFormat>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, TranscriptionError> {
+    let options = TranscribeOptions {
+        language,
+        task,
+        initial_prompt,
+        decoding,
+        performance,
+        preprocessing,
+        hallucination_filter,
+        post_processing,
+        device,
+        job_id,
+        output_format,
+    };
+    transcribe_samples(samples, model_path, options, app_handle).await
+}
 
+/// Options of a whisper.cpp transcription, as taken by
+/// `transcribe_audio_whisper`; anything left `None` uses its default
+#[derive(Debug, Clone, Default)]
+pub struct TranscribeOptions {
+    pub language: Option<String>,
+    pub task: Option<Task>,
+    pub initial_prompt: Option<String>,
+    pub decoding: Option<DecodingOptions>,
+    pub performance: Option<PerformanceOptions>,
+    pub preprocessing: Option<PreprocessingOptions>,
+    pub hallucination_filter: Option<HallucinationFilter>,
+    pub post_processing: Option<PostProcessingOptions>,
+    pub device: Option<ComputeDevice>,
+    /// Reports progress and allows cancelling under this ID
+    pub job_id: Option<String>,
+    pub output_format: Option<OutputFormat>,
+}
+
+/// Decode an audio file and transcribe it with a local whisper.cpp model
+pub async fn transcribe_audio(
+    audio_data: Vec<u8>,
+    model_path: String,
+    options: TranscribeOptions,
+    app_handle: tauri::AppHandle,
+) -> Result<String, TranscriptionError> {
+    let samples = tokio::task::spawn_blocking(move || {
+        // Convert audio to 16kHz mono format that whisper requires
+        let wav_data = convert_audio_for_whisper(audio_data)?;
+        extract_samples_from_wav(wav_data)
+    })
+    .await
+    .map_err(|e| TranscriptionError::TranscriptionError {
+        message: format!("Task join error: {}", e),
+    })??;
+
+    transcribe_samples(samples, model_path, options, app_handle).await
+}
+
+/// Transcribe 16 kHz mono samples with a local whisper.cpp model and render
+/// the result in the requested output format
+///
+/// whisper.cpp runs on a blocking thread so it doesn't stall the async runtime.
+pub async fn transcribe_samples(
+    samples: Vec<f32>,
+    model_path: String,
+    options: TranscribeOptions,
+    app_handle: tauri::AppHandle,
+) -> Result<String, TranscriptionError> {
     // Return early if audio is empty
     if samples.is_empty() {
         return Ok(String::new());
     }
 
+    let whisper = WhisperOptions {
+        decoding: options.decoding.unwrap_or_default(),
+        performance: options.performance.unwrap_or_default(),
+        preprocessing: options.preprocessing.unwrap_or_default(),
+        hallucination_filter: options.hallucination_filter.unwrap_or_default(),
+        post_processing: options.post_processing.unwrap_or_default(),
+        ..whisper_options(
+            options.language,
+            options.task,
+            options.initial_prompt,
+            &app_handle.state::<Vocabulary>(),
+            &app_handle,
+        )
+    };
+    let job_registry = app_handle.state::<JobRegistry>();
+    let job = options.job_id.as_ref().map(|id| TranscriptionJob {
+        app: app_handle.clone(),
+        id: id.clone(),
+        cancelled: job_registry.register(id),
+    });
+    let registered = job.as_ref().map(|job| (job.id.clone(), job.cancelled.clone()));
+
+    let device = options.device.unwrap_or_default();
+    let task_app = app_handle.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        run_whisper(
+            &samples,
+            &model_path,
+            device,
+            &whisper,
+            job,
+            &task_app.state::<ModelManager>(),
+            &task_app,
+        )
+    })
+    .await
+    .unwrap_or_else(|e| {
+        Err(TranscriptionError::TranscriptionError {
+            message: format!("Task join error: {}", e),
+        })
+    });
+
+    if let Some((id, cancelled)) = &registered {
+        job_registry.finish(id, cancelled);
+    }
+
+    let (segments, stats) = result?;
+    Ok(output_format::render(
+        &segments,
+        &stats,
+        options.output_format.unwrap_or_default(),
+    ))
+}
+
+/// Build per-request whisper.cpp options, merging in the saved vocabulary.
+/// Tuning options are left at their defaults for callers to fill in.
+fn whisper_options(
+    language: Option<String>,
+    task: Option<Task>,
+    initial_prompt: Option<String>,
+    vocabulary: &Vocabulary,
+    app_handle: &tauri::AppHandle,
+) -> WhisperOptions {
+    // A broken vocabulary file shouldn't block transcription
+    let saved_vocabulary = vocabulary.get(app_handle).unwrap_or_else(|e| {
+        eprintln!("[Transcription] Ignoring saved vocabulary: {}", e);
+        Vec::new()
+    });
+
+    WhisperOptions {
+        language,
+        task: task.unwrap_or_default(),
+        initial_prompt: vocabulary::build_prompt(&saved_vocabulary, initial_prompt.as_deref()),
+        ..Default::default()
+    }
+}
+
+/// Load (or reuse) the whisper.cpp model and transcribe 16 kHz mono samples
+///
+/// Preprocessing runs first; returned segment timestamps always refer to the
+/// original audio. Timing and peak memory of the run are returned alongside.
+fn run_whisper(
+    samples: &[f32],
+    model_path: &str,
+    device: ComputeDevice,
+    options: &WhisperOptions,
+    job: Option<TranscriptionJob>,
+    model_manager: &ModelManager,
+    app_handle: &tauri::AppHandle,
+) -> Result<(Vec<Segment>, TranscriptionStats), TranscriptionError> {
+    let memory = MemoryMonitor::start();
+    let already_loaded = model_manager.loaded_model().map_or(false, |loaded| {
+        loaded.engine == EngineKind::Whisper && loaded.model_path == model_path
+    });
+
     // Get or load the model using the persistent model manager
+    let load_started = Instant::now();
     let engine_arc = model_manager
-        .get_or_load_whisper(PathBuf::from(&model_path))
+        .get_or_load_whisper(PathBuf::from(model_path), device)
         .map_err(|e| TranscriptionError::ModelLoadError { message: e })?;
+    let model_load = (!already_loaded).then(|| load_started.elapsed());
+    record_model_use(app_handle, model_path);
+
+    // Run transcription with the persistent context
+    let mut engine_guard = engine_arc.lock().unwrap();
+    let engine = engine_guard.as_mut().ok_or_else(|| {
+        TranscriptionError::ModelLoadError {
+            message: "Model failed to load".to_string(),
+        }
+    })?;
 
-    // Configure inference parameters
-    let mut params = WhisperInferenceParams::default();
-    params.language = language;
-    params.print_special = false;
-    params.print_progress = false;
-    params.print_realtime = false;
-    params.print_timestamps = false;
-    params.suppress_blank = true;
-    params.suppress_non_speech_tokens = true;
-    params.no_speech_thold = 0.2;
+    // Extract the whisper.cpp context from the enum
+    let context = match engine {
+        model_manager::Engine::Whisper(c) => c,
+        _ => return Err(TranscriptionError::ModelLoadError {
+            message: "Expected Whisper engine but got different type".to_string(),
+        }),
+    };
 
-    // Run transcription with the persistent engine
-    let result = {
-        let mut engine_guard = engine_arc.lock().unwrap();
-        let engine = engine_guard.as_mut().ok_or_else(|| {
-            TranscriptionError::ModelLoadError {
-                message: "Model failed to load".to_string(),
-            }
-        })?;
+    let processing_started = Instant::now();
+    let timings = InferenceTimings::default();
+    // 16 samples per millisecond at 16 kHz
+    let audio_duration_ms = samples.len() as u64 / 16;
 
-        // Extract the WhisperEngine from the enum
-        let whisper_engine = match engine {
-            model_manager::Engine::Whisper(e) => e,
-            _ => return Err(TranscriptionError::ModelLoadError {
+    let (samples, time_map) = preprocess::apply(samples, &options.preprocessing);
+    let segments = if samples.is_empty() {
+        Vec::new()
+    } else {
+        let decoded = whisper_cpp::transcribe(context, &samples, options, job, &timings);
+        model_manager.touch();
+        time_map.restore(decoded?)
+    };
+    let segments = postprocess::process_segments(
+        app_handle,
+        segments,
+        &options.post_processing,
+        options.language.as_deref(),
+    );
+
+    let stats = TranscriptionStats::new(
+        audio_duration_ms,
+        processing_started.elapsed(),
+        model_load,
+        &timings,
+        memory.finish(),
+    );
+    println!(
+        "[Whisper] {}ms of audio in {}ms (RTF {:.2}, encode {}ms, decode {}ms)",
+        stats.audio_duration_ms,
+        stats.processing_ms,
+        stats.real_time_factor,
+        stats.encode_ms,
+        stats.decode_ms
+    );
+    Ok((segments, stats))
+}
+
+/// Detect the spoken language of an audio file with a local whisper.cpp model
+///
+/// Only the first 30 seconds are analysed, so this is cheap enough to run
+/// before committing to a long transcription.
+#[tauri::command]
+pub async fn detect_language(
+    audio_path: String,
+    model_path: String,
+    device: Option<ComputeDevice>,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<Vec<LanguageProbability>, TranscriptionError> {
+    let audio_data = std::fs::read(&audio_path).map_err(|e| TranscriptionError::AudioReadError {
+        message: format!("Failed to read {}: {}", audio_path, e),
+    })?;
+
+    let model_manager = model_manager.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let wav_data = convert_audio_for_whisper(audio_data)?;
+        let samples = extract_samples_from_wav(wav_data)?;
+
+        if samples.is_empty() {
+            return Err(TranscriptionError::AudioReadError {
+                message: "Audio file contains no samples".to_string(),
+            });
+        }
+
+        let engine_arc = model_manager
+            .get_or_load_whisper(PathBuf::from(&model_path), device.unwrap_or_default())
+            .map_err(|e| TranscriptionError::ModelLoadError { message: e })?;
+
+        let engine_guard = engine_arc.lock().unwrap();
+        match engine_guard.as_ref() {
+            Some(model_manager::Engine::Whisper(context)) => {
+                whisper_cpp::detect_language(context, &samples)
+            }
+            _ => Err(TranscriptionError::ModelLoadError {
                 message: "Expected Whisper engine but got different type".to_string(),
             }),
+        }
+    })
+    .await
+    .map_err(|e| TranscriptionError::TranscriptionError {
+        message: format!("Task join error: {}", e),
+    })?
+}
+
+/// Transcribe a short utterance, then decode it again constrained to a phrase list
+///
+/// The grammar pass only ever produces one of `grammar.phrases` (unless its
+/// penalty is too low to hold the decoder), so voice-control phrases can be
+/// recognised reliably; the unconstrained transcript is returned alongside
+/// for when nothing matches.
+#[tauri::command]
+pub async fn transcribe_audio_whisper_grammar(
+    audio_data: Vec<u8>,
+    model_path: String,
+    grammar: Grammar,
+    language: Option<String>,
+    device: Option<ComputeDevice>,
+    model_manager: tauri::State<'_, ModelManager>,
+    vocabulary: tauri::State<'_, Vocabulary>,
+    app_handle: tauri::AppHandle,
+) -> Result<ConstrainedTranscript, TranscriptionError> {
+    let options = whisper_options(language.clone(), None, None, &vocabulary, &app_handle);
+    let model_manager = model_manager.inner().clone();
+    tokio::task::spawn_blocking(move || {
+        let wav_data = convert_audio_for_whisper(audio_data)?;
+        let samples = extract_samples_from_wav(wav_data)?;
+
+        if samples.is_empty() {
+            return Err(TranscriptionError::AudioReadError {
+                message: "Audio contains no samples".to_string(),
+            });
+        }
+
+        let device = device.unwrap_or_default();
+        let (segments, stats) = run_whisper(
+            &samples,
+            &model_path,
+            device,
+            &options,
+            None,
+            &model_manager,
+            &app_handle,
+        )?;
+        let transcript = output_format::render(&segments, &stats, OutputFormat::Plain);
+
+        let engine_arc = model_manager
+            .get_or_load_whisper(PathBuf::from(&model_path), device)
+            .map_err(|e| TranscriptionError::ModelLoadError { message: e })?;
+
+        let engine_guard = engine_arc.lock().unwrap();
+        let constrained = match engine_guard.as_ref() {
+            Some(model_manager::Engine::Whisper(context)) => {
+                grammar::decode(context, &samples, language.as_deref(), &grammar)?
+            }
+            _ => {
+                return Err(TranscriptionError::ModelLoadError {
+                    message: "Expected Whisper engine but got different type".to_string(),
+                })
+            }
         };
 
-        whisper_engine
-            .transcribe_samples(samples, Some(params))
-            .map_err(|e| TranscriptionError::TranscriptionError {
-                message: e.to_string(),
-            })?
-    };
+        Ok(ConstrainedTranscript {
+            transcript,
+            constrained,
+        })
+    })
+    .await
+    .map_err(|e| TranscriptionError::TranscriptionError {
+        message: format!("Task join error: {}", e),
+    })?
+}
 
-    Ok(result.text.trim().to_string())
+/// Load a model ahead of time so the first transcription doesn't pay the load cost
+#[tauri::command]
+pub async fn load_model(
+    engine: EngineKind,
+    model_path: String,
+    device: Option<ComputeDevice>,
+    model_manager: tauri::State<'_, ModelManager>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), TranscriptionError> {
+    record_model_use(&app_handle, &model_path);
+
+    let model_manager = model_manager.inner().clone();
+    let model_path = PathBuf::from(model_path);
+    let loaded = tokio::task::spawn_blocking(move || match engine {
+        EngineKind::Whisper => {
+            model_manager.get_or_load_whisper(model_path, device.unwrap_or_default())
+        }
+        EngineKind::Parakeet => model_manager.get_or_load_parakeet(model_path),
+    })
+    .await
+    .map_err(|e| TranscriptionError::ModelLoadError {
+        message: format!("Task join error: {}", e),
+    })?;
+
+    loaded
+        .map(|_| ())
+        .map_err(|e| TranscriptionError::ModelLoadError { message: e })
+}
+
+/// Free the resident model immediately instead of waiting for the idle timeout
+///
+/// Waits for a running transcription to release the model first, off the
+/// main thread.
+#[tauri::command]
+pub async fn unload_model(model_manager: tauri::State<'_, ModelManager>) -> Result<(), String> {
+    let model_manager = model_manager.inner().clone();
+    tokio::task::spawn_blocking(move || model_manager.unload_model())
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// Get the model currently held in memory, if any
+///
+/// Waits for a running transcription to release the model, off the main
+/// thread.
+#[tauri::command]
+pub async fn get_loaded_model(
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<Option<LoadedModel>, String> {
+    let model_manager = model_manager.inner().clone();
+    tokio::task::spawn_blocking(move || model_manager.loaded_model())
+        .await
+        .map_err(|e| format!("Task join error: {}", e))
+}
+
+/// Set how long an unused model stays loaded; `None` keeps it loaded indefinitely
+#[tauri::command]
+pub fn set_model_idle_timeout(
+    idle_timeout_seconds: Option<u64>,
+    model_manager: tauri::State<'_, ModelManager>,
+) {
+    model_manager.set_idle_timeout(idle_timeout_seconds.map(std::time::Duration::from_secs));
+}
+
+/// Report which compute backends (Metal, CUDA, Vulkan, CPU) whisper.cpp was built with
+#[tauri::command]
+pub fn get_compute_backends() -> ComputeBackends {
+    whisper_cpp::compute_backends()
+}
+
+/// Check whether a CoreML encoder is available for a GGML model
+#[tauri::command]
+pub fn get_coreml_encoder_status(model_path: String) -> CoreMlEncoderStatus {
+    coreml::status(&PathBuf::from(model_path))
+}
+
+/// Download and unpack the CoreML encoder for a GGML model on Apple Silicon
+///
+/// The currently loaded model is unloaded so the encoder is used on the next
+/// transcription.
+#[tauri::command]
+pub async fn download_coreml_encoder(
+    model_path: String,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<CoreMlEncoderStatus, String> {
+    let status = coreml::download_encoder(&PathBuf::from(model_path)).await?;
+    model_manager.unload_model();
+    Ok(status)
+}
+
+/// Get the vocabulary that is prepended to every whisper.cpp prompt
+#[tauri::command]
+pub fn get_vocabulary(
+    app_handle: tauri::AppHandle,
+    vocabulary: tauri::State<'_, Vocabulary>,
+) -> Result<Vec<String>, String> {
+    vocabulary.get(&app_handle)
+}
+
+/// Replace the saved vocabulary, returning the cleaned list that was stored
+#[tauri::command]
+pub fn set_vocabulary(
+    words: Vec<String>,
+    app_handle: tauri::AppHandle,
+    vocabulary: tauri::State<'_, Vocabulary>,
+) -> Result<Vec<String>, String> {
+    vocabulary.set(&app_handle, words)
+}
+
+/// Get the find/replace rules applied to every transcript
+#[tauri::command]
+pub fn get_replacement_rules(
+    app_handle: tauri::AppHandle,
+    replacements: tauri::State<'_, Replacements>,
+) -> Result<Vec<ReplacementRule>, String> {
+    replacements.get(&app_handle)
+}
+
+/// Replace the saved find/replace rules, returning the rules that were stored
+///
+/// Fails without saving anything if a regex rule doesn't compile.
+#[tauri::command]
+pub fn set_replacement_rules(
+    rules: Vec<ReplacementRule>,
+    app_handle: tauri::AppHandle,
+    replacements: tauri::State<'_, Replacements>,
+) -> Result<Vec<ReplacementRule>, String> {
+    replacements.set(&app_handle, rules)
+}
+
+/// Cancel an in-flight whisper.cpp transcription started with a `job_id`
+///
+/// Returns false if no job with that ID is currently running.
+#[tauri::command]
+pub fn cancel_transcription(job_id: String, job_registry: tauri::State<'_, JobRegistry>) -> bool {
+    job_registry.cancel(&job_id)
 }
 
 #[tauri::command]
 pub async fn transcribe_audio_parakeet(
     audio_data: Vec<u8>,
     model_path: String,
+    post_processing: Option<PostProcessingOptions>,
     model_manager: tauri::State<'_, ModelManager>,
+    app_handle: tauri::AppHandle,
 ) -> Result<String, TranscriptionError> {
     // Convert audio to 16kHz mono format
     let wav_data = convert_audio_for_whisper(audio_data)?;
@@ -502,6 +1106,7 @@ pub async fn transcribe_audio_parakeet(
     let engine_arc = model_manager
         .get_or_load_parakeet(PathBuf::from(&model_path))
         .map_err(|e| TranscriptionError::ModelLoadError { message: e })?;
+    record_model_use(&app_handle, &model_path);
 
     let params = ParakeetInferenceParams {
         timestamp_granularity: TimestampGranularity::Segment,
@@ -525,12 +1130,18 @@ pub async fn transcribe_audio_parakeet(
             }),
         };
 
-        parakeet_engine
-            .transcribe_samples(samples, Some(params))
-            .map_err(|e| TranscriptionError::TranscriptionError {
-                message: e.to_string(),
-            })?
+        parakeet_engine.transcribe_samples(samples, Some(params))
     };
+    model_manager.touch();
+    let result = result.map_err(|e| TranscriptionError::TranscriptionError {
+        message: e.to_string(),
+    })?;
 
-    Ok(result.text.trim().to_string())
+    // Parakeet TDT only transcribes English
+    Ok(postprocess::process_text(
+        &app_handle,
+        &result.text,
+        &post_processing.unwrap_or_default(),
+        Some("en"),
+    ))
 }
\ No newline at end of file
@@ -1,30 +1,65 @@
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, SystemTime};
 use transcribe_rs::engines::parakeet::{ParakeetEngine, ParakeetModelParams};
-use transcribe_rs::engines::whisper::WhisperEngine;
 use transcribe_rs::TranscriptionEngine;
+use whisper_rs::WhisperContext;
+
+use super::whisper_cpp::{self, ComputeDevice};
 
 /// Engine type for managing different transcription engines
 pub enum Engine {
     Parakeet(ParakeetEngine),
-    Whisper(WhisperEngine),
+    Whisper(WhisperContext),
+}
+
+/// Which engine a model should be loaded with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EngineKind {
+    Parakeet,
+    Whisper,
 }
 
+/// The model currently resident in memory - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedModel {
+    pub engine: EngineKind,
+    pub model_path: String,
+}
+
+/// How often the idle watcher checks whether the model should be unloaded
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
 impl Engine {
+    fn kind(&self) -> EngineKind {
+        match self {
+            Engine::Parakeet(_) => EngineKind::Parakeet,
+            Engine::Whisper(_) => EngineKind::Whisper,
+        }
+    }
+
     fn unload(&mut self) {
         match self {
             Engine::Parakeet(e) => e.unload_model(),
-            Engine::Whisper(e) => e.unload_model(),
+            // Dropping the context frees the model, nothing to do up front
+            Engine::Whisper(_) => {}
         }
     }
 }
 
+/// Keeps a single model resident across transcriptions so short dictations
+/// don't pay the load cost every time, unloading it after an idle period.
+#[derive(Clone)]
 pub struct ModelManager {
     engine: Arc<Mutex<Option<Engine>>>,
     current_model_path: Arc<Mutex<Option<PathBuf>>>,
+    current_device: Arc<Mutex<ComputeDevice>>,
     last_activity: Arc<Mutex<SystemTime>>,
-    idle_timeout: Duration,
+    /// `None` keeps the model loaded until explicitly unloaded
+    idle_timeout: Arc<Mutex<Option<Duration>>>,
 }
 
 impl ModelManager {
@@ -32,11 +67,58 @@ impl ModelManager {
         Self {
             engine: Arc::new(Mutex::new(None)),
             current_model_path: Arc::new(Mutex::new(None)),
+            current_device: Arc::new(Mutex::new(ComputeDevice::default())),
             last_activity: Arc::new(Mutex::new(SystemTime::now())),
-            idle_timeout: Duration::from_secs(5 * 60), // 5 minutes default
+            idle_timeout: Arc::new(Mutex::new(Some(Duration::from_secs(5 * 60)))), // 5 minutes default
         }
     }
 
+    /// Start a background thread that unloads the model once it has been idle
+    /// for longer than the configured timeout
+    pub fn spawn_idle_watcher(&self) {
+        let manager = self.clone();
+        std::thread::spawn(move || loop {
+            std::thread::sleep(IDLE_CHECK_INTERVAL);
+            manager.unload_if_idle();
+        });
+    }
+
+    /// Count the model as just used, so the idle timeout runs from the end
+    /// of a long job rather than its start
+    pub fn touch(&self) {
+        *self.last_activity.lock().unwrap() = SystemTime::now();
+    }
+
+    pub fn set_idle_timeout(&self, idle_timeout: Option<Duration>) {
+        *self.idle_timeout.lock().unwrap() = idle_timeout;
+    }
+
+    pub fn loaded_model(&self) -> Option<LoadedModel> {
+        let engine_guard = self.engine.lock().unwrap();
+        let path_guard = self.current_model_path.lock().unwrap();
+        match (&*engine_guard, &*path_guard) {
+            (Some(engine), Some(path)) => Some(LoadedModel {
+                engine: engine.kind(),
+                model_path: path.to_string_lossy().to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// `loaded_model` without waiting, or `None` while a transcription holds
+    /// the model
+    pub fn try_loaded_model(&self) -> Option<Option<LoadedModel>> {
+        let engine_guard = self.engine.try_lock().ok()?;
+        let path_guard = self.current_model_path.lock().unwrap();
+        Some(match (&*engine_guard, &*path_guard) {
+            (Some(engine), Some(path)) => Some(LoadedModel {
+                engine: engine.kind(),
+                model_path: path.to_string_lossy().to_string(),
+            }),
+            _ => None,
+        })
+    }
+
     pub fn get_or_load_parakeet(&self, model_path: PathBuf) -> Result<Arc<Mutex<Option<Engine>>>, String> {
         let mut engine_guard = self.engine.lock().unwrap();
         let mut current_path_guard = self.current_model_path.lock().unwrap();
@@ -77,15 +159,20 @@ impl ModelManager {
         Ok(self.engine.clone())
     }
 
-    pub fn get_or_load_whisper(&self, model_path: PathBuf) -> Result<Arc<Mutex<Option<Engine>>>, String> {
+    pub fn get_or_load_whisper(
+        &self,
+        model_path: PathBuf,
+        device: ComputeDevice,
+    ) -> Result<Arc<Mutex<Option<Engine>>>, String> {
         let mut engine_guard = self.engine.lock().unwrap();
         let mut current_path_guard = self.current_model_path.lock().unwrap();
+        let mut current_device_guard = self.current_device.lock().unwrap();
 
         // Check if we need to load a new model
         let needs_load = match (&*engine_guard, &*current_path_guard) {
             (None, _) => true,
-            (Some(_), Some(path)) if path != &model_path => {
-                // Different model requested, unload current one
+            (Some(_), Some(path)) if path != &model_path || *current_device_guard != device => {
+                // Different model or device requested, unload current one
                 if let Some(mut engine) = engine_guard.take() {
                     engine.unload();
                 }
@@ -102,13 +189,11 @@ impl ModelManager {
         };
 
         if needs_load {
-            let mut engine = WhisperEngine::new();
-            engine
-                .load_model(&model_path)
-                .map_err(|e| format!("Failed to load Whisper model: {}", e))?;
+            let context = whisper_cpp::load_context(&model_path, device)?;
 
-            *engine_guard = Some(Engine::Whisper(engine));
+            *engine_guard = Some(Engine::Whisper(context));
             *current_path_guard = Some(model_path);
+            *current_device_guard = device;
         }
 
         // Update last activity
@@ -118,15 +203,23 @@ impl ModelManager {
     }
 
     pub fn unload_if_idle(&self) {
+        let Some(idle_timeout) = *self.idle_timeout.lock().unwrap() else {
+            return;
+        };
+
         let last_activity = *self.last_activity.lock().unwrap();
         let elapsed = SystemTime::now()
             .duration_since(last_activity)
             .unwrap_or(Duration::from_secs(0));
 
-        if elapsed > self.idle_timeout {
-            let mut engine_guard = self.engine.lock().unwrap();
+        if elapsed > idle_timeout {
+            // A transcription holding the engine is activity, so skip this round
+            let Ok(mut engine_guard) = self.engine.try_lock() else {
+                return;
+            };
             if let Some(mut engine) = engine_guard.take() {
                 engine.unload();
+                println!("[ModelManager] Unloaded model after {:?} idle", elapsed);
             }
             *self.current_model_path.lock().unwrap() = None;
         }
@@ -0,0 +1,262 @@
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::Tensor;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// BERT's sequence limit, less room for [CLS] and [SEP]
+const MAX_PIECES: usize = 510;
+
+/// Words longer than this are mapped straight to [UNK], as in BERT
+const MAX_WORD_CHARS: usize = 100;
+
+/// A BERT token-classification model exported to ONNX, in a folder holding
+/// `model.onnx`, `vocab.txt` and the Hugging Face `config.json`
+struct NerModel {
+    session: Session,
+    vocabulary: HashMap<String, i64>,
+    lowercase: bool,
+    /// Label ids that mark a person, from `id2label` ("B-PER", "I-PER", ...)
+    person_labels: Vec<usize>,
+    cls: i64,
+    sep: i64,
+    unknown: i64,
+}
+
+impl NerModel {
+    fn load(model_dir: &Path) -> Result<Self, String> {
+        let vocabulary: HashMap<String, i64> = std::fs::read_to_string(model_dir.join("vocab.txt"))
+            .map_err(|e| format!("Failed to read NER vocabulary: {}", e))?
+            .lines()
+            .enumerate()
+            .map(|(id, token)| (token.to_string(), id as i64))
+            .collect();
+        let special = |token: &str| {
+            vocabulary
+                .get(token)
+                .copied()
+                .ok_or_else(|| format!("NER vocabulary has no {} token", token))
+        };
+        let (cls, sep, unknown) = (special("[CLS]")?, special("[SEP]")?, special("[UNK]")?);
+
+        let config: serde_json::Value = std::fs::read_to_string(model_dir.join("config.json"))
+            .map_err(|e| format!("Failed to read NER config: {}", e))
+            .and_then(|contents| {
+                serde_json::from_str(&contents)
+                    .map_err(|e| format!("Failed to parse NER config: {}", e))
+            })?;
+        let person_labels: Vec<usize> = config["id2label"]
+            .as_object()
+            .ok_or("NER config has no id2label")?
+            .iter()
+            .filter(|(_, label)| {
+                label.as_str().is_some_and(|label| {
+                    let entity = label.rsplit('-').next().unwrap_or(label);
+                    entity == "PER" || entity == "PERSON"
+                })
+            })
+            .filter_map(|(id, _)| id.parse().ok())
+            .collect();
+        if person_labels.is_empty() {
+            return Err("NER model has no person label".to_string());
+        }
+
+        // Uncased models say so in their tokenizer config
+        let lowercase = std::fs::read_to_string(model_dir.join("tokenizer_config.json"))
+            .ok()
+            .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+            .and_then(|config| config["do_lower_case"].as_bool())
+            .unwrap_or(false);
+
+        let session = Session::builder()
+            .and_then(|builder| builder.with_optimization_level(GraphOptimizationLevel::Level3))
+            .and_then(|builder| builder.with_intra_threads(1))
+            .and_then(|builder| builder.commit_from_file(model_dir.join("model.onnx")))
+            .map_err(|e| format!("Failed to load NER model: {}", e))?;
+
+        Ok(Self {
+            session,
+            vocabulary,
+            lowercase,
+            person_labels,
+            cls,
+            sep,
+            unknown,
+        })
+    }
+
+    /// Split a word into WordPiece ids, longest vocabulary match first
+    fn word_pieces(&self, word: &str) -> Vec<i64> {
+        let word = if self.lowercase {
+            word.to_lowercase()
+        } else {
+            word.to_string()
+        };
+        if word.chars().count() > MAX_WORD_CHARS {
+            return vec![self.unknown];
+        }
+
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        while start < word.len() {
+            let piece = word[start..]
+                .char_indices()
+                .map(|(offset, c)| start + offset + c.len_utf8())
+                .rev()
+                .find_map(|end| {
+                    let piece = &word[start..end];
+                    let key = if start == 0 {
+                        piece.to_string()
+                    } else {
+                        format!("##{}", piece)
+                    };
+                    self.vocabulary.get(&key).map(|&id| (id, end))
+                });
+            match piece {
+                Some((id, end)) => {
+                    pieces.push(id);
+                    start = end;
+                }
+                None => return vec![self.unknown],
+            }
+        }
+        pieces
+    }
+
+    /// Whether each word names a person, judged by its first word piece
+    fn classify(&mut self, words: &[&str]) -> Result<Vec<bool>, String> {
+        let to_error = |e: ort::Error| format!("NER model failed: {}", e);
+
+        let mut ids = vec![self.cls];
+        let mut first_piece = Vec::with_capacity(words.len());
+        for word in words {
+            first_piece.push(ids.len());
+            ids.extend(self.word_pieces(word));
+        }
+        ids.push(self.sep);
+
+        let length = ids.len();
+        let inputs: Vec<(&str, Tensor<i64>)> = vec![
+            ("input_ids", Tensor::from_array(([1usize, length], ids)).map_err(to_error)?),
+            (
+                "attention_mask",
+                Tensor::from_array(([1usize, length], vec![1i64; length])).map_err(to_error)?,
+            ),
+            (
+                "token_type_ids",
+                Tensor::from_array(([1usize, length], vec![0i64; length])).map_err(to_error)?,
+            ),
+        ];
+        // Not every export takes token type ids
+        let expected: Vec<String> = self.session.inputs.iter().map(|i| i.name.clone()).collect();
+        let inputs: Vec<(&str, Tensor<i64>)> = inputs
+            .into_iter()
+            .filter(|(name, _)| expected.iter().any(|expected| expected == name))
+            .collect();
+
+        let outputs = self.session.run(inputs).map_err(to_error)?;
+        let (shape, logits) = outputs[0].try_extract_tensor::<f32>().map_err(to_error)?;
+        let labels = shape.last().copied().unwrap_or(0).max(1) as usize;
+
+        Ok(first_piece
+            .into_iter()
+            .map(|piece| {
+                let scores = logits.get(piece * labels..(piece + 1) * labels).unwrap_or(&[]);
+                scores
+                    .iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .is_some_and(|(label, _)| self.person_labels.contains(&label))
+            })
+            .collect())
+    }
+}
+
+/// Split text the way BERT's basic tokenizer does: runs of letters and digits
+/// are words, and every other visible character stands alone
+fn basic_tokens(text: &str) -> Vec<Range<usize>> {
+    let mut tokens = Vec::new();
+    let mut word_start = None;
+    for (index, c) in text.char_indices() {
+        if c.is_alphanumeric() {
+            word_start.get_or_insert(index);
+            continue;
+        }
+        if let Some(start) = word_start.take() {
+            tokens.push(start..index);
+        }
+        if !c.is_whitespace() {
+            tokens.push(index..index + c.len_utf8());
+        }
+    }
+    if let Some(start) = word_start {
+        tokens.push(start..text.len());
+    }
+    tokens
+}
+
+/// The most recently used NER model, kept loaded between transcriptions
+pub struct NerModels {
+    loaded: Mutex<Option<(PathBuf, NerModel)>>,
+}
+
+impl NerModels {
+    pub fn new() -> Self {
+        Self {
+            loaded: Mutex::new(None),
+        }
+    }
+
+    /// Byte ranges of `text` that name people
+    ///
+    /// Adjacent name words are merged, so "Ada Lovelace" is one range.
+    pub fn find_names(&self, model_dir: &Path, text: &str) -> Result<Vec<Range<usize>>, String> {
+        let tokens = basic_tokens(text);
+        if tokens.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut loaded = self.loaded.lock().unwrap();
+        if loaded.as_ref().map_or(true, |(path, _)| path != model_dir) {
+            let model = NerModel::load(model_dir)?;
+            println!("[NER] Loaded model {:?}", model_dir);
+            *loaded = Some((model_dir.to_path_buf(), model));
+        }
+        let (_, model) = loaded.as_mut().unwrap();
+
+        // Split into windows that fit BERT's sequence limit
+        let mut is_name = Vec::with_capacity(tokens.len());
+        let mut window: Vec<&str> = Vec::new();
+        let mut window_pieces = 0;
+        for token in &tokens {
+            let word = &text[token.clone()];
+            let pieces = model.word_pieces(word).len();
+            if window_pieces + pieces > MAX_PIECES && !window.is_empty() {
+                is_name.extend(model.classify(&window)?);
+                window.clear();
+                window_pieces = 0;
+            }
+            window.push(word);
+            window_pieces += pieces;
+        }
+        if !window.is_empty() {
+            is_name.extend(model.classify(&window)?);
+        }
+
+        let mut names: Vec<Range<usize>> = Vec::new();
+        for (token, &name) in tokens.iter().zip(&is_name) {
+            if !name {
+                continue;
+            }
+            match names.last_mut() {
+                Some(last) if text[last.end..token.start].trim().is_empty() => {
+                    last.end = token.end;
+                }
+                _ => names.push(token.clone()),
+            }
+        }
+        Ok(names)
+    }
+}
@@ -0,0 +1,93 @@
+use super::stats::TranscriptionStats;
+use super::whisper_cpp::Segment;
+use serde::{Deserialize, Serialize};
+
+/// Shape of the string returned by a transcription command
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Segment text joined into a single trimmed string
+    #[default]
+    Plain,
+    /// JSON array of `{ text, startMs, endMs, ... }` segments
+    Json,
+    /// JSON object with the full text, segments, and performance stats
+    VerboseJson,
+    /// SubRip subtitles
+    Srt,
+    /// WebVTT subtitles
+    Vtt,
+}
+
+/// Body of the `verbose_json` output format
+#[derive(Serialize)]
+struct VerboseTranscript<'a> {
+    text: String,
+    segments: &'a [Segment],
+    stats: &'a TranscriptionStats,
+}
+
+/// Render decoded segments in the requested output format
+pub fn render(segments: &[Segment], stats: &TranscriptionStats, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Plain => render_plain(segments),
+        OutputFormat::Json => serde_json::to_string(segments).unwrap_or_default(),
+        OutputFormat::VerboseJson => serde_json::to_string(&VerboseTranscript {
+            text: render_plain(segments),
+            segments,
+            stats,
+        })
+        .unwrap_or_default(),
+        OutputFormat::Srt => render_srt(segments),
+        OutputFormat::Vtt => render_vtt(segments),
+    }
+}
+
+fn render_plain(segments: &[Segment]) -> String {
+    segments
+        .iter()
+        .map(|segment| segment.text.as_str())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn render_srt(segments: &[Segment]) -> String {
+    let mut output = String::new();
+    for (index, segment) in segments.iter().enumerate() {
+        output.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_timestamp(segment.start_ms, ','),
+            format_timestamp(segment.end_ms, ','),
+            segment.text.trim()
+        ));
+    }
+    output
+}
+
+fn render_vtt(segments: &[Segment]) -> String {
+    let mut output = String::from("WEBVTT\n\n");
+    for segment in segments {
+        output.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_timestamp(segment.start_ms, '.'),
+            format_timestamp(segment.end_ms, '.'),
+            segment.text.trim()
+        ));
+    }
+    output
+}
+
+/// Format milliseconds as `HH:MM:SS<sep>mmm` (SRT uses ',' and VTT uses '.')
+fn format_timestamp(ms: i64, millis_separator: char) -> String {
+    let ms = ms.max(0);
+    let hours = ms / 3_600_000;
+    let minutes = (ms % 3_600_000) / 60_000;
+    let seconds = (ms % 60_000) / 1000;
+    let millis = ms % 1000;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        hours, minutes, seconds, millis_separator, millis
+    )
+}
@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+use super::dictation;
+use super::profanity::{self, ProfanityFilter};
+use super::punctuation::PunctuationModels;
+use super::redaction::{self, PiiRedaction};
+use super::replacements;
+use super::whisper_cpp::Segment;
+
+/// Text processing applied to a transcript before it leaves the Rust layer
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostProcessingOptions {
+    /// Turn spoken commands ("new line", "comma", "scratch that") into their effects
+    pub dictation_commands: Option<bool>,
+    /// Punctuation model (`.onnx`) used to punctuate and capitalize text from
+    /// backends that output neither, like Vosk; Whisper output is left alone
+    pub punctuation_model: Option<String>,
+    /// Mask or remove profanity; runs last so replacement rules can't bring it back
+    pub censor_profanity: Option<ProfanityFilter>,
+    /// Mask emails, phone numbers, card numbers and names; runs after
+    /// everything else so nothing downstream can reintroduce them
+    pub redact_pii: Option<PiiRedaction>,
+}
+
+/// Run the post-processing pipeline over whisper.cpp segments
+///
+/// Dictation commands run first, then the saved find/replace rules, then
+/// profanity censoring and PII redaction. `language` picks the dictation
+/// command set.
+pub fn process_segments(
+    app: &AppHandle,
+    segments: Vec<Segment>,
+    options: &PostProcessingOptions,
+    language: Option<&str>,
+) -> Vec<Segment> {
+    let segments = if options.dictation_commands.unwrap_or(false) {
+        let texts: Vec<&str> = segments.iter().map(|segment| segment.text.as_str()).collect();
+        let processed = dictation::apply(&texts, language);
+        segments
+            .iter()
+            .zip(processed)
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(segment, text)| Segment {
+                text,
+                ..segment.clone()
+            })
+            .collect()
+    } else {
+        segments
+    };
+
+    let segments = replacements::apply_saved_to_segments(app, segments);
+
+    let segments = match &options.censor_profanity {
+        Some(filter) => {
+            let texts = segments.iter().map(|segment| segment.text.clone()).collect();
+            with_texts(&segments, profanity::censor_all(texts, filter))
+        }
+        None => segments,
+    };
+
+    match &options.redact_pii {
+        Some(redaction) => {
+            let texts = segments.iter().map(|segment| segment.text.clone()).collect();
+            with_texts(&segments, redaction::redact_all(app, texts, redaction))
+        }
+        None => segments,
+    }
+}
+
+fn with_texts(segments: &[Segment], texts: Vec<String>) -> Vec<Segment> {
+    segments
+        .iter()
+        .zip(texts)
+        .map(|(segment, text)| Segment {
+            text,
+            ..segment.clone()
+        })
+        .collect()
+}
+
+/// Run the post-processing pipeline over a backend's finished transcript
+pub fn process_text(
+    app: &AppHandle,
+    text: &str,
+    options: &PostProcessingOptions,
+    language: Option<&str>,
+) -> String {
+    let restored = options.punctuation_model.as_deref().and_then(|model_path| {
+        let models = app.state::<PunctuationModels>();
+        // Unpunctuated text is still better than no text
+        models
+            .restore(Path::new(model_path), text)
+            .map_err(|e| eprintln!("[Transcription] Skipping punctuation restoration: {}", e))
+            .ok()
+    });
+    let text = restored.as_deref().unwrap_or(text);
+
+    let text = if options.dictation_commands.unwrap_or(false) {
+        dictation::apply(&[text], language).concat()
+    } else {
+        text.to_string()
+    };
+
+    let text = replacements::apply_saved(app, text.trim());
+
+    let text = match &options.censor_profanity {
+        Some(filter) => profanity::censor_all(vec![text], filter).concat().trim().to_string(),
+        None => text,
+    };
+
+    match &options.redact_pii {
+        Some(redaction) => redaction::redact_all(app, vec![text], redaction).concat(),
+        None => text,
+    }
+}
@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+use super::chunking::{self, FRAME_SAMPLES};
+use crate::loudness;
+use super::whisper_cpp::Segment;
+
+const SAMPLE_RATE: usize = 16000;
+
+/// Pauses shorter than this are left alone so speech rhythm is preserved
+const MIN_SILENCE_SAMPLES: usize = SAMPLE_RATE;
+
+/// Silence kept on each side of speech when a pause is shortened
+const SILENCE_PADDING_SAMPLES: usize = SAMPLE_RATE / 4;
+
+/// Speed-up range offered to users; beyond 2x accuracy drops off sharply
+const MIN_SPEED: f32 = 1.0;
+const MAX_SPEED: f32 = 2.0;
+
+/// WSOLA analysis frame (30 ms), overlapped by half on output
+const WSOLA_FRAME: usize = 480;
+const WSOLA_HOP_OUT: usize = WSOLA_FRAME / 2;
+
+/// How far (±7.5 ms) a frame may shift to line up with the previous one
+const WSOLA_TOLERANCE: usize = 120;
+
+/// Correlation is estimated on every 4th sample, which is plenty for alignment
+const WSOLA_CORRELATION_STEP: usize = 4;
+
+/// Audio processing applied before whisper.cpp sees the samples
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreprocessingOptions {
+    /// Bring the audio to the EBU R128 loudness target, helping very quiet recordings
+    pub normalize_loudness: Option<bool>,
+    /// Shorten pauses longer than a second, e.g. in meeting recordings
+    pub remove_silence: Option<bool>,
+    /// Play the audio faster (1.0-2.0) before transcription, keeping pitch
+    pub speed: Option<f32>,
+}
+
+/// Maps timestamps in preprocessed audio back to the original recording
+///
+/// Each preprocessing stage contributes a list of `(processed, original)`
+/// sample anchors, interpolated linearly between neighbours.
+#[derive(Debug, Clone, Default)]
+pub struct TimeMap {
+    stages: Vec<Vec<(usize, usize)>>,
+}
+
+impl TimeMap {
+    fn push_stage(&mut self, anchors: Vec<(usize, usize)>) {
+        self.stages.push(anchors);
+    }
+
+    /// Translate a position in the processed audio to the original recording
+    pub fn to_original_ms(&self, ms: i64) -> i64 {
+        let mut position = ms.max(0) as f64 * SAMPLE_RATE as f64 / 1000.0;
+        for anchors in self.stages.iter().rev() {
+            position = map_through(anchors, position);
+        }
+        (position * 1000.0 / SAMPLE_RATE as f64).round() as i64
+    }
+
+    /// Rewrite segment timestamps so they refer to the original recording
+    pub fn restore(&self, segments: Vec<Segment>) -> Vec<Segment> {
+        if self.stages.is_empty() {
+            return segments;
+        }
+
+        segments
+            .into_iter()
+            .map(|segment| Segment {
+                start_ms: self.to_original_ms(segment.start_ms),
+                end_ms: self.to_original_ms(segment.end_ms),
+                ..segment
+            })
+            .collect()
+    }
+}
+
+fn map_through(anchors: &[(usize, usize)], position: f64) -> f64 {
+    // Removed audio shows up as two anchors sharing a processed position, so
+    // the left anchor is the last one at or before the position
+    let next = anchors.partition_point(|&(processed, _)| processed as f64 <= position);
+    let (left, right) = match next {
+        0 => return position,
+        n if n == anchors.len() && n < 2 => {
+            let (p0, o0) = anchors[0];
+            return o0 as f64 + (position - p0 as f64);
+        }
+        n if n == anchors.len() => (anchors[n - 2], anchors[n - 1]),
+        n => (anchors[n - 1], anchors[n]),
+    };
+
+    let (p0, o0) = (left.0 as f64, left.1 as f64);
+    let (p1, o1) = (right.0 as f64, right.1 as f64);
+    if p1 <= p0 {
+        return o0 + (position - p0);
+    }
+    o0 + (position - p0) * (o1 - o0) / (p1 - p0)
+}
+
+/// Run the requested preprocessing stages
+///
+/// Returns the samples to transcribe and the map needed to restore original
+/// timestamps. Samples are only copied when a stage actually runs.
+pub fn apply<'a>(
+    samples: &'a [f32],
+    options: &PreprocessingOptions,
+) -> (Cow<'a, [f32]>, TimeMap) {
+    let mut samples = Cow::Borrowed(samples);
+    let mut time_map = TimeMap::default();
+
+    // Runs first so silence detection sees consistent levels; timing is unchanged
+    if options.normalize_loudness.unwrap_or(false) {
+        let mut normalized = samples.into_owned();
+        loudness::normalize(&mut normalized, 1, SAMPLE_RATE as u32, loudness::TARGET_LUFS);
+        samples = Cow::Owned(normalized);
+    }
+
+    if options.remove_silence.unwrap_or(false) {
+        let (trimmed, anchors) = remove_silence(&samples);
+        println!(
+            "[Preprocess] Silence removal: {} -> {} samples",
+            samples.len(),
+            trimmed.len()
+        );
+        samples = Cow::Owned(trimmed);
+        time_map.push_stage(anchors);
+    }
+
+    if let Some(speed) = options.speed {
+        let speed = speed.clamp(MIN_SPEED, MAX_SPEED);
+        if speed > MIN_SPEED {
+            let (compressed, anchors) = time_compress(&samples, speed);
+            println!(
+                "[Preprocess] {:.2}x speed-up: {} -> {} samples",
+                speed,
+                samples.len(),
+                compressed.len()
+            );
+            samples = Cow::Owned(compressed);
+            time_map.push_stage(anchors);
+        }
+    }
+
+    (samples, time_map)
+}
+
+/// Speed audio up without changing its pitch using WSOLA
+/// (waveform similarity overlap-add)
+///
+/// Frames are read from the input `speed` times faster than they are written,
+/// and each one is nudged by up to `WSOLA_TOLERANCE` samples to the position
+/// that best continues the previous frame's waveform, which avoids the phasing
+/// artifacts of plain overlap-add. Returns the compressed audio and an anchor
+/// per frame mapping output positions back to input positions.
+fn time_compress(samples: &[f32], speed: f32) -> (Vec<f32>, Vec<(usize, usize)>) {
+    let hop_in = (WSOLA_HOP_OUT as f32 * speed).round() as usize;
+    if samples.len() < WSOLA_FRAME + hop_in + WSOLA_TOLERANCE {
+        return (samples.to_vec(), vec![(0, 0)]);
+    }
+
+    let window: Vec<f32> = (0..WSOLA_FRAME)
+        .map(|i| {
+            let phase = 2.0 * std::f32::consts::PI * i as f32 / WSOLA_FRAME as f32;
+            0.5 - 0.5 * phase.cos()
+        })
+        .collect();
+
+    let frames = (samples.len() - WSOLA_FRAME - WSOLA_TOLERANCE) / hop_in + 1;
+    let output_len = (frames - 1) * WSOLA_HOP_OUT + WSOLA_FRAME;
+    let mut output = vec![0.0f32; output_len];
+    let mut weights = vec![0.0f32; output_len];
+    let mut anchors = Vec::with_capacity(frames + 1);
+
+    let mut previous = 0usize;
+    for frame in 0..frames {
+        let nominal = frame * hop_in;
+        let offset = if frame == 0 {
+            0
+        } else {
+            best_alignment(samples, previous + WSOLA_HOP_OUT, nominal)
+        };
+
+        let out_start = frame * WSOLA_HOP_OUT;
+        for i in 0..WSOLA_FRAME {
+            output[out_start + i] += samples[offset + i] * window[i];
+            weights[out_start + i] += window[i];
+        }
+
+        anchors.push((out_start, offset));
+        previous = offset;
+    }
+    anchors.push((output_len, previous + WSOLA_FRAME));
+
+    for (sample, weight) in output.iter_mut().zip(&weights) {
+        if *weight > 1e-3 {
+            *sample /= weight;
+        }
+    }
+
+    (output, anchors)
+}
+
+/// Find the input offset near `nominal` whose frame best matches the natural
+/// continuation of the previous frame, starting at `continuation`
+fn best_alignment(samples: &[f32], continuation: usize, nominal: usize) -> usize {
+    let lowest = nominal.saturating_sub(WSOLA_TOLERANCE);
+    let highest = (nominal + WSOLA_TOLERANCE).min(samples.len() - WSOLA_FRAME);
+    if continuation + WSOLA_FRAME > samples.len() {
+        return nominal.min(highest);
+    }
+    let template = &samples[continuation..continuation + WSOLA_FRAME];
+
+    let score = |offset: usize| -> f32 {
+        (0..WSOLA_FRAME)
+            .step_by(WSOLA_CORRELATION_STEP)
+            .map(|i| samples[offset + i] * template[i])
+            .sum()
+    };
+
+    (lowest..=highest)
+        .map(|offset| (offset, score(offset)))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(nominal, |(offset, _)| offset)
+}
+
+/// Shorten every pause longer than `MIN_SILENCE_SAMPLES` to a little padding
+/// either side of the surrounding speech
+///
+/// Silence is detected per 30 ms frame against a threshold adapted to the
+/// recording's noise floor.
+fn remove_silence(samples: &[f32]) -> (Vec<f32>, Vec<(usize, usize)>) {
+    let energies = chunking::frame_energies(samples);
+    if energies.is_empty() {
+        return (samples.to_vec(), vec![(0, 0)]);
+    }
+
+    let threshold = chunking::silence_threshold(&energies);
+
+    // Collect the original sample ranges to keep
+    let mut kept: Vec<(usize, usize)> = Vec::new();
+    let mut keep_from = 0;
+    let mut frame = 0;
+    while frame < energies.len() {
+        if energies[frame] >= threshold {
+            frame += 1;
+            continue;
+        }
+
+        let silence_start = frame;
+        while frame < energies.len() && energies[frame] < threshold {
+            frame += 1;
+        }
+        let start = silence_start * FRAME_SAMPLES;
+        let end = (frame * FRAME_SAMPLES).min(samples.len());
+
+        if end - start >= MIN_SILENCE_SAMPLES {
+            let cut_start = if start == 0 { 0 } else { start + SILENCE_PADDING_SAMPLES };
+            let cut_end = if end == samples.len() { end } else { end - SILENCE_PADDING_SAMPLES };
+            if cut_start > keep_from {
+                kept.push((keep_from, cut_start));
+            }
+            keep_from = cut_end;
+        }
+    }
+    if keep_from < samples.len() {
+        kept.push((keep_from, samples.len()));
+    }
+
+    let mut output = Vec::with_capacity(samples.len());
+    let mut anchors = Vec::with_capacity(kept.len() * 2);
+    for (start, end) in kept {
+        anchors.push((output.len(), start));
+        output.extend_from_slice(&samples[start..end]);
+        anchors.push((output.len(), end));
+    }
+    if anchors.is_empty() {
+        anchors.push((0, 0));
+    }
+
+    (output, anchors)
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Serialize};
+
+/// CPU scheduling priority for a transcription, so long background jobs
+/// don't make the rest of the machine sluggish
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    #[default]
+    Normal,
+    /// Yield to interactive work (nice 10, utility QoS, below-normal class)
+    Low,
+    /// Only use otherwise idle CPU time (nice 19, background QoS, idle class)
+    Idle,
+}
+
+/// Run `work` at the requested priority
+///
+/// Lowered priorities run on a dedicated thread that exits afterwards, since
+/// an unprivileged process can't raise a thread's priority back up and the
+/// async runtime's threads must keep theirs. whisper.cpp's worker threads are
+/// started from that thread and inherit its niceness on Linux and its QoS
+/// class on macOS. Windows threads don't inherit priority, so there the whole
+/// process's priority class is lowered until the last lowered job returns.
+pub fn run_with_priority<T: Send>(priority: Priority, work: impl FnOnce() -> T + Send) -> T {
+    if priority == Priority::Normal {
+        return work();
+    }
+
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                #[cfg(windows)]
+                let _restore = lower_current_thread(priority);
+                #[cfg(not(windows))]
+                lower_current_thread(priority);
+                work()
+            })
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn lower_current_thread(priority: Priority) {
+    let nice = match priority {
+        Priority::Normal => return,
+        Priority::Low => 10,
+        Priority::Idle => 19,
+    };
+
+    // On Linux, PRIO_PROCESS with who = 0 applies to the calling thread only
+    if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, nice) } != 0 {
+        eprintln!(
+            "[Priority] Failed to set niceness {}: {}",
+            nice,
+            std::io::Error::last_os_error()
+        );
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn lower_current_thread(priority: Priority) {
+    let qos_class = match priority {
+        Priority::Normal => return,
+        Priority::Low => libc::qos_class_t::QOS_CLASS_UTILITY,
+        Priority::Idle => libc::qos_class_t::QOS_CLASS_BACKGROUND,
+    };
+
+    if unsafe { libc::pthread_set_qos_class_self_np(qos_class, 0) } != 0 {
+        eprintln!("[Priority] Failed to set thread QoS class");
+    }
+}
+
+/// Lowered-priority transcriptions currently running, and the priority class
+/// the process had before the first of them started
+///
+/// The priority class is process-wide, so overlapping jobs share it: it stays
+/// at the mildest class any running job asked for and is only restored once
+/// the last one finishes.
+#[cfg(windows)]
+struct LoweredJobs {
+    original_class: u32,
+    low: usize,
+    idle: usize,
+}
+
+#[cfg(windows)]
+static LOWERED_JOBS: std::sync::Mutex<Option<LoweredJobs>> = std::sync::Mutex::new(None);
+
+#[cfg(windows)]
+impl LoweredJobs {
+    fn class(&self) -> u32 {
+        use windows_sys::Win32::System::Threading::{
+            BELOW_NORMAL_PRIORITY_CLASS, IDLE_PRIORITY_CLASS,
+        };
+        if self.low > 0 {
+            BELOW_NORMAL_PRIORITY_CLASS
+        } else {
+            IDLE_PRIORITY_CLASS
+        }
+    }
+
+    fn count(&mut self, priority: Priority) -> &mut usize {
+        match priority {
+            Priority::Idle => &mut self.idle,
+            _ => &mut self.low,
+        }
+    }
+}
+
+#[cfg(windows)]
+fn set_process_class(class: u32) -> bool {
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, SetPriorityClass};
+    if unsafe { SetPriorityClass(GetCurrentProcess(), class) } == 0 {
+        eprintln!(
+            "[Priority] Failed to set process priority class: {}",
+            std::io::Error::last_os_error()
+        );
+        return false;
+    }
+    true
+}
+
+/// Releases a job's hold on the process priority class when it finishes
+#[cfg(windows)]
+struct PriorityClassGuard(Priority);
+
+#[cfg(windows)]
+impl Drop for PriorityClassGuard {
+    fn drop(&mut self) {
+        let mut lowered = LOWERED_JOBS.lock().unwrap();
+        let Some(jobs) = lowered.as_mut() else {
+            return;
+        };
+        *jobs.count(self.0) -= 1;
+        if jobs.low == 0 && jobs.idle == 0 {
+            set_process_class(jobs.original_class);
+            *lowered = None;
+        } else {
+            set_process_class(jobs.class());
+        }
+    }
+}
+
+#[cfg(windows)]
+fn lower_current_thread(priority: Priority) -> Option<PriorityClassGuard> {
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, GetPriorityClass};
+
+    if priority == Priority::Normal {
+        return None;
+    }
+
+    let mut lowered = LOWERED_JOBS.lock().unwrap();
+    if lowered.is_none() {
+        let original_class = unsafe { GetPriorityClass(GetCurrentProcess()) };
+        if original_class == 0 {
+            eprintln!(
+                "[Priority] Failed to read process priority class: {}",
+                std::io::Error::last_os_error()
+            );
+            return None;
+        }
+        *lowered = Some(LoweredJobs {
+            original_class,
+            low: 0,
+            idle: 0,
+        });
+    }
+    let jobs = lowered.as_mut().unwrap();
+    *jobs.count(priority) += 1;
+    set_process_class(jobs.class());
+    Some(PriorityClassGuard(priority))
+}
@@ -0,0 +1,133 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+/// How censored words appear in the transcript
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CensorMode {
+    /// Keep the first letter and star out the rest: "s***"
+    #[default]
+    Mask,
+    /// Drop the word entirely
+    Remove,
+}
+
+/// Profanity censoring for dictation in workplace settings - from frontend
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfanityFilter {
+    pub mode: Option<CensorMode>,
+    /// Extra words to censor alongside the built-in list
+    pub words: Option<Vec<String>>,
+    /// Words from the built-in list to leave alone
+    pub allowed: Option<Vec<String>>,
+}
+
+/// Common English profanity, matched as whole words with the usual endings.
+/// Words with everyday meanings (names, animals) are left to the user's list.
+const PROFANITY: &[&str] = &[
+    "arse",
+    "arsehole",
+    "ass",
+    "asshole",
+    "bastard",
+    "bitch",
+    "bollocks",
+    "bullshit",
+    "cunt",
+    "damn",
+    "dickhead",
+    "douche",
+    "douchebag",
+    "fuck",
+    "goddamn",
+    "motherfuck",
+    "motherfucker",
+    "shit",
+    "slut",
+    "twat",
+    "wanker",
+    "whore",
+];
+
+/// Endings matched after a listed word, so "fuck" also covers "fucking"
+const SUFFIXES: &str = "(?:s|es|ed|er|ers|ing|in|y|ty)?";
+
+/// Censor profanity in each text, using one compiled pattern for all of them
+pub fn censor_all(texts: Vec<String>, filter: &ProfanityFilter) -> Vec<String> {
+    let Some(pattern) = build_pattern(filter) else {
+        return texts;
+    };
+    let mode = filter.mode.unwrap_or_default();
+    texts
+        .into_iter()
+        .map(|text| censor_with(&pattern, &text, mode))
+        .collect()
+}
+
+fn build_pattern(filter: &ProfanityFilter) -> Option<Regex> {
+    let allowed: Vec<String> = filter
+        .allowed
+        .iter()
+        .flatten()
+        .map(|word| word.trim().to_lowercase())
+        .collect();
+    let mut words: Vec<String> = PROFANITY
+        .iter()
+        .map(|word| word.to_string())
+        .filter(|word| !allowed.contains(word))
+        .collect();
+    words.extend(
+        filter
+            .words
+            .iter()
+            .flatten()
+            .map(|word| word.trim().to_lowercase())
+            .filter(|word| !word.is_empty()),
+    );
+    if words.is_empty() {
+        return None;
+    }
+
+    // Longest first so "motherfucker" isn't cut short at "motherfuck"
+    words.sort_by_key(|word| std::cmp::Reverse(word.len()));
+    let alternatives: Vec<String> = words.iter().map(|word| regex::escape(word)).collect();
+    let pattern = format!(r"\b(?:{}){}\b", alternatives.join("|"), SUFFIXES);
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| eprintln!("[Transcription] Invalid profanity list: {}", e))
+        .ok()
+}
+
+fn censor_with(pattern: &Regex, text: &str, mode: CensorMode) -> String {
+    match mode {
+        CensorMode::Mask => pattern
+            .replace_all(text, |captures: &regex::Captures| {
+                let mut chars = captures[0].chars();
+                let first = chars.next().map(String::from).unwrap_or_default();
+                first + &"*".repeat(chars.count())
+            })
+            .into_owned(),
+        CensorMode::Remove => {
+            let removed = pattern.replace_all(text, "");
+            // Tidy the gaps left behind: doubled spaces and spaces before punctuation
+            let mut tidied = String::with_capacity(removed.len());
+            for c in removed.chars() {
+                let after_space = tidied.ends_with(' ');
+                match c {
+                    ' ' if after_space => continue,
+                    // A sentence that began with a removed word
+                    ',' | ';' | ':' if tidied.trim().is_empty() => continue,
+                    ',' | '.' | '!' | '?' | ';' | ':' if after_space => {
+                        tidied.pop();
+                    }
+                    _ => {}
+                }
+                tidied.push(c);
+            }
+            tidied
+        }
+    }
+}
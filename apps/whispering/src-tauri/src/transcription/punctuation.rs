@@ -0,0 +1,188 @@
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use ort::value::Tensor;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Words per model run; the CT-Transformer attends over the whole window
+const WINDOW_WORDS: usize = 200;
+
+/// A CT-Transformer punctuation model, as exported by sherpa-onnx
+/// (`sherpa-onnx-punct-ct-transformer-*`)
+///
+/// The model predicts, for every word, which mark follows it. Its vocabulary
+/// and the ids of each mark are stored in the ONNX metadata.
+struct PunctuationModel {
+    session: Session,
+    vocabulary: HashMap<String, i32>,
+    unknown: i32,
+    comma: usize,
+    period: usize,
+    question: usize,
+    pause: usize,
+}
+
+impl PunctuationModel {
+    fn load(model_path: &Path) -> Result<Self, String> {
+        let to_error = |e: ort::Error| format!("Failed to load punctuation model: {}", e);
+        let session = Session::builder()
+            .and_then(|builder| builder.with_optimization_level(GraphOptimizationLevel::Level3))
+            .and_then(|builder| builder.with_intra_threads(1))
+            .and_then(|builder| builder.commit_from_file(model_path))
+            .map_err(to_error)?;
+
+        // The metadata borrows the session, so read everything up front
+        let (tokens, unknown_symbol, [comma, period, question, pause]) = {
+            let metadata = session.metadata().map_err(to_error)?;
+            let read = |key: &str| -> Result<String, String> {
+                metadata
+                    .custom(key)
+                    .map_err(to_error)?
+                    .ok_or_else(|| format!("Punctuation model is missing \"{}\" metadata", key))
+            };
+            let read_id = |key: &str| -> Result<usize, String> {
+                read(key)?
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Punctuation model has an invalid \"{}\" id", key))
+            };
+            (
+                read("tokens")?,
+                read("unk_symbol").unwrap_or_else(|_| "<unk>".to_string()),
+                [read_id("comma")?, read_id("dot")?, read_id("quest")?, read_id("pause")?],
+            )
+        };
+
+        let vocabulary: HashMap<String, i32> = tokens
+            .split('|')
+            .enumerate()
+            .map(|(id, token)| (token.to_string(), id as i32))
+            .collect();
+        let unknown = vocabulary.get(&unknown_symbol).copied().unwrap_or(0);
+
+        let model = Self {
+            session,
+            vocabulary,
+            unknown,
+            comma,
+            period,
+            question,
+            pause,
+        };
+        Ok(model)
+    }
+
+    /// Predict the mark (if any) following each word
+    fn predict(&mut self, words: &[String]) -> Result<Vec<Option<char>>, String> {
+        let to_error = |e: ort::Error| format!("Punctuation model failed: {}", e);
+        let ids: Vec<i32> = words
+            .iter()
+            .map(|word| self.vocabulary.get(word).copied().unwrap_or(self.unknown))
+            .collect();
+        let length = ids.len();
+
+        let text = Tensor::from_array(([1usize, length], ids)).map_err(to_error)?;
+        let text_length = Tensor::from_array(([1usize], vec![length as i32])).map_err(to_error)?;
+        let outputs = self
+            .session
+            .run(ort::inputs![text, text_length])
+            .map_err(to_error)?;
+        let (shape, logits) = outputs[0].try_extract_tensor::<f32>().map_err(to_error)?;
+
+        let classes = shape.last().copied().unwrap_or(0).max(1) as usize;
+        Ok(logits
+            .chunks(classes)
+            .take(length)
+            .map(|scores| {
+                let best = scores
+                    .iter()
+                    .enumerate()
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .map_or(0, |(class, _)| class);
+                match best {
+                    class if class == self.comma || class == self.pause => Some(','),
+                    class if class == self.period => Some('.'),
+                    class if class == self.question => Some('?'),
+                    _ => None,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Lowercase a word and strip surrounding punctuation, matching the model's
+/// vocabulary
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+        .to_lowercase()
+}
+
+/// The most recently used punctuation model, kept loaded between transcriptions
+pub struct PunctuationModels {
+    loaded: Mutex<Option<(PathBuf, PunctuationModel)>>,
+}
+
+impl PunctuationModels {
+    pub fn new() -> Self {
+        Self {
+            loaded: Mutex::new(None),
+        }
+    }
+
+    /// Add punctuation and sentence capitalization to lowercase, unpunctuated
+    /// text, such as Vosk or CTC model output
+    ///
+    /// Any punctuation already present is replaced by the model's. Words are
+    /// split on whitespace, so this suits languages written with spaces.
+    pub fn restore(&self, model_path: &Path, text: &str) -> Result<String, String> {
+        let words: Vec<String> = text
+            .split_whitespace()
+            .map(normalize_word)
+            .filter(|word| !word.is_empty())
+            .collect();
+        if words.is_empty() {
+            return Ok(String::new());
+        }
+
+        let mut loaded = self.loaded.lock().unwrap();
+        if loaded.as_ref().map_or(true, |(path, _)| path != model_path) {
+            let model = PunctuationModel::load(model_path)?;
+            println!("[Punctuation] Loaded model {:?}", model_path);
+            *loaded = Some((model_path.to_path_buf(), model));
+        }
+        let (_, model) = loaded.as_mut().unwrap();
+
+        let mut marks = Vec::with_capacity(words.len());
+        for window in words.chunks(WINDOW_WORDS) {
+            marks.extend(model.predict(window)?);
+        }
+
+        let mut output = String::with_capacity(text.len() + words.len());
+        let mut sentence_start = true;
+        for (word, mark) in words.iter().zip(&marks) {
+            if !output.is_empty() {
+                output.push(' ');
+            }
+            if sentence_start || word == "i" || word.starts_with("i'") {
+                let mut chars = word.chars();
+                if let Some(first) = chars.next() {
+                    output.extend(first.to_uppercase());
+                    output.push_str(chars.as_str());
+                }
+            } else {
+                output.push_str(word);
+            }
+            if let Some(mark) = mark {
+                output.push(*mark);
+            }
+            sentence_start = matches!(mark, Some('.') | Some('?'));
+        }
+        if !output.ends_with(['.', '?']) {
+            output.truncate(output.trim_end_matches(',').len());
+            output.push('.');
+        }
+
+        Ok(output)
+    }
+}
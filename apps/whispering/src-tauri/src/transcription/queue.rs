@@ -0,0 +1,464 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::error::TranscriptionError;
+use super::output_format::{self, OutputFormat};
+use super::postprocess::PostProcessingOptions;
+use super::preprocess::PreprocessingOptions;
+use super::whisper_cpp::{
+    ComputeDevice, DecodingOptions, HallucinationFilter, PerformanceOptions, Task, TranscriptionJob,
+    WhisperOptions,
+};
+use super::{ModelManager, Vocabulary};
+
+/// Event emitted whenever a queued job changes status
+pub const JOB_EVENT: &str = "transcription-job";
+
+/// Unfinished and failed jobs, saved in the app data directory
+const QUEUE_FILE: &str = "transcription-queue.json";
+
+/// Completed and cancelled jobs kept for `list_jobs`; older ones are dropped so
+/// their transcripts don't accumulate for the life of the process
+const MAX_FINISHED_JOBS: usize = 50;
+
+/// Higher priority jobs run first; equal priorities run in submission order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// A file transcription submitted to the queue
+///
+/// Mirrors the options of `transcribe_audio_whisper`, but takes a path so
+/// large batches aren't held in memory while they wait.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionRequest {
+    pub audio_path: String,
+    pub model_path: String,
+    pub language: Option<String>,
+    pub task: Option<Task>,
+    pub initial_prompt: Option<String>,
+    pub decoding: Option<DecodingOptions>,
+    pub performance: Option<PerformanceOptions>,
+    pub preprocessing: Option<PreprocessingOptions>,
+    #[serde(default)]
+    pub hallucination_filter: Option<HallucinationFilter>,
+    #[serde(default)]
+    pub post_processing: Option<PostProcessingOptions>,
+    pub device: Option<ComputeDevice>,
+    pub output_format: Option<OutputFormat>,
+    #[serde(default)]
+    pub priority: JobPriority,
+}
+
+/// A job in the transcription queue - sent to frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuedJob {
+    pub id: String,
+    pub request: TranscriptionRequest,
+    pub status: JobStatus,
+    /// Rendered transcript once completed
+    pub result: Option<String>,
+    pub error: Option<String>,
+    pub enqueued_ms: u64,
+    #[serde(skip)]
+    sequence: u64,
+    #[serde(skip)]
+    cancelled: Arc<AtomicBool>,
+}
+
+struct QueueState {
+    jobs: Vec<QueuedJob>,
+    next_sequence: u64,
+    /// Where pending jobs are persisted; unset until the worker has started
+    store_path: Option<PathBuf>,
+}
+
+impl QueueState {
+    /// Save queued, running, and failed jobs so a restart can pick them up
+    fn persist(&self) {
+        let Some(path) = &self.store_path else {
+            return;
+        };
+
+        let pending: Vec<&QueuedJob> = self
+            .jobs
+            .iter()
+            .filter(|job| {
+                matches!(
+                    job.status,
+                    JobStatus::Queued | JobStatus::Running | JobStatus::Failed
+                )
+            })
+            .collect();
+
+        let result = serde_json::to_string(&pending)
+            .map_err(|e| e.to_string())
+            .and_then(|contents| std::fs::write(path, contents).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            eprintln!("[Transcription Queue] Failed to save queue: {}", e);
+        }
+    }
+
+    /// Drop the oldest completed and cancelled jobs beyond `MAX_FINISHED_JOBS`.
+    /// Their final status was already emitted as a `transcription-job` event.
+    fn prune_finished(&mut self) {
+        let is_finished =
+            |job: &QueuedJob| matches!(job.status, JobStatus::Completed | JobStatus::Cancelled);
+        let mut excess = self
+            .jobs
+            .iter()
+            .filter(|job| is_finished(job))
+            .count()
+            .saturating_sub(MAX_FINISHED_JOBS);
+
+        // Jobs are kept in submission order, so the first finished ones are oldest
+        self.jobs.retain(|job| {
+            if excess > 0 && is_finished(job) {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+/// Serializes file transcriptions against the shared model
+///
+/// A single worker thread runs one job at a time, so batch-transcribing many
+/// files never has more than one decode in memory. Progress and segment events
+/// for the running job use its queue ID, like `transcribe_audio_whisper` does
+/// with `job_id`.
+///
+/// Pending and failed jobs are persisted to disk, so quitting mid-batch
+/// resumes the remaining files on the next launch.
+pub struct TranscriptionQueue {
+    state: Mutex<QueueState>,
+    wakeup: Condvar,
+}
+
+impl TranscriptionQueue {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(QueueState {
+                jobs: Vec::new(),
+                next_sequence: 0,
+                store_path: None,
+            }),
+            wakeup: Condvar::new(),
+        }
+    }
+
+    /// Restore jobs saved by a previous session, then start the worker thread
+    /// that drains the queue for the app's lifetime
+    pub fn spawn_worker(app: AppHandle) {
+        match app.path().app_data_dir() {
+            Ok(dir) => app.state::<TranscriptionQueue>().restore(dir.join(QUEUE_FILE)),
+            Err(e) => eprintln!("[Transcription Queue] Queue won't be persisted: {}", e),
+        }
+
+        std::thread::spawn(move || loop {
+            let queue = app.state::<TranscriptionQueue>();
+            let job = queue.next_job();
+            let _ = app.emit(JOB_EVENT, &job);
+
+            println!("[Transcription Queue] Running job {}", job.id);
+            let result = run_job(&app, &job);
+
+            if let Some(finished) = queue.finish_job(&job.id, result) {
+                let _ = app.emit(JOB_EVENT, &finished);
+            }
+        });
+    }
+
+    fn restore(&self, path: PathBuf) {
+        let mut state = self.state.lock().unwrap();
+
+        let saved: Vec<QueuedJob> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+
+        for mut job in saved {
+            // A job that was running when the app quit starts over
+            if job.status == JobStatus::Running {
+                job.status = JobStatus::Queued;
+            }
+            job.sequence = state.next_sequence;
+            state.next_sequence += 1;
+            state.jobs.push(job);
+        }
+
+        if !state.jobs.is_empty() {
+            println!(
+                "[Transcription Queue] Restored {} unfinished jobs",
+                state.jobs.len()
+            );
+        }
+
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        state.store_path = Some(path);
+        self.wakeup.notify_one();
+    }
+
+    fn enqueue(&self, request: TranscriptionRequest) -> QueuedJob {
+        let mut state = self.state.lock().unwrap();
+        let sequence = state.next_sequence;
+        state.next_sequence += 1;
+
+        let enqueued_ms = now_ms();
+        let job = QueuedJob {
+            id: format!("job-{}-{}", enqueued_ms, sequence),
+            request,
+            status: JobStatus::Queued,
+            result: None,
+            error: None,
+            enqueued_ms,
+            sequence,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        };
+        state.jobs.push(job.clone());
+        state.persist();
+        self.wakeup.notify_one();
+        job
+    }
+
+    /// Block until a job is queued, then mark the most urgent one as running
+    fn next_job(&self) -> QueuedJob {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            let next = state
+                .jobs
+                .iter_mut()
+                .filter(|job| job.status == JobStatus::Queued)
+                .max_by(|a, b| {
+                    a.request
+                        .priority
+                        .cmp(&b.request.priority)
+                        .then(b.sequence.cmp(&a.sequence))
+                });
+
+            if let Some(job) = next {
+                job.status = JobStatus::Running;
+                let job = job.clone();
+                state.persist();
+                return job;
+            }
+            state = self.wakeup.wait(state).unwrap();
+        }
+    }
+
+    fn finish_job(
+        &self,
+        job_id: &str,
+        result: Result<String, TranscriptionError>,
+    ) -> Option<QueuedJob> {
+        let mut state = self.state.lock().unwrap();
+        let job = state.jobs.iter_mut().find(|job| job.id == job_id)?;
+
+        match result {
+            Ok(text) => {
+                job.status = JobStatus::Completed;
+                job.result = Some(text);
+            }
+            Err(TranscriptionError::CancelledError { .. }) => {
+                job.status = JobStatus::Cancelled;
+            }
+            Err(e) => {
+                eprintln!("[Transcription Queue] Job {} failed: {}", job_id, e);
+                job.status = JobStatus::Failed;
+                job.error = Some(e.to_string());
+            }
+        }
+        let job = job.clone();
+        state.prune_finished();
+        state.persist();
+        Some(job)
+    }
+
+    fn list(&self) -> Vec<QueuedJob> {
+        self.state.lock().unwrap().jobs.clone()
+    }
+
+    /// Cancel a queued or running job. Returns the updated job if it was still pending.
+    fn cancel(&self, job_id: &str) -> Option<QueuedJob> {
+        let mut state = self.state.lock().unwrap();
+        let job = state.jobs.iter_mut().find(|job| job.id == job_id)?;
+
+        match job.status {
+            JobStatus::Queued => {
+                job.status = JobStatus::Cancelled;
+                let job = job.clone();
+                state.prune_finished();
+                state.persist();
+                Some(job)
+            }
+            // whisper.cpp polls this flag through the abort callback
+            JobStatus::Running => {
+                job.cancelled.store(true, Ordering::Relaxed);
+                Some(job.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Re-queue every failed job with a fresh cancellation flag
+    fn retry_failed(&self) -> Vec<QueuedJob> {
+        let mut state = self.state.lock().unwrap();
+        let mut retried = Vec::new();
+
+        for job in state.jobs.iter_mut() {
+            if job.status == JobStatus::Failed {
+                job.status = JobStatus::Queued;
+                job.error = None;
+                job.cancelled = Arc::new(AtomicBool::new(false));
+                retried.push(job.clone());
+            }
+        }
+
+        if !retried.is_empty() {
+            state.persist();
+            self.wakeup.notify_one();
+        }
+        retried
+    }
+}
+
+fn run_job(app: &AppHandle, job: &QueuedJob) -> Result<String, TranscriptionError> {
+    let request = &job.request;
+
+    let audio_data =
+        std::fs::read(&request.audio_path).map_err(|e| TranscriptionError::AudioReadError {
+            message: format!("Failed to read {}: {}", request.audio_path, e),
+        })?;
+    let wav_data = super::convert_audio_for_whisper(audio_data)?;
+    let samples = super::extract_samples_from_wav(wav_data)?;
+    if samples.is_empty() {
+        return Ok(String::new());
+    }
+
+    let options = WhisperOptions {
+        decoding: request.decoding.clone().unwrap_or_default(),
+        performance: request.performance.clone().unwrap_or_default(),
+        preprocessing: request.preprocessing.clone().unwrap_or_default(),
+        hallucination_filter: request.hallucination_filter.clone().unwrap_or_default(),
+        post_processing: request.post_processing.clone().unwrap_or_default(),
+        ..super::whisper_options(
+            request.language.clone(),
+            request.task,
+            request.initial_prompt.clone(),
+            &app.state::<Vocabulary>(),
+            app,
+        )
+    };
+    let transcription_job = TranscriptionJob {
+        app: app.clone(),
+        id: job.id.clone(),
+        cancelled: job.cancelled.clone(),
+    };
+
+    let (segments, stats) = super::run_whisper(
+        &samples,
+        &request.model_path,
+        request.device.unwrap_or_default(),
+        &options,
+        Some(transcription_job),
+        &app.state::<ModelManager>(),
+        app,
+    )?;
+
+    Ok(output_format::render(
+        &segments,
+        &stats,
+        request.output_format.unwrap_or_default(),
+    ))
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Add a file to the transcription queue
+///
+/// # Returns
+/// * `QueuedJob` - The queued job; watch `transcription-job` events for status changes
+#[tauri::command]
+pub fn enqueue_transcription(
+    request: TranscriptionRequest,
+    queue: tauri::State<'_, TranscriptionQueue>,
+    app_handle: AppHandle,
+) -> QueuedJob {
+    let job = queue.enqueue(request);
+    let _ = app_handle.emit(JOB_EVENT, &job);
+    job
+}
+
+/// List queued, running, and finished jobs in submission order
+///
+/// Only the most recent completed and cancelled jobs are kept; failed jobs
+/// stay until retried.
+#[tauri::command]
+pub fn list_jobs(queue: tauri::State<'_, TranscriptionQueue>) -> Vec<QueuedJob> {
+    queue.list()
+}
+
+/// Cancel a queued or running job. Returns false if it had already finished or doesn't exist.
+#[tauri::command]
+pub fn cancel_job(
+    job_id: String,
+    queue: tauri::State<'_, TranscriptionQueue>,
+    app_handle: AppHandle,
+) -> bool {
+    match queue.cancel(&job_id) {
+        Some(job) => {
+            // Running jobs report Cancelled once whisper.cpp has actually stopped
+            if job.status == JobStatus::Cancelled {
+                let _ = app_handle.emit(JOB_EVENT, &job);
+            }
+            true
+        }
+        None => false,
+    }
+}
+
+/// Re-queue every failed job, e.g. after fixing a missing model or FFmpeg install
+///
+/// # Returns
+/// * `Vec<QueuedJob>` - The jobs that were queued again
+#[tauri::command]
+pub fn retry_failed_jobs(
+    queue: tauri::State<'_, TranscriptionQueue>,
+    app_handle: AppHandle,
+) -> Vec<QueuedJob> {
+    let retried = queue.retry_failed();
+    for job in &retried {
+        let _ = app_handle.emit(JOB_EVENT, job);
+    }
+    retried
+}
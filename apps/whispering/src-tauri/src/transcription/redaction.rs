@@ -0,0 +1,128 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+use super::ner::NerModels;
+
+/// Which kinds of personal information to mask - from frontend
+///
+/// Emails, phone numbers and card numbers are found with patterns and are on
+/// unless turned off. Names need a NER model, so they're only masked when
+/// `ner_model` is set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PiiRedaction {
+    pub emails: Option<bool>,
+    pub phone_numbers: Option<bool>,
+    pub credit_cards: Option<bool>,
+    pub names: Option<bool>,
+    /// Folder holding a BERT token-classification model (`model.onnx`,
+    /// `vocab.txt`, `config.json`), e.g. an ONNX export of `dslim/bert-base-NER`
+    pub ner_model: Option<String>,
+}
+
+const EMAIL_MASK: &str = "[EMAIL]";
+const PHONE_MASK: &str = "[PHONE]";
+const CARD_MASK: &str = "[CARD]";
+const NAME_MASK: &str = "[NAME]";
+const WITHHELD_MASK: &str = "[REDACTED]";
+
+/// Written addresses, and the spoken "jane at example dot com" form
+const EMAIL: &str = concat!(
+    r"(?i)\b[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}\b",
+    r"|\b[a-z0-9._-]+ at [a-z0-9-]+(?: dot [a-z0-9-]+)+\b",
+);
+
+/// 13 to 19 digits, optionally grouped by spaces or dashes; confirmed with Luhn
+const CARD: &str = r"\b(?:\d[ -]?){12,18}\d\b";
+
+/// North American numbers like (555) 123-4567, and international numbers
+/// written with a leading +
+const PHONE: &str = concat!(
+    r"(?:\+1[ .-]?)?(?:\(\d{3}\)|\b\d{3})[ .-]?\d{3}[ .-]?\d{4}\b",
+    r"|\+\d{1,3}(?:[ .-]?\d{2,4}){2,5}\b",
+);
+
+/// Mask personal information in each text
+///
+/// Patterns run before the NER model so that addresses like
+/// "jane.doe@example.com" are masked whole rather than around the name.
+pub fn redact_all(app: &AppHandle, texts: Vec<String>, options: &PiiRedaction) -> Vec<String> {
+    let patterns = [EMAIL, CARD, PHONE].map(|pattern| Regex::new(pattern).unwrap());
+    texts
+        .into_iter()
+        .map(|text| {
+            let text = redact_patterns(&text, &patterns, options);
+            match options.ner_model.as_deref() {
+                Some(model_dir) if options.names.unwrap_or(true) => {
+                    redact_names(app, Path::new(model_dir), &text)
+                }
+                _ => text,
+            }
+        })
+        .collect()
+}
+
+fn redact_patterns(text: &str, patterns: &[Regex; 3], options: &PiiRedaction) -> String {
+    let [email, card, phone] = patterns;
+    let mut text = text.to_string();
+    if options.emails.unwrap_or(true) {
+        text = email.replace_all(&text, EMAIL_MASK).into_owned();
+    }
+    // Cards before phones, so a card's last ten digits aren't taken for a phone number
+    if options.credit_cards.unwrap_or(true) {
+        text = card
+            .replace_all(&text, |captures: &regex::Captures| {
+                if passes_luhn(&captures[0]) {
+                    CARD_MASK.to_string()
+                } else {
+                    captures[0].to_string()
+                }
+            })
+            .into_owned();
+    }
+    if options.phone_numbers.unwrap_or(true) {
+        text = phone.replace_all(&text, PHONE_MASK).into_owned();
+    }
+    text
+}
+
+/// The checksum every payment card number satisfies, which rules out most
+/// other long numbers
+fn passes_luhn(number: &str) -> bool {
+    let digits: Vec<u32> = number.chars().filter_map(|c| c.to_digit(10)).collect();
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(position, &digit)| match position % 2 {
+            0 => digit,
+            _ if digit > 4 => digit * 2 - 9,
+            _ => digit * 2,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+fn redact_names(app: &AppHandle, model_dir: &Path, text: &str) -> String {
+    let names: Vec<Range<usize>> = match app.state::<NerModels>().find_names(model_dir, text) {
+        Ok(names) => names,
+        // Failing open would leak names, so withhold the text instead
+        Err(e) => {
+            eprintln!("[Transcription] Name redaction failed: {}", e);
+            return WITHHELD_MASK.to_string();
+        }
+    };
+
+    let mut redacted = String::with_capacity(text.len());
+    let mut last = 0;
+    for name in names {
+        redacted.push_str(&text[last..name.start]);
+        redacted.push_str(NAME_MASK);
+        last = name.end;
+    }
+    redacted.push_str(&text[last..]);
+    redacted
+}
@@ -0,0 +1,244 @@
+use regex::{Captures, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use super::whisper_cpp::Segment;
+
+/// A find/replace correction applied to every transcript - from frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacementRule {
+    pub find: String,
+    pub replace: String,
+    /// Treat `find` as a regular expression; `replace` may then use `$1`-style groups
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Only match literal rules at word boundaries, so "cat" leaves "concatenate" alone
+    #[serde(default = "enabled")]
+    pub whole_word: bool,
+    /// Match the case of the replaced text: "EPICENTRE" becomes "EPICENTER"
+    #[serde(default = "enabled")]
+    pub preserve_case: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+struct CompiledRule {
+    pattern: Regex,
+    rule: ReplacementRule,
+}
+
+/// Find/replace rules applied to transcripts before they are returned, so
+/// corrections like "epicentre" → "Epicenter" are made the same way for every
+/// backend and for queued jobs
+///
+/// Persisted as a JSON array in the app config directory and cached, compiled,
+/// after the first read. Rules run in order, each on the previous one's output.
+pub struct Replacements {
+    rules: Mutex<Option<Vec<CompiledRule>>>,
+}
+
+impl Replacements {
+    pub fn new() -> Self {
+        Self {
+            rules: Mutex::new(None),
+        }
+    }
+
+    /// Get the saved rules, reading them from disk on first use
+    pub fn get(&self, app: &AppHandle) -> Result<Vec<ReplacementRule>, String> {
+        self.with_rules(app, |rules| {
+            rules.iter().map(|compiled| compiled.rule.clone()).collect()
+        })
+    }
+
+    /// Replace the saved rules, dropping ones with nothing to find
+    ///
+    /// Every rule is compiled first, so an invalid regex is reported without
+    /// touching the saved rules.
+    pub fn set(
+        &self,
+        app: &AppHandle,
+        rules: Vec<ReplacementRule>,
+    ) -> Result<Vec<ReplacementRule>, String> {
+        let compiled = compile(rules.into_iter().filter(|rule| !rule.find.is_empty()))?;
+        let cleaned: Vec<ReplacementRule> =
+            compiled.iter().map(|compiled| compiled.rule.clone()).collect();
+
+        let path = replacements_path(app)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let contents = serde_json::to_string_pretty(&cleaned)
+            .map_err(|e| format!("Failed to serialize replacement rules: {}", e))?;
+        std::fs::write(&path, contents)
+            .map_err(|e| format!("Failed to save replacement rules: {}", e))?;
+
+        *self.rules.lock().unwrap() = Some(compiled);
+        Ok(cleaned)
+    }
+
+    /// Apply the saved rules to a transcript
+    pub fn apply(&self, app: &AppHandle, text: &str) -> String {
+        self.with_saved_rules(app, |rules| apply_rules(rules, text))
+            .unwrap_or_else(|| text.to_string())
+    }
+
+    /// Apply the saved rules to each segment's text, keeping timestamps
+    pub fn apply_to_segments(&self, app: &AppHandle, segments: Vec<Segment>) -> Vec<Segment> {
+        self.with_saved_rules(app, |rules| {
+            segments
+                .iter()
+                .map(|segment| Segment {
+                    text: apply_rules(rules, &segment.text),
+                    ..segment.clone()
+                })
+                .collect()
+        })
+        .unwrap_or(segments)
+    }
+
+    /// Like `with_rules`, but a broken rules file shouldn't block transcription
+    fn with_saved_rules<T>(
+        &self,
+        app: &AppHandle,
+        f: impl FnOnce(&[CompiledRule]) -> T,
+    ) -> Option<T> {
+        self.with_rules(app, |rules| (!rules.is_empty()).then(|| f(rules)))
+            .unwrap_or_else(|e| {
+                eprintln!("[Transcription] Ignoring replacement rules: {}", e);
+                None
+            })
+    }
+
+    fn with_rules<T>(
+        &self,
+        app: &AppHandle,
+        f: impl FnOnce(&[CompiledRule]) -> T,
+    ) -> Result<T, String> {
+        let mut rules = self.rules.lock().unwrap();
+        if let Some(rules) = rules.as_ref() {
+            return Ok(f(rules));
+        }
+
+        let path = replacements_path(app)?;
+        let loaded: Vec<ReplacementRule> = if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read replacement rules: {}", e))?;
+            serde_json::from_str(&contents)
+                .map_err(|e| format!("Failed to parse replacement rules: {}", e))?
+        } else {
+            Vec::new()
+        };
+
+        Ok(f(rules.insert(compile(loaded)?)))
+    }
+}
+
+fn replacements_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join("replacements.json"))
+        .map_err(|e| format!("Failed to resolve config directory: {}", e))
+}
+
+fn compile(rules: impl IntoIterator<Item = ReplacementRule>) -> Result<Vec<CompiledRule>, String> {
+    rules
+        .into_iter()
+        .map(|rule| {
+            let pattern = if rule.regex {
+                rule.find.clone()
+            } else {
+                literal_pattern(&rule.find, rule.whole_word)
+            };
+            let pattern = RegexBuilder::new(&pattern)
+                .case_insensitive(!rule.case_sensitive)
+                .build()
+                .map_err(|e| format!("Invalid pattern \"{}\": {}", rule.find, e))?;
+            Ok(CompiledRule { pattern, rule })
+        })
+        .collect()
+}
+
+/// Escape a literal search term, anchoring it at word boundaries
+///
+/// A boundary is only added next to a word character, since `\b` beside
+/// punctuation (as in "C++") would never match where users expect.
+fn literal_pattern(find: &str, whole_word: bool) -> String {
+    let escaped = regex::escape(find);
+    if !whole_word {
+        return escaped;
+    }
+
+    let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    format!(
+        "{}{}{}",
+        if is_word(find.chars().next()) { r"\b" } else { "" },
+        escaped,
+        if is_word(find.chars().last()) { r"\b" } else { "" },
+    )
+}
+
+fn apply_rules(rules: &[CompiledRule], text: &str) -> String {
+    let mut text = text.to_string();
+    for CompiledRule { pattern, rule } in rules {
+        text = pattern
+            .replace_all(&text, |captures: &Captures| {
+                let mut replacement = String::new();
+                if rule.regex {
+                    captures.expand(&rule.replace, &mut replacement);
+                } else {
+                    replacement.push_str(&rule.replace);
+                }
+
+                if rule.preserve_case {
+                    match_case(&captures[0], &replacement)
+                } else {
+                    replacement
+                }
+            })
+            .into_owned();
+    }
+    text
+}
+
+/// Carry the case of the matched text over to its replacement
+///
+/// All-caps matches are upper-cased and capitalized matches get a capital
+/// first letter; otherwise the replacement is used as written, so rules that
+/// fix capitalization ("github" → "GitHub") still apply.
+fn match_case(matched: &str, replacement: &str) -> String {
+    let mut letters = matched.chars().filter(|c| c.is_alphabetic());
+    let Some(first) = letters.next() else {
+        return replacement.to_string();
+    };
+    let rest: Vec<char> = letters.collect();
+
+    if first.is_uppercase() && !rest.is_empty() && rest.iter().all(|c| c.is_uppercase()) {
+        return replacement.to_uppercase();
+    }
+    if first.is_uppercase() {
+        let mut chars = replacement.chars();
+        if let Some(initial) = chars.next() {
+            return initial.to_uppercase().chain(chars).collect();
+        }
+    }
+    replacement.to_string()
+}
+
+/// Apply the saved rules to a backend's finished transcript
+pub fn apply_saved(app: &AppHandle, text: &str) -> String {
+    app.state::<Replacements>().apply(app, text)
+}
+
+/// Apply the saved rules to whisper.cpp segments
+pub fn apply_saved_to_segments(app: &AppHandle, segments: Vec<Segment>) -> Vec<Segment> {
+    app.state::<Replacements>().apply_to_segments(app, segments)
+}
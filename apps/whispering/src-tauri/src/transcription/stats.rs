@@ -0,0 +1,199 @@
+use serde::Serialize;
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use whisper_rs::FullParams;
+use whisper_rs_sys::{whisper_context, whisper_state, whisper_token_data};
+
+use super::hotwords::HotwordBias;
+
+/// How often resident memory is sampled while a transcription runs
+const MEMORY_SAMPLE_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Timing and resource usage of a single transcription - sent to frontend
+///
+/// `real_time_factor` is processing time divided by audio duration, so values
+/// below 1.0 are faster than real time. Encode and decode times are summed
+/// across chunks when `performance.parallelChunks` is used, so they can
+/// exceed `processing_ms`.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionStats {
+    pub audio_duration_ms: u64,
+    /// Preprocessing plus inference, excluding the model load
+    pub processing_ms: u64,
+    pub real_time_factor: f64,
+    /// `None` when the model was already resident
+    pub model_load_ms: Option<u64>,
+    /// Time spent in whisper's audio encoder
+    pub encode_ms: u64,
+    /// Time spent decoding text tokens
+    pub decode_ms: u64,
+    /// Peak resident memory of the app during the transcription
+    pub peak_memory_bytes: Option<u64>,
+}
+
+impl TranscriptionStats {
+    pub fn new(
+        audio_duration_ms: u64,
+        processing: Duration,
+        model_load: Option<Duration>,
+        timings: &InferenceTimings,
+        peak_memory_bytes: Option<u64>,
+    ) -> Self {
+        let processing_ms = processing.as_millis() as u64;
+        let real_time_factor = if audio_duration_ms > 0 {
+            processing_ms as f64 / audio_duration_ms as f64
+        } else {
+            0.0
+        };
+
+        Self {
+            audio_duration_ms,
+            processing_ms,
+            real_time_factor,
+            model_load_ms: model_load.map(|load| load.as_millis() as u64),
+            encode_ms: timings.encode_us.load(Ordering::Relaxed) / 1000,
+            decode_ms: timings.decode_us.load(Ordering::Relaxed) / 1000,
+            peak_memory_bytes,
+        }
+    }
+}
+
+/// Encoder and decoder time accumulated across whisper.cpp runs
+#[derive(Debug, Default)]
+pub struct InferenceTimings {
+    encode_us: AtomicU64,
+    decode_us: AtomicU64,
+}
+
+impl InferenceTimings {
+    /// Record a finished whisper.cpp run; whatever wasn't encoding was decoding
+    pub fn add_run(&self, stages: &StageClock, total: Duration) {
+        let encode = *stages.encode.lock().unwrap();
+        self.encode_us
+            .fetch_add(encode.as_micros() as u64, Ordering::Relaxed);
+        self.decode_us.fetch_add(
+            total.saturating_sub(encode).as_micros() as u64,
+            Ordering::Relaxed,
+        );
+    }
+}
+
+/// Splits one whisper.cpp run into encoder and decoder time
+///
+/// whisper.cpp calls the encoder-begin callback before encoding each 30 s
+/// window and the logits filter before sampling each token, so the gap from
+/// the former to the first of the latter is that window's encode time.
+///
+/// Since the clock owns the run's logits filter, hotword biasing is applied
+/// from the same callback.
+#[derive(Default)]
+pub struct StageClock<'a> {
+    encoding_since: Mutex<Option<Instant>>,
+    encode: Mutex<Duration>,
+    hotwords: Option<&'a HotwordBias>,
+}
+
+impl<'a> StageClock<'a> {
+    pub fn with_hotwords(hotwords: Option<&'a HotwordBias>) -> Self {
+        Self {
+            hotwords,
+            ..Default::default()
+        }
+    }
+
+    /// Point the run's callbacks at this clock
+    ///
+    /// The clock must outlive the `state.full()` call using `params`.
+    pub fn install(&self, params: &mut FullParams) {
+        let user_data = self as *const StageClock as *mut c_void;
+        unsafe {
+            params.set_start_encoder_callback(Some(encoder_begin));
+            params.set_start_encoder_callback_user_data(user_data);
+            params.set_filter_logits_callback(Some(logits_filter));
+            params.set_filter_logits_callback_user_data(user_data);
+        }
+    }
+}
+
+unsafe extern "C" fn encoder_begin(
+    _ctx: *mut whisper_context,
+    _state: *mut whisper_state,
+    user_data: *mut c_void,
+) -> bool {
+    let clock = &*(user_data as *const StageClock);
+    *clock.encoding_since.lock().unwrap() = Some(Instant::now());
+    true
+}
+
+unsafe extern "C" fn logits_filter(
+    _ctx: *mut whisper_context,
+    _state: *mut whisper_state,
+    tokens: *const whisper_token_data,
+    n_tokens: i32,
+    logits: *mut f32,
+    user_data: *mut c_void,
+) {
+    let clock = &*(user_data as *const StageClock);
+    if let Some(started) = clock.encoding_since.lock().unwrap().take() {
+        *clock.encode.lock().unwrap() += started.elapsed();
+    }
+    if let Some(hotwords) = clock.hotwords {
+        hotwords.apply(tokens, n_tokens, logits);
+    }
+}
+
+/// Samples the app's resident memory on a background thread to find its peak
+pub struct MemoryMonitor {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<Option<u64>>>,
+}
+
+impl MemoryMonitor {
+    pub fn start() -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let handle = sysinfo::get_current_pid().ok().map(|pid| {
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                let mut system = System::new();
+                let mut peak = None;
+                loop {
+                    if let Some(memory) = process_memory(&mut system, pid) {
+                        peak = peak.max(Some(memory));
+                    }
+                    if stop.load(Ordering::Relaxed) {
+                        break peak;
+                    }
+                    std::thread::sleep(MEMORY_SAMPLE_INTERVAL);
+                }
+            })
+        });
+
+        Self { stop, handle }
+    }
+
+    /// Stop sampling and return the highest resident memory seen, in bytes
+    pub fn finish(mut self) -> Option<u64> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.handle.take()?.join().ok().flatten()
+    }
+}
+
+impl Drop for MemoryMonitor {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn process_memory(system: &mut System, pid: Pid) -> Option<u64> {
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        false,
+        ProcessRefreshKind::nothing().with_memory(),
+    );
+    system.process(pid).map(|process| process.memory())
+}
@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Per-user vocabulary (names, jargon, acronyms) that is prepended to every
+/// whisper.cpp prompt so domain-specific terms are spelled consistently.
+///
+/// Persisted as a JSON array in the app config directory and cached after the
+/// first read.
+pub struct Vocabulary {
+    words: Mutex<Option<Vec<String>>>,
+}
+
+impl Vocabulary {
+    pub fn new() -> Self {
+        Self {
+            words: Mutex::new(None),
+        }
+    }
+
+    /// Get the saved vocabulary, reading it from disk on first use
+    pub fn get(&self, app: &AppHandle) -> Result<Vec<String>, String> {
+        let mut words = self.words.lock().unwrap();
+        if let Some(words) = words.as_ref() {
+            return Ok(words.clone());
+        }
+
+        let path = vocabulary_path(app)?;
+        let loaded: Vec<String> = if path.exists() {
+            let contents = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read vocabulary: {}", e))?;
+            serde_json::from_str(&contents)
+                .map_err(|e| format!("Failed to parse vocabulary: {}", e))?
+        } else {
+            Vec::new()
+        };
+
+        *words = Some(loaded.clone());
+        Ok(loaded)
+    }
+
+    /// Replace the saved vocabulary, dropping blank and duplicate entries
+    pub fn set(&self, app: &AppHandle, words: Vec<String>) -> Result<Vec<String>, String> {
+        let mut cleaned: Vec<String> = Vec::with_capacity(words.len());
+        for word in words {
+            let word = word.trim().to_string();
+            if !word.is_empty() && !cleaned.contains(&word) {
+                cleaned.push(word);
+            }
+        }
+
+        let path = vocabulary_path(app)?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let contents = serde_json::to_string_pretty(&cleaned)
+            .map_err(|e| format!("Failed to serialize vocabulary: {}", e))?;
+        std::fs::write(&path, contents).map_err(|e| format!("Failed to save vocabulary: {}", e))?;
+
+        *self.words.lock().unwrap() = Some(cleaned.clone());
+        Ok(cleaned)
+    }
+}
+
+fn vocabulary_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_config_dir()
+        .map(|dir| dir.join("vocabulary.json"))
+        .map_err(|e| format!("Failed to resolve config directory: {}", e))
+}
+
+/// Combine the saved vocabulary with a per-request prompt
+///
+/// Whisper treats the prompt as preceding transcript, so the vocabulary is
+/// written as a plain comma-separated list ahead of the caller's own text.
+pub fn build_prompt(vocabulary: &[String], initial_prompt: Option<&str>) -> Option<String> {
+    let initial_prompt = initial_prompt.map(str::trim).filter(|p| !p.is_empty());
+
+    match (vocabulary.is_empty(), initial_prompt) {
+        (true, None) => None,
+        (true, Some(prompt)) => Some(prompt.to_string()),
+        (false, None) => Some(format!("{}.", vocabulary.join(", "))),
+        (false, Some(prompt)) => Some(format!("{}. {}", vocabulary.join(", "), prompt)),
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::Path;
+use tauri::Manager;
+
+use super::error::TranscriptionError;
+use super::postprocess::{self, PostProcessingOptions};
+use super::{convert_audio_for_whisper, extract_samples_from_wav};
+use crate::models::cache::record_model_use;
+
+/// Files every Vosk model folder contains
+const REQUIRED_FILES: [&str; 2] = ["am/final.mdl", "conf/model.conf"];
+
+pub use engine::VoskModels;
+
+/// Transcribe audio with a Vosk (Kaldi) model folder
+///
+/// Vosk's small models run comfortably on old CPUs where even whisper tiny
+/// can't keep up, at a noticeable accuracy cost. The last used model is kept
+/// loaded. Vosk support is an optional build feature (`--features vosk`,
+/// which needs libvosk at link time); without it this returns an error.
+#[tauri::command]
+pub async fn transcribe_audio_vosk(
+    audio_data: Vec<u8>,
+    model_path: String,
+    post_processing: Option<PostProcessingOptions>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, TranscriptionError> {
+    if !is_vosk_model(Path::new(&model_path)) {
+        return Err(TranscriptionError::ModelLoadError {
+            message: format!("{} is not a Vosk model folder", model_path),
+        });
+    }
+
+    // Loading a model and decoding both block for seconds
+    let task_app = app_handle.clone();
+    let task_model_path = model_path.clone();
+    let text = tokio::task::spawn_blocking(move || {
+        let wav_data = convert_audio_for_whisper(audio_data)?;
+        let samples = extract_samples_from_wav(wav_data)?;
+        if samples.is_empty() {
+            return Ok(String::new());
+        }
+        task_app
+            .state::<VoskModels>()
+            .transcribe(Path::new(&task_model_path), &samples)
+    })
+    .await
+    .map_err(|e| TranscriptionError::TranscriptionError {
+        message: format!("Task join error: {}", e),
+    })??;
+    record_model_use(&app_handle, &model_path);
+    Ok(postprocess::process_text(
+        &app_handle,
+        &text,
+        &post_processing.unwrap_or_default(),
+        None,
+    ))
+}
+
+/// Whether `path` is an extracted Vosk model folder
+pub fn is_vosk_model(path: &Path) -> bool {
+    path.is_dir() && REQUIRED_FILES.iter().all(|file| path.join(file).is_file())
+}
+
+#[cfg(feature = "vosk")]
+mod engine {
+    use std::path::{Path, PathBuf};
+    use std::sync::{Arc, Mutex};
+    use vosk::{Model, Recognizer};
+
+    use super::super::error::TranscriptionError;
+
+    /// The most recently used Vosk model, kept loaded between transcriptions
+    pub struct VoskModels {
+        loaded: Mutex<Option<(PathBuf, Arc<Model>)>>,
+    }
+
+    impl VoskModels {
+        pub fn new() -> Self {
+            Self {
+                loaded: Mutex::new(None),
+            }
+        }
+
+        fn get_or_load(&self, model_path: &Path) -> Result<Arc<Model>, TranscriptionError> {
+            let mut loaded = self.loaded.lock().unwrap();
+            if let Some((path, model)) = &*loaded {
+                if path == model_path {
+                    return Ok(model.clone());
+                }
+            }
+
+            let model = Model::new(model_path.to_string_lossy()).ok_or_else(|| {
+                TranscriptionError::ModelLoadError {
+                    message: format!("Failed to load Vosk model from {:?}", model_path),
+                }
+            })?;
+            let model = Arc::new(model);
+            *loaded = Some((model_path.to_path_buf(), model.clone()));
+            println!("[Vosk] Loaded model {:?}", model_path);
+            Ok(model)
+        }
+
+        /// Run 16 kHz mono samples through a fresh recognizer
+        pub fn transcribe(
+            &self,
+            model_path: &Path,
+            samples: &[f32],
+        ) -> Result<String, TranscriptionError> {
+            let model = self.get_or_load(model_path)?;
+            let mut recognizer = Recognizer::new(&model, 16000.0).ok_or_else(|| {
+                TranscriptionError::TranscriptionError {
+                    message: "Failed to create Vosk recognizer".to_string(),
+                }
+            })?;
+
+            let pcm: Vec<i16> = samples
+                .iter()
+                .map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+                .collect();
+            let _ = recognizer.accept_waveform(&pcm);
+
+            let text = recognizer
+                .final_result()
+                .single()
+                .map(|result| result.text.trim().to_string())
+                .unwrap_or_default();
+            Ok(text)
+        }
+    }
+}
+
+#[cfg(not(feature = "vosk"))]
+mod engine {
+    use std::path::Path;
+
+    use super::super::error::TranscriptionError;
+
+    /// Placeholder state for builds without the `vosk` feature
+    pub struct VoskModels;
+
+    impl VoskModels {
+        pub fn new() -> Self {
+            Self
+        }
+
+        pub fn transcribe(
+            &self,
+            _model_path: &Path,
+            _samples: &[f32],
+        ) -> Result<String, TranscriptionError> {
+            Err(TranscriptionError::ModelLoadError {
+                message: "This build of Whispering was compiled without Vosk support"
+                    .to_string(),
+            })
+        }
+    }
+}
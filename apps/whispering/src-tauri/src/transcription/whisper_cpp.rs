@@ -0,0 +1,720 @@
+use super::chunking;
+use super::confidence::{self, SilenceEstimator};
+use super::error::TranscriptionError;
+use super::ggml;
+use super::hotwords::HotwordBias;
+use super::postprocess::PostProcessingOptions;
+use super::preprocess::PreprocessingOptions;
+use super::priority::{self, Priority};
+use super::stats::{InferenceTimings, StageClock};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+use whisper_rs::{
+    FullParams, SamplingStrategy, SegmentCallbackData, WhisperContext, WhisperContextParameters,
+};
+
+/// Event emitted while whisper.cpp works through a transcription job
+pub const PROGRESS_EVENT: &str = "transcription-progress";
+
+/// Event emitted for each segment as soon as whisper.cpp has decoded it
+pub const SEGMENT_EVENT: &str = "transcription-segment";
+
+/// Progress update for a single transcription job - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionProgress {
+    pub job_id: String,
+    pub percent: i32,
+    pub elapsed_ms: u64,
+    pub eta_ms: Option<u64>,
+}
+
+/// A single decoded segment for a transcription job - sent to frontend
+///
+/// `t0` and `t1` are offsets into the audio in milliseconds.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionSegment {
+    pub job_id: String,
+    pub index: i32,
+    pub text: String,
+    pub t0: i64,
+    pub t1: i64,
+}
+
+/// Whether whisper.cpp keeps the spoken language or translates into English
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Task {
+    #[default]
+    Transcribe,
+    Translate,
+}
+
+/// Decoder tuning for power users - any field left unset keeps whisper.cpp's default
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodingOptions {
+    /// Initial sampling temperature (0.0 is deterministic)
+    pub temperature: Option<f32>,
+    /// Step added to the temperature on each fallback retry; 0.0 disables fallback
+    pub temperature_increment: Option<f32>,
+    /// Switches to beam search with this many beams when greater than 1
+    pub beam_size: Option<i32>,
+    /// Number of candidates sampled per temperature when using greedy decoding
+    pub best_of: Option<i32>,
+    /// Retry at a higher temperature when token entropy exceeds this value
+    pub entropy_threshold: Option<f32>,
+    /// Don't feed previously decoded text back in as context (openai-whisper's
+    /// `condition_on_previous_text = False`), which stops one hallucinated
+    /// phrase looping through a long recording
+    pub no_context: Option<bool>,
+    /// Treat a window as silent when its no-speech probability exceeds this
+    /// and its average log probability is below `logprob_threshold` (default 0.2)
+    pub no_speech_threshold: Option<f32>,
+    /// Retry at a higher temperature when a window's average log probability
+    /// falls below this value (default -1.0)
+    pub logprob_threshold: Option<f32>,
+    /// Stop whisper from starting a window with a blank token (default true)
+    pub suppress_blank: Option<bool>,
+    /// Stop whisper from emitting non-speech tokens such as "[music]" or
+    /// speaker tags (default true)
+    pub suppress_non_speech_tokens: Option<bool>,
+    /// Names and product terms to bias decoding towards throughout the
+    /// recording, where `initial_prompt` only steers the first window
+    pub hotwords: Option<Vec<String>>,
+    /// Logit bonus for hotword tokens (default 2.0); raise it for terms that
+    /// are still missed, lower it if hotwords start appearing unbidden
+    pub hotword_boost: Option<f32>,
+}
+
+/// How a transcription is spread across the machine
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerformanceOptions {
+    /// Split long audio at pauses and decode this many chunks concurrently
+    pub parallel_chunks: Option<usize>,
+    /// Total CPU threads whisper.cpp may use, shared between parallel chunks
+    pub threads: Option<usize>,
+    /// Lower the CPU priority so background jobs leave the machine responsive
+    pub priority: Option<Priority>,
+}
+
+/// Post-decode removal of segments whisper.cpp tends to invent in silence or
+/// noise - any field left unset keeps the built-in heuristics
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HallucinationFilter {
+    /// Set to false to keep every decoded segment
+    pub enabled: Option<bool>,
+    /// Extra phrases to drop whenever a segment consists of nothing else
+    pub blocklist: Option<Vec<String>>,
+    /// Treat segments as over silence when more than this fraction of their
+    /// audio is silent (default 0.6)
+    pub max_silence_ratio: Option<f32>,
+    /// Drop segments that compress better than this, i.e. loops (default 2.4)
+    pub max_compression_ratio: Option<f32>,
+}
+
+/// Phrases absorbed from subtitled video training data, almost never spoken
+/// into a dictation app
+const HALLUCINATED_PHRASES: &[&str] = &[
+    "thank you for watching",
+    "thanks for watching",
+    "thank you so much for watching",
+    "please subscribe",
+    "like and subscribe",
+    "please like and subscribe",
+    "dont forget to like and subscribe",
+    "subtitles by the amaraorg community",
+    "subtitles by",
+    "transcription by castingwords",
+    "see you in the next video",
+];
+
+/// Short outputs that are only suspicious when the audio under them is quiet
+const SILENCE_PHRASES: &[&str] = &["you", "thank you", "thanks", "bye", "okay", "music"];
+
+/// Log probability below which a mostly silent segment is treated as invented,
+/// as in Whisper's reference `logprob_threshold`
+const LOW_LOGPROB: f32 = -1.0;
+
+/// Options for a single whisper.cpp run
+#[derive(Debug, Clone, Default)]
+pub struct WhisperOptions {
+    pub language: Option<String>,
+    pub task: Task,
+    /// Text whisper treats as preceding context, used to steer spelling
+    pub initial_prompt: Option<String>,
+    pub decoding: DecodingOptions,
+    pub performance: PerformanceOptions,
+    pub preprocessing: PreprocessingOptions,
+    pub hallucination_filter: HallucinationFilter,
+    pub post_processing: PostProcessingOptions,
+}
+
+/// A decoded segment of the final transcript, with offsets in milliseconds
+///
+/// The confidence fields let the frontend flag passages for manual review:
+/// a low `avg_logprob`, high `silence_ratio`, or high `compression_ratio`
+/// (repetitive text) all suggest the segment is unreliable.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Segment {
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// Mean log probability of the segment's text tokens (0.0 is certain)
+    pub avg_logprob: f32,
+    /// Fraction of the segment's audio below the recording's silence level
+    /// (0.0 to 1.0)
+    ///
+    /// Measured from the audio's energy, not by whisper: the whisper.cpp
+    /// bundled with whisper-rs-sys 0.11 has no per-segment no-speech
+    /// probability (`whisper_full_get_segment_no_speech_prob` came later), so
+    /// whisper's own `no_speech_prob` can't be reported here.
+    pub silence_ratio: f32,
+    /// Raw to zlib-compressed text length; above ~2.4 indicates repetition
+    pub compression_ratio: f32,
+}
+
+/// Probability that the audio is in a given language - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanguageProbability {
+    pub code: String,
+    pub name: String,
+    pub probability: f32,
+}
+
+/// Which device whisper.cpp loads the model onto
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum ComputeDevice {
+    /// Use whatever GPU backend whisper.cpp was built with, on its first device
+    #[default]
+    Auto,
+    /// Force CPU inference, e.g. when GPU drivers misbehave
+    Cpu,
+    /// Pin inference to a specific GPU on multi-GPU machines
+    Gpu { index: i32 },
+}
+
+/// Compute backends compiled into whisper.cpp - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComputeBackends {
+    pub cpu: bool,
+    pub metal: bool,
+    pub cuda: bool,
+    pub vulkan: bool,
+    /// Raw `whisper_print_system_info` output, useful in bug reports
+    pub system_info: String,
+}
+
+/// Identifies a transcription job so its events can be routed to the right listener
+#[derive(Clone)]
+pub struct TranscriptionJob {
+    pub app: AppHandle,
+    pub id: String,
+    pub cancelled: Arc<AtomicBool>,
+}
+
+/// Load a GGML whisper model into a fresh whisper.cpp context
+pub fn load_context(model_path: &Path, device: ComputeDevice) -> Result<WhisperContext, String> {
+    let path = model_path
+        .to_str()
+        .ok_or_else(|| format!("Model path is not valid UTF-8: {:?}", model_path))?;
+
+    // whisper.cpp's own loader decides what it can read; the header is only
+    // for the log
+    match ggml::read_header(model_path) {
+        Ok(header) => println!(
+            "[Whisper] Loading {:?} ({} weights, {} text layers)",
+            model_path,
+            header.quantization(),
+            header.n_text_layer
+        ),
+        Err(e) => eprintln!(
+            "[Whisper] Couldn't read the header of {:?}, loading anyway: {}",
+            model_path, e
+        ),
+    }
+
+    let mut params = WhisperContextParameters::default();
+    match device {
+        ComputeDevice::Auto => {}
+        ComputeDevice::Cpu => {
+            params.use_gpu(false);
+        }
+        ComputeDevice::Gpu { index } => {
+            params.use_gpu(true).gpu_device(index);
+        }
+    }
+
+    WhisperContext::new_with_params(path, params).map_err(|e| {
+        if ggml::is_gguf(model_path) {
+            ggml::GGUF_UNSUPPORTED.to_string()
+        } else {
+            format!("Failed to load Whisper model: {}", e)
+        }
+    })
+}
+
+/// Report which compute backends whisper.cpp was built with
+///
+/// whisper.cpp only exposes this as a human-readable string, whose layout has
+/// changed between releases ("CUDA = 1" vs a "CUDA : ..." backend section), so
+/// both forms are accepted.
+pub fn compute_backends() -> ComputeBackends {
+    let system_info = whisper_rs::print_system_info().to_string();
+    let has = |name: &str| {
+        system_info.contains(&format!("{} = 1", name))
+            || system_info.contains(&format!("{} :", name))
+    };
+
+    ComputeBackends {
+        cpu: true,
+        metal: has("METAL"),
+        cuda: has("CUDA"),
+        vulkan: has("VULKAN"),
+        system_info,
+    }
+}
+
+/// Run whisper.cpp over 16kHz mono samples and return the decoded segments
+///
+/// With `parallel_chunks` above 1, long audio is transcribed with
+/// `transcribe_chunked`; otherwise it is decoded in a single pass. The result
+/// is passed through `filter_hallucinations`; segments streamed to the
+/// frontend while decoding are not filtered. Encoder and decoder time is
+/// added to `timings`.
+pub fn transcribe(
+    context: &WhisperContext,
+    samples: &[f32],
+    options: &WhisperOptions,
+    job: Option<TranscriptionJob>,
+    timings: &InferenceTimings,
+) -> Result<Vec<Segment>, TranscriptionError> {
+    let performance = &options.performance;
+    let segments = priority::run_with_priority(performance.priority.unwrap_or_default(), || {
+        match performance.parallel_chunks {
+            Some(workers) if workers > 1 => {
+                transcribe_chunked(context, samples, options, job, timings, workers)
+            }
+            _ => transcribe_whole(context, samples, options, job, timings),
+        }
+    })?;
+    Ok(filter_hallucinations(segments, &options.hallucination_filter))
+}
+
+/// Drop segments that look like whisper.cpp hallucinations
+///
+/// A segment is removed when its text is a known stock phrase (or on the
+/// user's blocklist), when it repeats itself enough to compress like a loop,
+/// when it sits over silence and was decoded with low confidence, or when it
+/// repeats the previous segment word for word over silence or with low
+/// confidence. People do repeat themselves, so confident repeats are kept.
+fn filter_hallucinations(segments: Vec<Segment>, filter: &HallucinationFilter) -> Vec<Segment> {
+    if !filter.enabled.unwrap_or(true) {
+        return segments;
+    }
+
+    let max_silence_ratio = filter.max_silence_ratio.unwrap_or(0.6);
+    let max_compression_ratio = filter.max_compression_ratio.unwrap_or(2.4);
+    let blocklist: Vec<String> = filter
+        .blocklist
+        .iter()
+        .flatten()
+        .map(|phrase| normalize_phrase(phrase))
+        .filter(|phrase| !phrase.is_empty())
+        .collect();
+
+    let mut kept: Vec<Segment> = Vec::with_capacity(segments.len());
+    let mut previous_text = String::new();
+    for segment in segments {
+        let text = normalize_phrase(&segment.text);
+        let over_silence = segment.silence_ratio > max_silence_ratio;
+
+        let reason = if text.is_empty() {
+            None
+        } else if HALLUCINATED_PHRASES.contains(&text.as_str()) || blocklist.contains(&text) {
+            Some("blocklisted phrase")
+        } else if segment.compression_ratio > max_compression_ratio {
+            Some("repetitive text")
+        } else if over_silence && SILENCE_PHRASES.contains(&text.as_str()) {
+            Some("stock phrase over silence")
+        } else if over_silence && segment.avg_logprob < LOW_LOGPROB {
+            Some("low confidence over silence")
+        } else if text == previous_text && (over_silence || segment.avg_logprob < LOW_LOGPROB) {
+            Some("repeats previous segment")
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            println!(
+                "[Whisper] Dropped segment at {}ms ({}): {:?}",
+                segment.start_ms,
+                reason,
+                segment.text.trim()
+            );
+            continue;
+        }
+
+        if !text.is_empty() {
+            previous_text = text;
+        }
+        kept.push(segment);
+    }
+
+    kept
+}
+
+/// Lowercase and strip punctuation so "Thanks for watching!" matches the list
+pub(super) fn normalize_phrase(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Decode the whole recording in one whisper.cpp run
+///
+/// When a job is given, whisper.cpp's progress callback is forwarded to the
+/// frontend as `transcription-progress` events keyed by the job ID, with a
+/// naive linear ETA based on the time spent so far, and every decoded segment
+/// is emitted as a `transcription-segment` event so long files show text
+/// incrementally. The job's cancellation flag is polled through whisper.cpp's
+/// abort callback between decoding steps.
+fn transcribe_whole(
+    context: &WhisperContext,
+    samples: &[f32],
+    options: &WhisperOptions,
+    job: Option<TranscriptionJob>,
+    timings: &InferenceTimings,
+) -> Result<Vec<Segment>, TranscriptionError> {
+    let mut params = full_params(options);
+    let hotwords = HotwordBias::new(context, &options.decoding)?;
+    let cancelled = job.as_ref().map(|job| job.cancelled.clone());
+
+    if let Some(cancelled) = cancelled.clone() {
+        params.set_abort_callback_safe(move || cancelled.load(Ordering::Relaxed));
+    }
+
+    if let Some(job) = job.clone() {
+        params.set_segment_callback_safe(move |segment: SegmentCallbackData| {
+            // whisper.cpp timestamps are in centiseconds
+            let _ = job.app.emit(
+                SEGMENT_EVENT,
+                TranscriptionSegment {
+                    job_id: job.id.clone(),
+                    index: segment.segment,
+                    text: segment.text.trim().to_string(),
+                    t0: segment.start_timestamp * 10,
+                    t1: segment.end_timestamp * 10,
+                },
+            );
+        });
+    }
+
+    if let Some(job) = job {
+        let started = Instant::now();
+        params.set_progress_callback_safe(move |percent: i32| {
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            emit_progress(&job, percent, elapsed_ms);
+        });
+    }
+
+    run_full(
+        context,
+        params,
+        samples,
+        cancelled.as_deref(),
+        hotwords.as_ref(),
+        timings,
+    )
+}
+
+/// Transcribe long audio by splitting it at pauses and decoding the chunks on
+/// several whisper.cpp states at once
+///
+/// All states share the loaded model weights, so extra workers only cost
+/// their decoding buffers. Available CPU threads are divided between workers,
+/// and the overlapping edges of neighbouring chunks are de-duplicated when the
+/// results are stitched back together. Progress is reported per finished
+/// chunk; segment events are not emitted since chunks finish out of order.
+fn transcribe_chunked(
+    context: &WhisperContext,
+    samples: &[f32],
+    options: &WhisperOptions,
+    job: Option<TranscriptionJob>,
+    timings: &InferenceTimings,
+    workers: usize,
+) -> Result<Vec<Segment>, TranscriptionError> {
+    let chunks = chunking::split_on_pauses(samples);
+    if chunks.len() < 2 {
+        return transcribe_whole(context, samples, options, job, timings);
+    }
+
+    let workers = workers.min(chunks.len());
+    let total_threads = options.performance.threads.unwrap_or_else(|| {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    });
+    let threads_per_worker = (total_threads / workers).max(1);
+    println!(
+        "[Whisper] Transcribing {} chunks on {} workers with {} threads each",
+        chunks.len(),
+        workers,
+        threads_per_worker
+    );
+
+    let hotwords = HotwordBias::new(context, &options.decoding)?;
+    let cancelled = job.as_ref().map(|job| job.cancelled.clone());
+    let started = Instant::now();
+    let next_chunk = AtomicUsize::new(0);
+    let finished_chunks = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<Vec<Segment>, TranscriptionError>>>> =
+        Mutex::new((0..chunks.len()).map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next_chunk.fetch_add(1, Ordering::Relaxed);
+                if index >= chunks.len()
+                    || cancelled.as_ref().map_or(false, |flag| flag.load(Ordering::Relaxed))
+                {
+                    break;
+                }
+
+                let chunk = &chunks[index];
+                let mut params = full_params(options);
+                params.set_n_threads(threads_per_worker as i32);
+                if let Some(flag) = cancelled.clone() {
+                    params.set_abort_callback_safe(move || flag.load(Ordering::Relaxed));
+                }
+
+                let offset_ms = chunking::samples_to_ms(chunk.decode.start);
+                let result = run_full(
+                    context,
+                    params,
+                    &samples[chunk.decode.clone()],
+                    cancelled.as_deref(),
+                    hotwords.as_ref(),
+                    timings,
+                )
+                .map(|segments| {
+                    segments
+                        .into_iter()
+                        .map(|segment| Segment {
+                            start_ms: segment.start_ms + offset_ms,
+                            end_ms: segment.end_ms + offset_ms,
+                            ..segment
+                        })
+                        .collect()
+                });
+                results.lock().unwrap()[index] = Some(result);
+
+                let finished = finished_chunks.fetch_add(1, Ordering::Relaxed) + 1;
+                if let Some(job) = &job {
+                    let percent = (finished * 100 / chunks.len()) as i32;
+                    emit_progress(job, percent, started.elapsed().as_millis() as u64);
+                }
+            });
+        }
+    });
+
+    if cancelled.map_or(false, |flag| flag.load(Ordering::Relaxed)) {
+        return Err(TranscriptionError::CancelledError {
+            message: "Transcription was cancelled".to_string(),
+        });
+    }
+
+    let mut chunk_segments = Vec::with_capacity(chunks.len());
+    for result in results.into_inner().unwrap() {
+        match result {
+            Some(result) => chunk_segments.push(result?),
+            None => {
+                return Err(TranscriptionError::TranscriptionError {
+                    message: "A chunk was never transcribed".to_string(),
+                })
+            }
+        }
+    }
+
+    Ok(chunking::stitch(&chunks, chunk_segments))
+}
+
+fn emit_progress(job: &TranscriptionJob, percent: i32, elapsed_ms: u64) {
+    let eta_ms = if percent > 0 && percent < 100 {
+        Some(elapsed_ms * (100 - percent) as u64 / percent as u64)
+    } else {
+        None
+    };
+
+    let _ = job.app.emit(
+        PROGRESS_EVENT,
+        TranscriptionProgress {
+            job_id: job.id.clone(),
+            percent,
+            elapsed_ms,
+            eta_ms,
+        },
+    );
+}
+
+/// Build whisper.cpp parameters for the requested language, task, and decoder settings
+fn full_params(options: &WhisperOptions) -> FullParams<'_, '_> {
+    let decoding = &options.decoding;
+    let strategy = match decoding.beam_size {
+        Some(beam_size) if beam_size > 1 => SamplingStrategy::BeamSearch {
+            beam_size,
+            patience: -1.0,
+        },
+        _ => SamplingStrategy::Greedy {
+            best_of: decoding.best_of.unwrap_or(1).max(1),
+        },
+    };
+
+    let mut params = FullParams::new(strategy);
+    params.set_language(options.language.as_deref());
+    params.set_translate(options.task == Task::Translate);
+    if let Some(prompt) = &options.initial_prompt {
+        params.set_initial_prompt(prompt);
+    }
+    if let Some(temperature) = decoding.temperature {
+        params.set_temperature(temperature);
+    }
+    if let Some(increment) = decoding.temperature_increment {
+        params.set_temperature_inc(increment);
+    }
+    if let Some(threshold) = decoding.entropy_threshold {
+        params.set_entropy_thold(threshold);
+    }
+    if let Some(no_context) = decoding.no_context {
+        params.set_no_context(no_context);
+    }
+    if let Some(threshold) = decoding.logprob_threshold {
+        params.set_logprob_thold(threshold);
+    }
+    params.set_print_special(false);
+    params.set_print_progress(false);
+    params.set_print_realtime(false);
+    params.set_print_timestamps(false);
+    params.set_suppress_blank(decoding.suppress_blank.unwrap_or(true));
+    params.set_suppress_non_speech_tokens(decoding.suppress_non_speech_tokens.unwrap_or(true));
+    params.set_no_speech_thold(decoding.no_speech_threshold.unwrap_or(0.2));
+    if let Some(threads) = options.performance.threads {
+        params.set_n_threads(threads.max(1) as i32);
+    }
+    params
+}
+
+/// Decode samples on a fresh whisper.cpp state and collect the segments
+///
+/// `cancelled` is checked after the run because an aborted run surfaces as a
+/// generic whisper error.
+fn run_full(
+    context: &WhisperContext,
+    mut params: FullParams,
+    samples: &[f32],
+    cancelled: Option<&AtomicBool>,
+    hotwords: Option<&HotwordBias>,
+    timings: &InferenceTimings,
+) -> Result<Vec<Segment>, TranscriptionError> {
+    let mut state = context
+        .create_state()
+        .map_err(|e| TranscriptionError::TranscriptionError {
+            message: format!("Failed to create whisper state: {}", e),
+        })?;
+
+    let clock = StageClock::with_hotwords(hotwords);
+    clock.install(&mut params);
+    let started = Instant::now();
+    let full_result = state.full(params, samples);
+    timings.add_run(&clock, started.elapsed());
+    if cancelled.map_or(false, |flag| flag.load(Ordering::Relaxed)) {
+        return Err(TranscriptionError::CancelledError {
+            message: "Transcription was cancelled".to_string(),
+        });
+    }
+    full_result.map_err(|e| TranscriptionError::TranscriptionError {
+        message: e.to_string(),
+    })?;
+
+    let segment_count = state
+        .full_n_segments()
+        .map_err(|e| TranscriptionError::TranscriptionError {
+            message: e.to_string(),
+        })?;
+
+    let to_error = |e: whisper_rs::WhisperError| TranscriptionError::TranscriptionError {
+        message: e.to_string(),
+    };
+
+    let eot = context.token_eot();
+    let silence = SilenceEstimator::new(samples);
+
+    let mut segments = Vec::with_capacity(segment_count.max(0) as usize);
+    for segment in 0..segment_count {
+        let text = state.full_get_segment_text_lossy(segment).map_err(to_error)?;
+        let start_ms = state.full_get_segment_t0(segment).map_err(to_error)? * 10;
+        let end_ms = state.full_get_segment_t1(segment).map_err(to_error)? * 10;
+
+        segments.push(Segment {
+            avg_logprob: confidence::average_logprob(&state, segment, eot).map_err(to_error)?,
+            silence_ratio: silence.silence_ratio(start_ms, end_ms),
+            compression_ratio: confidence::compression_ratio(&text),
+            text,
+            start_ms,
+            end_ms,
+        });
+    }
+
+    Ok(segments)
+}
+
+/// Run whisper.cpp's language identification over the first 30 seconds of audio
+///
+/// Returns every language whisper knows about, ranked from most to least likely.
+pub fn detect_language(
+    context: &WhisperContext,
+    samples: &[f32],
+) -> Result<Vec<LanguageProbability>, TranscriptionError> {
+    let to_error = |e: whisper_rs::WhisperError| TranscriptionError::TranscriptionError {
+        message: format!("Language detection failed: {}", e),
+    };
+
+    // Whisper only looks at a single 30 second window when identifying language
+    let window = &samples[..samples.len().min(30 * 16000)];
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get().min(4))
+        .unwrap_or(1);
+
+    let mut state = context.create_state().map_err(to_error)?;
+    state.pcm_to_mel(window, threads).map_err(to_error)?;
+    let (_, probabilities) = state.lang_detect(0, threads).map_err(to_error)?;
+
+    let mut ranked: Vec<LanguageProbability> = probabilities
+        .iter()
+        .enumerate()
+        .filter_map(|(id, &probability)| {
+            let id = id as i32;
+            Some(LanguageProbability {
+                code: whisper_rs::get_lang_str(id)?.to_string(),
+                name: whisper_rs::get_lang_str_full(id)?.to_string(),
+                probability,
+            })
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.probability.total_cmp(&a.probability));
+
+    Ok(ranked)
+}
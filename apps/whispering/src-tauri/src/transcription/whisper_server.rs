@@ -0,0 +1,266 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::convert_audio_for_whisper;
+use super::error::TranscriptionError;
+use super::postprocess::{self, PostProcessingOptions};
+use crate::ports::{any_free_port, port_in_use, PortError};
+use crate::supervisor::{ProcessConfig, ProcessState, Supervisor};
+
+/// Supervisor id of the managed server, whose state changes arrive as
+/// `supervised-process` events with this id
+pub const WHISPER_SERVER_ID: &str = "whisper-server";
+
+/// Looked up on PATH when no binary is given
+const DEFAULT_BINARY: &str = "whisper-server";
+
+/// Large models can take a while to load before the server answers
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Give up restarting a server that keeps crashing
+const MAX_RESTARTS: u32 = 5;
+
+/// State of the managed whisper-server process - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperServerStatus {
+    pub running: bool,
+    pub pid: Option<u32>,
+    pub port: Option<u16>,
+    pub model_path: Option<String>,
+    pub restarts: u32,
+}
+
+/// How the supervisor runs `whisper-server` for a model
+///
+/// The server only listens once its model has loaded, and builds differ on
+/// whether they have `/health`, so accepting connections counts as healthy.
+fn process_config(
+    binary: String,
+    model_path: &str,
+    threads: Option<usize>,
+    port: u16,
+) -> ProcessConfig {
+    let mut args: Vec<String> = ["--model", model_path, "--host", "127.0.0.1", "--port"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    args.push(port.to_string());
+    if let Some(threads) = threads {
+        args.extend(["--threads".to_string(), threads.to_string()]);
+    }
+    ProcessConfig {
+        id: WHISPER_SERVER_ID.to_string(),
+        program: binary,
+        args,
+        env: HashMap::new(),
+        cwd: None,
+        port: Some(port),
+        health_path: None,
+        restart: true,
+        max_restarts: Some(MAX_RESTARTS),
+        startup_timeout_ms: Some(STARTUP_TIMEOUT.as_millis() as u64),
+        shutdown_timeout_ms: None,
+    }
+}
+
+/// The model a server config loads, read back from its arguments
+fn model_path(config: &ProcessConfig) -> Option<String> {
+    let position = config.args.iter().position(|arg| arg == "--model")?;
+    config.args.get(position + 1).cloned()
+}
+
+pub fn whisper_server_status(app_handle: &AppHandle) -> WhisperServerStatus {
+    let supervisor = app_handle.state::<Supervisor>();
+    let process = supervisor.get(WHISPER_SERVER_ID);
+    let config = supervisor.config(WHISPER_SERVER_ID);
+    WhisperServerStatus {
+        running: process
+            .as_ref()
+            .is_some_and(|process| process.state == ProcessState::Running),
+        pid: process.as_ref().and_then(|process| process.pid),
+        port: process.as_ref().and_then(|process| process.port),
+        model_path: config.as_ref().and_then(model_path),
+        restarts: process.map_or(0, |process| process.restarts),
+    }
+}
+
+/// Port of the server while it's running and ready
+pub fn whisper_server_port(app_handle: &AppHandle) -> Option<u16> {
+    let status = whisper_server_status(app_handle);
+    status.port.filter(|_| status.running)
+}
+
+/// The port to run the server on
+///
+/// A port the server already holds is reused. A `requested` port that's
+/// taken fails with the reason; without one, the `previous` server's port is
+/// kept when it's free and the OS picks one otherwise.
+fn pick_port(
+    app_handle: &AppHandle,
+    requested: Option<u16>,
+    previous: Option<u16>,
+) -> Result<u16, PortError> {
+    let supervisor = app_handle.state::<Supervisor>();
+    let held = |port: u16| supervisor.port_owner(port).as_deref() == Some(WHISPER_SERVER_ID);
+    match (requested, previous) {
+        (Some(port), _) | (None, Some(port)) if held(port) => Ok(port),
+        (Some(port), _) => match port_in_use(app_handle, port, None) {
+            Some(error) => Err(error),
+            None => Ok(port),
+        },
+        (None, Some(port)) if port_in_use(app_handle, port, None).is_none() => Ok(port),
+        (None, _) => any_free_port(),
+    }
+}
+
+/// Start a managed `whisper-server` for the given model
+///
+/// `binary_path` defaults to `whisper-server` on PATH and `port` to any free
+/// local port; a requested port that's taken fails with the reason. Calling
+/// this again with the same model returns the running server, so the
+/// frontend can call it on every launch and get a warm model. The supervisor restarts the server if it crashes or hangs.
+#[tauri::command]
+pub async fn start_whisper_server(
+    model_path: String,
+    binary_path: Option<String>,
+    port: Option<u16>,
+    threads: Option<usize>,
+    app_handle: AppHandle,
+) -> Result<WhisperServerStatus, String> {
+    let binary = binary_path.unwrap_or_else(|| DEFAULT_BINARY.to_string());
+
+    tokio::task::spawn_blocking(move || {
+        let supervisor = app_handle.state::<Supervisor>();
+        // Keep the port of an earlier server, so the same config reuses it
+        let previous = supervisor.config(WHISPER_SERVER_ID).and_then(|config| config.port);
+        let port = pick_port(&app_handle, port, previous).map_err(|e| e.to_string())?;
+        supervisor.start(process_config(binary, &model_path, threads, port), &app_handle)?;
+        Ok(whisper_server_status(&app_handle))
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+/// Stop the managed `whisper-server`, if one is running
+#[tauri::command]
+pub async fn stop_whisper_server(app_handle: AppHandle) -> Result<(), String> {
+    tokio::task::spawn_blocking(move || {
+        let supervisor = app_handle.state::<Supervisor>();
+        if supervisor.get(WHISPER_SERVER_ID).is_none() {
+            return Ok(());
+        }
+        supervisor.stop(WHISPER_SERVER_ID, &app_handle)
+    })
+    .await
+    .map_err(|e| format!("Task join error: {}", e))?
+}
+
+#[tauri::command]
+pub fn get_whisper_server_status(app_handle: AppHandle) -> WhisperServerStatus {
+    whisper_server_status(&app_handle)
+}
+
+/// Transcribe audio through the managed `whisper-server`
+///
+/// Sends the recording to the server's `/inference` endpoint, so the model
+/// loaded by `start_whisper_server` is reused instead of loading in-process.
+#[tauri::command]
+pub async fn transcribe_audio_whisper_server(
+    audio_data: Vec<u8>,
+    language: Option<String>,
+    initial_prompt: Option<String>,
+    post_processing: Option<PostProcessingOptions>,
+    app_handle: AppHandle,
+) -> Result<String, TranscriptionError> {
+    let port =
+        whisper_server_port(&app_handle).ok_or_else(|| TranscriptionError::ModelLoadError {
+            message: "whisper-server isn't running".to_string(),
+        })?;
+    let wav_data = convert_audio_for_whisper(audio_data)?;
+
+    let mut fields = vec![("response_format", "json".to_string())];
+    if let Some(language) = &language {
+        fields.push(("language", language.clone()));
+    }
+    if let Some(prompt) = initial_prompt {
+        fields.push(("prompt", prompt));
+    }
+    let (content_type, body) = multipart_body(&wav_data, &fields);
+
+    let response = tauri_plugin_http::reqwest::Client::new()
+        .post(format!("http://127.0.0.1:{}/inference", port))
+        .header("Content-Type", content_type)
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| TranscriptionError::TranscriptionError {
+            message: format!("Failed to reach whisper-server: {}", e),
+        })?;
+
+    let status = response.status();
+    let result: serde_json::Value = response.json().await.map_err(|e| {
+        TranscriptionError::TranscriptionError {
+            message: format!("Invalid response from whisper-server: {}", e),
+        }
+    })?;
+
+    if let Some(error) = result.get("error").and_then(|error| error.as_str()) {
+        return Err(TranscriptionError::TranscriptionError {
+            message: format!("whisper-server error: {}", error),
+        });
+    }
+    if !status.is_success() {
+        return Err(TranscriptionError::TranscriptionError {
+            message: format!("whisper-server returned {}", status),
+        });
+    }
+
+    let text = result
+        .get("text")
+        .and_then(|text| text.as_str())
+        .unwrap_or_default();
+    Ok(postprocess::process_text(
+        &app_handle,
+        text,
+        &post_processing.unwrap_or_default(),
+        language.as_deref(),
+    ))
+}
+
+/// Encode the audio file and text fields as `multipart/form-data`
+fn multipart_body(wav_data: &[u8], fields: &[(&str, String)]) -> (String, Vec<u8>) {
+    let boundary = format!("whispering-{:x}", boundary_suffix());
+    let mut body = Vec::with_capacity(wav_data.len() + 512);
+
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                boundary, name, value
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"audio.wav\"\r\n\
+             Content-Type: audio/wav\r\n\r\n",
+            boundary
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(wav_data);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+
+    (format!("multipart/form-data; boundary={}", boundary), body)
+}
+
+/// Unique enough for a multipart boundary without pulling in a RNG
+fn boundary_suffix() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos())
+}
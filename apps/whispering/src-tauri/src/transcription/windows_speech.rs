@@ -0,0 +1,180 @@
+use super::convert_audio_for_whisper;
+use super::error::TranscriptionError;
+use super::postprocess::{self, PostProcessingOptions};
+
+/// Transcribe audio with the speech recognizer built into Windows
+///
+/// Uses the in-process SAPI dictation recognizer, which runs fully offline
+/// with the speech language packs installed in Windows settings, so users get
+/// a local option without downloading a model. (The WinRT
+/// `Windows.Media.SpeechRecognition` API only listens to the live microphone,
+/// so it can't transcribe recordings.) Accuracy is well below Whisper's.
+/// Returns an error on other platforms.
+#[tauri::command]
+pub async fn transcribe_audio_windows(
+    audio_data: Vec<u8>,
+    post_processing: Option<PostProcessingOptions>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, TranscriptionError> {
+    let wav_data = convert_audio_for_whisper(audio_data)?;
+
+    let text = tokio::task::spawn_blocking(move || platform::transcribe(wav_data))
+        .await
+        .map_err(|e| TranscriptionError::TranscriptionError {
+            message: format!("Task join error: {}", e),
+        })??;
+    Ok(postprocess::process_text(
+        &app_handle,
+        &text,
+        &post_processing.unwrap_or_default(),
+        None,
+    ))
+}
+
+#[cfg(windows)]
+mod platform {
+    use std::io::Write;
+    use std::time::{Duration, Instant};
+    use windows::core::{Interface, HSTRING, PCWSTR, PWSTR};
+    use windows::Win32::Media::Speech::{
+        ISpRecoContext, ISpRecoGrammar, ISpRecoResult, ISpRecognizer, ISpStream, SpInprocRecognizer,
+        SpStream, SPEVENT, SPFM_OPEN_READONLY, SPLO_STATIC, SPRS_ACTIVE,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoTaskMemFree, CoUninitialize, CLSCTX_ALL,
+        COINIT_MULTITHREADED,
+    };
+
+    use super::super::error::TranscriptionError;
+
+    /// SAPI event IDs (`SPEVENTENUM`) this recognizer listens for
+    const SPEI_END_SR_STREAM: i32 = 34;
+    const SPEI_RECOGNITION: i32 = 38;
+    const SPEI_FALSE_RECOGNITION: i32 = 43;
+
+    /// `SPET_LPARAM_IS_OBJECT`: the event owns a COM reference in `lParam`
+    const SPET_LPARAM_IS_OBJECT: i32 = 2;
+
+    /// Bits SAPI requires in every interest mask (`SPFEI_FLAGCHECK`)
+    const SPFEI_FLAGCHECK: u64 = (1 << 30) | (1 << 33);
+
+    /// `SP_GETWHOLEPHRASE`: request every element of a phrase
+    const WHOLE_PHRASE: u32 = u32::MAX;
+
+    /// Give up if the recognizer goes quiet for this long without finishing
+    const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+    fn to_error(e: windows::core::Error) -> TranscriptionError {
+        TranscriptionError::TranscriptionError {
+            message: format!("Windows speech recognition failed: {}", e),
+        }
+    }
+
+    pub fn transcribe(wav_data: Vec<u8>) -> Result<String, TranscriptionError> {
+        // SAPI reads the recording through a file-backed stream
+        let mut file = tempfile::Builder::new()
+            .suffix(".wav")
+            .tempfile()
+            .map_err(|e| TranscriptionError::AudioReadError {
+                message: format!("Failed to create temp file: {}", e),
+            })?;
+        file.write_all(&wav_data)
+            .map_err(|e| TranscriptionError::AudioReadError {
+                message: format!("Failed to write audio data: {}", e),
+            })?;
+        let path = HSTRING::from(file.path().as_os_str());
+
+        let initialized = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) }.is_ok();
+        let result = unsafe { recognize(&path) };
+        if initialized {
+            unsafe { CoUninitialize() };
+        }
+        result
+    }
+
+    unsafe fn recognize(path: &HSTRING) -> Result<String, TranscriptionError> {
+        let stream: ISpStream = CoCreateInstance(&SpStream, None, CLSCTX_ALL).map_err(to_error)?;
+        // Read-only streams take their format from the WAV header
+        stream
+            .BindToFile(PCWSTR(path.as_ptr()), SPFM_OPEN_READONLY, None, None, 0)
+            .map_err(to_error)?;
+
+        let recognizer: ISpRecognizer =
+            CoCreateInstance(&SpInprocRecognizer, None, CLSCTX_ALL).map_err(to_error)?;
+        recognizer.SetInput(&stream, false).map_err(to_error)?;
+
+        let context: ISpRecoContext = recognizer.CreateRecoContext().map_err(to_error)?;
+        context.SetNotifyWin32Event().map_err(to_error)?;
+        let interest = SPFEI_FLAGCHECK
+            | (1 << SPEI_END_SR_STREAM)
+            | (1 << SPEI_RECOGNITION)
+            | (1 << SPEI_FALSE_RECOGNITION);
+        context.SetInterest(interest, interest).map_err(to_error)?;
+
+        let grammar: ISpRecoGrammar = context.CreateGrammar(0).map_err(to_error)?;
+        grammar
+            .LoadDictation(PCWSTR::null(), SPLO_STATIC)
+            .map_err(to_error)?;
+        grammar.SetDictationState(SPRS_ACTIVE).map_err(to_error)?;
+
+        let mut phrases = Vec::new();
+        let mut last_event = Instant::now();
+        loop {
+            // Times out with S_FALSE, which is fine; the loop checks for idleness
+            let _ = context.WaitForNotifyEvent(500);
+
+            let mut event = SPEVENT::default();
+            let mut fetched = 0u32;
+            while context.GetEvents(1, &mut event, &mut fetched).is_ok() && fetched == 1 {
+                last_event = Instant::now();
+                let event_id = event._bitfield & 0xFFFF;
+                let param_type = (event._bitfield >> 16) & 0xFFFF;
+
+                // Take ownership of the event's COM object so it is released
+                let result = (param_type == SPET_LPARAM_IS_OBJECT && event.lParam.0 != 0)
+                    .then(|| ISpRecoResult::from_raw(event.lParam.0 as *mut _));
+
+                match event_id {
+                    SPEI_RECOGNITION => {
+                        if let Some(text) = result.as_ref().and_then(|r| phrase_text(r)) {
+                            phrases.push(text);
+                        }
+                    }
+                    SPEI_END_SR_STREAM => return Ok(phrases.join(" ")),
+                    _ => {}
+                }
+            }
+
+            if last_event.elapsed() > IDLE_TIMEOUT {
+                return Err(TranscriptionError::TranscriptionError {
+                    message: "Timed out waiting for Windows speech recognition".to_string(),
+                });
+            }
+        }
+    }
+
+    unsafe fn phrase_text(result: &ISpRecoResult) -> Option<String> {
+        let mut text = PWSTR::null();
+        result
+            .GetText(WHOLE_PHRASE, WHOLE_PHRASE, true, &mut text, None)
+            .ok()?;
+        if text.is_null() {
+            return None;
+        }
+
+        let phrase = text.to_string().ok();
+        CoTaskMemFree(Some(text.0 as *const _));
+        phrase.map(|phrase| phrase.trim().to_string())
+    }
+}
+
+#[cfg(not(windows))]
+mod platform {
+    use super::super::error::TranscriptionError;
+
+    pub fn transcribe(_wav_data: Vec<u8>) -> Result<String, TranscriptionError> {
+        Err(TranscriptionError::TranscriptionError {
+            message: "Windows speech recognition is only available on Windows".to_string(),
+        })
+    }
+}
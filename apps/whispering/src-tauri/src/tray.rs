@@ -0,0 +1,296 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager, Window, WindowEvent};
+
+use crate::recorder::{self, AudioRecording, LevelMeter};
+
+/// Shared with the frontend, which looks the tray up by id to swap its icon
+/// with the recorder state
+pub const TRAY_ID: &str = "whispering-tray";
+
+/// Event emitted when a tray item changes the recorder, so the frontend can
+/// follow along and transcribe what was recorded
+pub const TRAY_RECORDING_EVENT: &str = "tray-recording";
+
+/// Event emitted when the tray asks to toggle voice activated recording,
+/// which runs in the frontend
+pub const TOGGLE_VAD_EVENT: &str = "tray-toggle-vad";
+
+const START_RECORDING: &str = "start-recording";
+const STOP_RECORDING: &str = "stop-recording";
+const CANCEL_RECORDING: &str = "cancel-recording";
+const TOGGLE_VAD: &str = "toggle-vad";
+const OPEN_WINDOW: &str = "open-window";
+const QUIT: &str = "quit";
+
+/// How often the tray title's timer and level meter update; slower than the
+/// overlay, since the menu bar re-lays out on every change
+const METER_TICK: Duration = Duration::from_millis(200);
+const METER_SEGMENTS: usize = 5;
+
+/// Payload for `TRAY_RECORDING_EVENT` - sent to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TrayRecording {
+    Started,
+    Stopped { recording: AudioRecording },
+    Cancelled,
+}
+
+/// Whether closing the main window hides it to the tray instead of quitting
+///
+/// Off until the frontend applies the user's setting. Quitting from the tray
+/// (or Cmd+Q on macOS) always exits.
+pub struct CloseToTray {
+    enabled: AtomicBool,
+}
+
+impl CloseToTray {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for CloseToTray {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The elapsed time and input level shown in the tray title while
+/// recording, so recording over a full-screen app still has feedback
+///
+/// Titles show next to the icon on macOS and as the indicator label on
+/// Linux, and not at all on Windows, so it's on by default only on macOS.
+pub struct TrayMeter {
+    enabled: AtomicBool,
+    /// Stop flag for the thread updating the current title
+    ticker: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+impl TrayMeter {
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(cfg!(target_os = "macos")),
+            ticker: Mutex::new(None),
+        }
+    }
+
+    /// Start updating the tray title from `level`, when enabled
+    pub fn start(&self, app: &AppHandle, level: LevelMeter) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        let Some(tray) = app.tray_by_id(TRAY_ID) else {
+            return;
+        };
+
+        let stop = Arc::new(AtomicBool::new(false));
+        if let Some(previous) = self.ticker.lock().unwrap().replace(stop.clone()) {
+            previous.store(true, Ordering::Relaxed);
+        }
+        std::thread::spawn(move || {
+            let started = Instant::now();
+            while !stop.load(Ordering::Relaxed) {
+                let _ = tray.set_title(Some(meter_title(started.elapsed(), level.current())));
+                std::thread::sleep(METER_TICK);
+            }
+            // Cleared here rather than in `stop`, so a last tick can't put
+            // the title back after it
+            let _ = tray.set_title(None::<&str>);
+        });
+    }
+
+    /// Stop updating the tray title and clear it
+    pub fn stop(&self) {
+        if let Some(stop) = self.ticker.lock().unwrap().take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Default for TrayMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A red dot, the elapsed time and a bar meter, e.g. "● 1:05 ▮▮▮▯▯"
+///
+/// The level is square-rooted so normal speech fills more than a segment.
+fn meter_title(elapsed: Duration, level: f32) -> String {
+    let seconds = elapsed.as_secs();
+    let filled = ((level.sqrt() * METER_SEGMENTS as f32).round() as usize).min(METER_SEGMENTS);
+    format!(
+        "● {}:{:02} {}{}",
+        seconds / 60,
+        seconds % 60,
+        "▮".repeat(filled),
+        "▯".repeat(METER_SEGMENTS - filled)
+    )
+}
+
+/// Create the tray icon and its menu
+///
+/// Menu items drive the recorder commands directly, so recording works with
+/// the main window hidden for good.
+pub fn create(app: &AppHandle) -> tauri::Result<()> {
+    let menu = Menu::with_items(
+        app,
+        &[
+            &MenuItem::with_id(app, START_RECORDING, "Start Recording", true, None::<&str>)?,
+            &MenuItem::with_id(app, STOP_RECORDING, "Stop Recording", true, None::<&str>)?,
+            &MenuItem::with_id(app, CANCEL_RECORDING, "Cancel Recording", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, TOGGLE_VAD, "Toggle Voice Activated", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, OPEN_WINDOW, "Open Window", true, None::<&str>)?,
+            &MenuItem::with_id(app, QUIT, "Quit", true, None::<&str>)?,
+        ],
+    )?;
+
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip("Whispering")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(handle_menu_event);
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+    Ok(())
+}
+
+fn handle_menu_event(app: &AppHandle, event: MenuEvent) {
+    match event.id().as_ref() {
+        START_RECORDING => start_recording(app),
+        STOP_RECORDING => stop_recording(app),
+        CANCEL_RECORDING => cancel_recording(app),
+        TOGGLE_VAD => {
+            let _ = app.emit(TOGGLE_VAD_EVENT, ());
+        }
+        OPEN_WINDOW => show_main_window(app),
+        QUIT => app.exit(0),
+        _ => {}
+    }
+}
+
+/// Hide the main window instead of closing it when close-to-tray is on
+pub fn handle_window_event(window: &Window, event: &WindowEvent) {
+    let WindowEvent::CloseRequested { api, .. } = event else {
+        return;
+    };
+    if window.label() != "main" || !window.state::<CloseToTray>().is_enabled() {
+        return;
+    }
+    api.prevent_close();
+    let _ = window.hide();
+}
+
+/// Show, unminimize and focus the main window
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn start_recording(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match recorder::start_native_recording(&app).await {
+            Ok(()) => {
+                let _ = app.emit(TRAY_RECORDING_EVENT, TrayRecording::Started);
+            }
+            Err(e) => eprintln!("[Tray] Failed to start recording: {}", e),
+        }
+    });
+}
+
+fn stop_recording(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match recorder::stop_recording(None, app.state(), app.clone()).await {
+            Ok(recording) => {
+                let _ = app.emit(TRAY_RECORDING_EVENT, TrayRecording::Stopped { recording });
+            }
+            Err(e) => eprintln!("[Tray] Failed to stop recording: {}", e),
+        }
+    });
+}
+
+fn cancel_recording(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match recorder::cancel_recording(app.state(), app.clone()).await {
+            Ok(()) => {
+                let _ = app.emit(TRAY_RECORDING_EVENT, TrayRecording::Cancelled);
+            }
+            Err(e) => eprintln!("[Tray] Failed to cancel recording: {}", e),
+        }
+    });
+}
+
+/// Get whether closing the main window hides it to the tray
+#[tauri::command]
+pub fn get_close_to_tray(close_to_tray: tauri::State<'_, CloseToTray>) -> bool {
+    close_to_tray.is_enabled()
+}
+
+/// Set whether closing the main window hides it to the tray instead of
+/// quitting
+#[tauri::command]
+pub fn set_close_to_tray(enabled: bool, close_to_tray: tauri::State<'_, CloseToTray>) {
+    close_to_tray.enabled.store(enabled, Ordering::Relaxed);
+}
+
+/// Turn the recording time and level in the tray title on or off
+#[tauri::command]
+pub fn set_tray_recording_meter(enabled: bool, meter: tauri::State<'_, TrayMeter>) {
+    meter.enabled.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        meter.stop();
+    }
+}
+
+/// Hide the Dock icon and app menu so the app lives in the menu bar only, or
+/// bring them back
+///
+/// Open windows stay open; an accessory app is just left out of the Dock and
+/// the app switcher. Does nothing on other platforms, which have no Dock.
+#[tauri::command]
+pub fn set_dock_icon_hidden(hidden: bool, app_handle: AppHandle) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        let policy = if hidden {
+            tauri::ActivationPolicy::Accessory
+        } else {
+            tauri::ActivationPolicy::Regular
+        };
+        app_handle
+            .set_activation_policy(policy)
+            .map_err(|e| format!("Failed to change Dock icon: {}", e))?;
+        // Changing the policy deactivates the app, sending a visible main
+        // window behind others
+        let visible = app_handle
+            .get_webview_window("main")
+            .is_some_and(|window| window.is_visible().unwrap_or(false));
+        if visible {
+            show_main_window(&app_handle);
+        }
+    }
+    #[cfg(not(target_os = "macos"))]
+    let _ = (hidden, app_handle);
+    Ok(())
+}